@@ -1,5 +1,13 @@
+use nldl::attr::PolicyError;
+use nldl::bytes::BufMut;
+use nldl::message::utils::visit_messages;
+use nldl::message::utils::MessageVisitor;
+use nldl::message::utils::VisitMessagesError;
+use nldl::message::utils::VisitMessagesStatus;
+use nldl::message::NetlinkMessageHeader;
 use nldl::message::NetlinkPayloadRequest;
 use nldl::message::NetlinkPayloadResponse;
+use nldl::SerializeError;
 use std::mem::size_of;
 
 pub mod socket;
@@ -12,13 +20,13 @@ pub struct GenericNetlinkHeader {
 }
 
 impl GenericNetlinkHeader {
-    fn serialize(&self, buf: &mut Vec<u8>) {
-        buf.push(self.cmd);
-        buf.push(self.version);
+    fn serialize<B: BufMut>(&self, buf: &mut B) {
+        buf.put_u8(self.cmd);
+        buf.put_u8(self.version);
 
         // Add 2 reserved bytes per genlmsghdr's C definition.
-        buf.push(0);
-        buf.push(0);
+        buf.put_u8(0);
+        buf.put_u8(0);
     }
 
     fn deserialize(buf: [u8; size_of::<libc::genlmsghdr>()]) -> Self {
@@ -35,9 +43,17 @@ pub struct GenericNetlinkRequest<T: NetlinkPayloadRequest> {
 }
 
 impl<T: NetlinkPayloadRequest> NetlinkPayloadRequest for GenericNetlinkRequest<T> {
-    fn serialize(&self, buf: &mut Vec<u8>) {
+    fn serialized_len(&self) -> usize {
+        size_of::<libc::genlmsghdr>() + self.payload.serialized_len()
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
         self.header.serialize(buf);
-        self.payload.serialize(buf);
+        self.payload.serialize(buf)
+    }
+
+    fn validate(&self) -> Result<(), PolicyError> {
+        self.payload.validate()
     }
 }
 
@@ -65,3 +81,74 @@ impl<T: NetlinkPayloadResponse> NetlinkPayloadResponse for GenericNetlinkRespons
         Ok(Self { header, payload })
     }
 }
+
+/// Callbacks for each generic netlink message in a response. The payload
+/// passed to [visit_message](Self::visit_message) is the attribute buffer
+/// following the `genlmsghdr`, and only lives until the callback returns.
+///
+/// Attribute enums deriving `Deserialize` with `#[nldl(visitor)]` generate a
+/// visitor trait whose `visit_attributes` method can be called from here.
+pub trait GenericNetlinkVisitor {
+    type Error;
+
+    fn visit_message(
+        &mut self,
+        header: GenericNetlinkHeader,
+        payload: &[u8],
+    ) -> Result<(), Self::Error>;
+}
+
+impl<F, E> GenericNetlinkVisitor for F
+where
+    F: FnMut(GenericNetlinkHeader, &[u8]) -> Result<(), E>,
+{
+    type Error = E;
+
+    fn visit_message(&mut self, header: GenericNetlinkHeader, payload: &[u8]) -> Result<(), E> {
+        self(header, payload)
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum VisitGenericMessageError<E> {
+    #[error("Found a generic netlink message with {len} payload bytes, which is too short for a genlmsghdr.")]
+    IncompleteHeader { len: usize },
+    #[error("{0}")]
+    Visitor(E),
+}
+
+pub type VisitGenericMessagesError<E> = VisitMessagesError<VisitGenericMessageError<E>>;
+
+struct GenericMessageVisitorAdapter<'v, V: ?Sized>(&'v mut V);
+
+impl<'a, V: GenericNetlinkVisitor + ?Sized> MessageVisitor<'a>
+    for GenericMessageVisitorAdapter<'_, V>
+{
+    type Error = VisitGenericMessageError<V::Error>;
+
+    fn visit_message(
+        &mut self,
+        _header: NetlinkMessageHeader,
+        payload: &'a [u8],
+    ) -> Result<(), Self::Error> {
+        if payload.len() < size_of::<libc::genlmsghdr>() {
+            return Err(VisitGenericMessageError::IncompleteHeader { len: payload.len() });
+        }
+
+        let (header_bytes, payload_bytes) = payload.split_at(size_of::<libc::genlmsghdr>());
+        let header = GenericNetlinkHeader::deserialize(header_bytes.try_into().unwrap());
+
+        self.0
+            .visit_message(header, payload_bytes)
+            .map_err(VisitGenericMessageError::Visitor)
+    }
+}
+
+/// Passes each generic netlink message in a receive buffer to `visitor`. See
+/// [visit_messages] for how control messages are handled.
+pub fn visit_generic_messages<V: GenericNetlinkVisitor + ?Sized>(
+    buf: &[u8],
+    visitor: &mut V,
+) -> Result<VisitMessagesStatus, VisitGenericMessagesError<V::Error>> {
+    visit_messages(buf, &mut GenericMessageVisitorAdapter(visitor))
+}
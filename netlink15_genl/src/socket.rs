@@ -1,5 +1,8 @@
+use super::visit_generic_messages;
 use super::GenericNetlinkRequest;
 use super::GenericNetlinkResponse;
+use super::GenericNetlinkVisitor;
+use super::VisitGenericMessagesError;
 use nix::sys::socket::bind;
 use nix::sys::socket::socket;
 use nix::sys::socket::AddressFamily;
@@ -10,6 +13,7 @@ use nix::sys::socket::SockFlag;
 use nix::sys::socket::SockProtocol;
 use nix::sys::socket::SockType;
 use nldl::message::utils::create_message_iterator;
+use nldl::message::utils::VisitMessagesStatus;
 use nldl::message::DeserializeNetlinkMessageResult;
 use nldl::message::NetlinkErrorMessagePayload;
 use nldl::message::NetlinkMessageHeader;
@@ -19,6 +23,7 @@ use nldl::message::NetlinkMessageResponseDeserializeError;
 use nldl::message::NetlinkMessageType;
 use nldl::message::NetlinkPayloadRequest;
 use nldl::message::NetlinkPayloadResponse;
+use nldl::SerializeError;
 use std::os::unix::io::RawFd;
 
 pub struct GenlSocket {
@@ -47,7 +52,7 @@ impl GenlSocket {
         &self,
         genl_request: GenericNetlinkRequest<T>,
         flags: u16,
-    ) -> nix::Result<()> {
+    ) -> Result<(), SendError> {
         let message = NetlinkMessageRequest {
             header: NetlinkMessageHeader {
                 ty: libc::GENL_ID_CTRL as u16,
@@ -58,7 +63,7 @@ impl GenlSocket {
             payload: NetlinkMessageType::ProtocolMessage(genl_request),
         };
 
-        let message_bytes = nldl::serialize(&message);
+        let message_bytes = nldl::serialize(&message)?;
 
         nix::sys::socket::send(self.fd, &message_bytes, MsgFlags::empty())?;
         Ok(())
@@ -109,6 +114,34 @@ impl GenlSocket {
             }
         }
     }
+
+    /// Passes each message of a multipart response to `visitor` as it's
+    /// received. Unlike [recv_until_done_buffered](Self::recv_until_done_buffered),
+    /// messages aren't collected, and a single receive buffer is reused for the
+    /// whole response. Stops after encountering Error, Done, or Overrun message
+    /// types.
+    pub fn recv_until_done_visit<V: GenericNetlinkVisitor + ?Sized>(
+        &self,
+        visitor: &mut V,
+    ) -> Result<(), RecvUntilDoneVisitError<V::Error>> {
+        let mut resp_bytes = vec![0; 32768];
+
+        loop {
+            let bytes_read = nix::sys::socket::recv(self.fd, &mut resp_bytes, MsgFlags::empty())?;
+            match visit_generic_messages(&resp_bytes[..bytes_read], visitor)? {
+                VisitMessagesStatus::Incomplete => continue,
+                VisitMessagesStatus::Done => return Ok(()),
+            }
+        }
+    }
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum SendError {
+    #[error(transparent)]
+    NixError(#[from] nix::Error),
+    #[error(transparent)]
+    SerializeError(#[from] SerializeError),
 }
 
 #[derive(thiserror::Error, Debug)]
@@ -123,6 +156,14 @@ pub enum RecvUntilDoneError<T: NetlinkPayloadResponse> {
     NetlinkError(NetlinkErrorMessagePayload),
 }
 
+#[derive(thiserror::Error, Debug)]
+pub enum RecvUntilDoneVisitError<E> {
+    #[error(transparent)]
+    NixError(#[from] nix::Error),
+    #[error(transparent)]
+    VisitError(#[from] VisitGenericMessagesError<E>),
+}
+
 /// Some SockProtocol values aren't bound by nix yet.
 fn transmute_to_sock_protocol(value: libc::c_int) -> SockProtocol {
     unsafe { std::mem::transmute::<libc::c_int, SockProtocol>(value) }
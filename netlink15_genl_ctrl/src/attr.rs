@@ -1,14 +1,18 @@
-use nldl::attr::Nested;
+use nldl::attr::NlaArray;
 use nldl::attr::UnknownAttribute;
 
 // https://www.infradead.org/~tgr/libnl/doc/api/ctrl_8c_source.html#l00043
-#[derive(Debug, PartialEq, Eq, nldl::attr::Serialize, nldl::attr::Deserialize)]
+#[derive(
+    Debug, PartialEq, Eq, nldl::attr::Serialize, nldl::attr::Deserialize, nldl::attr::Schema,
+)]
+#[nldl(visitor)]
 pub enum ControllerAttribute {
     #[nla_type(libc::CTRL_ATTR_UNSPEC as u16)]
     Unspec,
     #[nla_type(libc::CTRL_ATTR_FAMILY_ID as u16)]
     FamilyId(u16),
     #[nla_type(libc::CTRL_ATTR_FAMILY_NAME as u16)]
+    #[nla_policy(max_strlen = libc::GENL_NAMSIZ as usize - 1)]
     FamilyName(String),
     #[nla_type(libc::CTRL_ATTR_VERSION as u16)]
     Version(u32),
@@ -17,14 +21,16 @@ pub enum ControllerAttribute {
     #[nla_type(libc::CTRL_ATTR_MAXATTR as u16)]
     MaxAttr(u32),
     #[nla_type(libc::CTRL_ATTR_OPS as u16)]
-    Operations(Vec<Nested<ControllerAttributeOperation>>),
+    Operations(NlaArray<Vec<ControllerAttributeOperation>>),
     #[nla_type(libc::CTRL_ATTR_MCAST_GROUPS as u16)]
-    MulticastGroups(Vec<Nested<ControllerAttributeMulticastGroup>>),
+    MulticastGroups(NlaArray<Vec<ControllerAttributeMulticastGroup>>),
     #[nla_type(_)]
     Unknown(UnknownAttribute),
 }
 
-#[derive(Debug, PartialEq, Eq, nldl::attr::Serialize, nldl::attr::Deserialize)]
+#[derive(
+    Debug, PartialEq, Eq, nldl::attr::Serialize, nldl::attr::Deserialize, nldl::attr::Schema,
+)]
 pub enum ControllerAttributeOperation {
     #[nla_type(libc::CTRL_ATTR_OP_UNSPEC as u16)]
     Unspec,
@@ -36,7 +42,9 @@ pub enum ControllerAttributeOperation {
     Unknown(UnknownAttribute),
 }
 
-#[derive(Debug, PartialEq, Eq, nldl::attr::Serialize, nldl::attr::Deserialize)]
+#[derive(
+    Debug, PartialEq, Eq, nldl::attr::Serialize, nldl::attr::Deserialize, nldl::attr::Schema,
+)]
 pub enum ControllerAttributeMulticastGroup {
     #[nla_type(libc::CTRL_ATTR_MCAST_GRP_UNSPEC as u16)]
     Unspec,
@@ -1,6 +1,10 @@
 use crate::attr::ControllerAttribute;
+use crate::family::Family;
 use netlink15_genl::socket::RecvUntilDoneError;
+use netlink15_genl::socket::SendError;
 use netlink15_genl::GenericNetlinkResponse;
+use nldl::attr::Lenient;
+use nldl::attr::PolicyError;
 use nldl::message::NetlinkMessageResponseDeserializeError;
 
 #[derive(thiserror::Error, Debug)]
@@ -8,12 +12,20 @@ pub enum GenlCtrlCommandError {
     #[error(transparent)]
     NixError(#[from] nix::Error),
     #[error(transparent)]
+    SendError(#[from] SendError),
+    #[error(transparent)]
+    PolicyError(#[from] PolicyError),
+    #[error(transparent)]
     DeserializeError(
         #[from]
         NetlinkMessageResponseDeserializeError<GenericNetlinkResponse<Vec<ControllerAttribute>>>,
     ),
     #[error(transparent)]
     RecvUntilDoneError(#[from] RecvUntilDoneError<Vec<ControllerAttribute>>),
+    #[error(transparent)]
+    RecvFamiliesError(#[from] RecvUntilDoneError<Family>),
+    #[error(transparent)]
+    RecvLenientError(#[from] RecvUntilDoneError<Lenient<ControllerAttribute>>),
     #[error("Recieved an unexpected NLMSG type: {0}")]
     UnexpectedMessageType(u16),
 }
@@ -1,150 +1,182 @@
-use crate::attr::ControllerAttribute;
-use crate::attr::ControllerAttributeMulticastGroup;
-use crate::attr::ControllerAttributeOperation;
+use bitflags::bitflags;
+use nldl::message::Flags;
 
-#[derive(Debug, PartialEq, Eq)]
+// https://www.infradead.org/~tgr/libnl/doc/api/ctrl_8c_source.html#l00043
+#[derive(Debug, PartialEq, Eq, nldl::attr::Deserialize)]
 pub struct Family {
+    #[nla_type(libc::CTRL_ATTR_FAMILY_ID as u16)]
     pub family_id: u16,
+    #[nla_type(libc::CTRL_ATTR_FAMILY_NAME as u16)]
     pub family_name: String,
+    #[nla_type(libc::CTRL_ATTR_VERSION as u16)]
     pub version: u32,
+    #[nla_type(libc::CTRL_ATTR_HDRSIZE as u16)]
     pub header_size: u32,
+    #[nla_type(libc::CTRL_ATTR_MAXATTR as u16)]
     pub max_attr: u32,
+    #[nla_type(libc::CTRL_ATTR_OPS as u16, default)]
     pub operations: Vec<FamilyOperation>,
+    #[nla_type(libc::CTRL_ATTR_MCAST_GROUPS as u16, default)]
     pub multicast_groups: Vec<FamilyMulticastGroup>,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Debug, PartialEq, Eq, nldl::attr::Deserialize)]
 pub struct FamilyOperation {
+    #[nla_type(libc::CTRL_ATTR_OP_ID as u16)]
     pub id: u32,
-    pub flags: u32,
+    #[nla_type(libc::CTRL_ATTR_OP_FLAGS as u16)]
+    pub flags: Flags<OperationFlags>,
 }
 
-#[derive(Debug, PartialEq, Eq)]
+bitflags! {
+    /// The `GENL_*` flags describing a generic netlink operation.
+    #[derive(Clone, Copy, Debug, PartialEq, Eq, Hash)]
+    pub struct OperationFlags: u32 {
+        const ADMIN_PERM = libc::GENL_ADMIN_PERM as u32;
+        const CMD_CAP_DO = libc::GENL_CMD_CAP_DO as u32;
+        const CMD_CAP_DUMP = libc::GENL_CMD_CAP_DUMP as u32;
+        const CMD_CAP_HASPOL = libc::GENL_CMD_CAP_HASPOL as u32;
+        // Not yet exported by libc.
+        const UNS_ADMIN_PERM = 0x10;
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, nldl::attr::Deserialize)]
 pub struct FamilyMulticastGroup {
+    #[nla_type(libc::CTRL_ATTR_MCAST_GRP_NAME as u16)]
     pub name: String,
+    #[nla_type(libc::CTRL_ATTR_MCAST_GRP_ID as u16)]
     pub id: u32,
 }
 
-#[derive(thiserror::Error, Debug, Clone)]
-pub enum MissingFamilyAttributeError {
-    #[error("Missing family attribute")]
-    MissingFamilyAttribute,
-    #[error(transparent)]
-    MissingOperationAttribute(#[from] MissingFamilyOperationAttributeError),
-    #[error(transparent)]
-    MissingMulticastGroupAttribute(#[from] MissingFamilyMulticastGroupError),
-}
-
-#[derive(Debug, Clone)]
-pub struct MissingFamilyOperationAttributeError;
-
-#[derive(Debug, Clone)]
-pub struct MissingFamilyMulticastGroupError;
-
-impl std::error::Error for MissingFamilyOperationAttributeError {}
-
-impl std::error::Error for MissingFamilyMulticastGroupError {}
-
-impl std::fmt::Display for MissingFamilyOperationAttributeError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "Missing family operation attribute")
+#[allow(deprecated)]
+pub use compat::MissingFamilyAttributeError;
+
+// Kept for callers of the conversion that predates deriving Deserialize for
+// Family. The derived impls reference the deprecated error, hence the module.
+#[allow(deprecated)]
+mod compat {
+    use super::Family;
+    use crate::attr::ControllerAttribute;
+    use nldl::attr::MissingAttributeError;
+    use nldl::attr::ParseNetlinkAttributeFromBufferError;
+    use nldl::message::NetlinkPayloadRequest;
+    use nldl::message::NetlinkPayloadResponse;
+    use nldl::SerializeError;
+
+    /// Returned by the deprecated `TryFrom<Vec<ControllerAttribute>>` conversion
+    /// to [Family].
+    #[deprecated(note = "Deserialize a Family directly, e.g. with list_families()")]
+    #[derive(thiserror::Error, Debug)]
+    pub enum MissingFamilyAttributeError {
+        #[error("Missing family attribute")]
+        MissingFamilyAttribute,
+        #[error("Missing family operation attribute")]
+        MissingOperationAttribute,
+        #[error("Missing family multicast group attribute")]
+        MissingMulticastGroupAttribute,
+        #[error(transparent)]
+        Serialize(#[from] SerializeError),
+        #[error(transparent)]
+        Deserialize(ParseNetlinkAttributeFromBufferError),
     }
-}
 
-impl std::fmt::Display for MissingFamilyMulticastGroupError {
-    fn fmt(&self, f: &mut std::fmt::Formatter) -> std::fmt::Result {
-        write!(f, "Missing family multicast group attribute")
-    }
-}
-
-impl TryFrom<Vec<ControllerAttribute>> for Family {
-    type Error = MissingFamilyAttributeError;
-
-    fn try_from(attrs: Vec<ControllerAttribute>) -> Result<Self, Self::Error> {
-        let mut family_id = None;
-        let mut family_name = None;
-        let mut version = None;
-        let mut header_size = None;
-        let mut max_attr = None;
-        let mut operations = None;
-        let mut multicast_groups = None;
-
-        for attr in attrs {
-            match attr {
-                ControllerAttribute::Unspec => {}
-                ControllerAttribute::FamilyId(val) => family_id = Some(val),
-                ControllerAttribute::FamilyName(val) => family_name = Some(val),
-                ControllerAttribute::Version(val) => version = Some(val),
-                ControllerAttribute::HeaderSize(val) => header_size = Some(val),
-                ControllerAttribute::MaxAttr(val) => max_attr = Some(val),
-                ControllerAttribute::Operations(val) => operations = Some(val),
-                ControllerAttribute::MulticastGroups(val) => multicast_groups = Some(val),
-                ControllerAttribute::Unknown(_) => {}
-            }
+    /// Deprecated. Re-encodes the attributes and decodes them with the derived
+    /// [NetlinkPayloadResponse] implementation, which should be used directly.
+    impl TryFrom<Vec<ControllerAttribute>> for Family {
+        type Error = MissingFamilyAttributeError;
+
+        fn try_from(attrs: Vec<ControllerAttribute>) -> Result<Self, Self::Error> {
+            let mut buf = vec![];
+            attrs.serialize(&mut buf)?;
+
+            Self::deserialize(&buf).map_err(|err| match missing_struct_name(&err) {
+                Some("FamilyOperation") => Self::Error::MissingOperationAttribute,
+                Some("FamilyMulticastGroup") => Self::Error::MissingMulticastGroupAttribute,
+                Some(_) => Self::Error::MissingFamilyAttribute,
+                None => Self::Error::Deserialize(err),
+            })
         }
-
-        Ok(Self {
-            family_id: family_id.ok_or(Self::Error::MissingFamilyAttribute)?,
-            family_name: family_name.ok_or(Self::Error::MissingFamilyAttribute)?,
-            version: version.ok_or(Self::Error::MissingFamilyAttribute)?,
-            header_size: header_size.ok_or(Self::Error::MissingFamilyAttribute)?,
-            max_attr: max_attr.ok_or(Self::Error::MissingFamilyAttribute)?,
-            operations: operations
-                .unwrap_or_default()
-                .into_iter()
-                .map(|nested| nested.0.try_into())
-                .collect::<Result<Vec<FamilyOperation>, _>>()?,
-            multicast_groups: multicast_groups
-                .unwrap_or_default()
-                .into_iter()
-                .map(|nested| nested.0.try_into())
-                .collect::<Result<Vec<FamilyMulticastGroup>, _>>()?,
-        })
     }
-}
-
-impl TryFrom<Vec<ControllerAttributeOperation>> for FamilyOperation {
-    type Error = MissingFamilyOperationAttributeError;
 
-    fn try_from(attrs: Vec<ControllerAttributeOperation>) -> Result<Self, Self::Error> {
-        let mut id = None;
-        let mut flags = None;
+    /// The struct missing a required attribute, which may be nested in a
+    /// chain of attribute errors.
+    fn missing_struct_name(err: &ParseNetlinkAttributeFromBufferError) -> Option<&'static str> {
+        let mut current: Option<&(dyn std::error::Error + 'static)> = Some(err);
 
-        for attr in attrs {
-            match attr {
-                ControllerAttributeOperation::Unspec => {}
-                ControllerAttributeOperation::Id(val) => id = Some(val),
-                ControllerAttributeOperation::Flags(val) => flags = Some(val),
-                ControllerAttributeOperation::Unknown(_) => {}
+        while let Some(err) = current {
+            if let Some(ParseNetlinkAttributeFromBufferError::MissingAttribute(err)) =
+                err.downcast_ref()
+            {
+                return Some(err.attribute_struct_name);
+            }
+            if let Some(err) = err.downcast_ref::<MissingAttributeError>() {
+                return Some(err.attribute_struct_name);
             }
+            current = err.source();
         }
 
-        Ok(Self {
-            id: id.ok_or(Self::Error {})?,
-            flags: flags.ok_or(Self::Error {})?,
-        })
+        None
     }
 }
 
-impl TryFrom<Vec<ControllerAttributeMulticastGroup>> for FamilyMulticastGroup {
-    type Error = MissingFamilyMulticastGroupError;
-
-    fn try_from(attrs: Vec<ControllerAttributeMulticastGroup>) -> Result<Self, Self::Error> {
-        let mut name = None;
-        let mut id = None;
-
-        for attr in attrs {
-            match attr {
-                ControllerAttributeMulticastGroup::Unspec => {}
-                ControllerAttributeMulticastGroup::Name(val) => name = Some(val),
-                ControllerAttributeMulticastGroup::Id(val) => id = Some(val),
-                ControllerAttributeMulticastGroup::Unknown(_) => {}
-            }
-        }
+#[cfg(test)]
+mod tests {
+    #![allow(deprecated)]
+
+    use super::Family;
+    use super::FamilyOperation;
+    use super::MissingFamilyAttributeError;
+    use crate::attr::ControllerAttribute;
+    use crate::attr::ControllerAttributeOperation;
+    use nldl::attr::ParseNetlinkAttributeFromBufferError;
+    use nldl::message::NetlinkPayloadResponse;
+
+    #[test]
+    fn missing_required_attribute() {
+        // Only CTRL_ATTR_OP_ID is present.
+        let recv_bytes = [0x08, 0x00, 0x01, 0x00, 0x03, 0x00, 0x00, 0x00];
+
+        let err = FamilyOperation::deserialize(&recv_bytes).unwrap_err();
+
+        assert!(matches!(
+            err,
+            ParseNetlinkAttributeFromBufferError::MissingAttribute(err)
+                if err.attribute_struct_name == "FamilyOperation"
+                    && err.field_name == "flags"
+                    && err.nla_type_id == libc::CTRL_ATTR_OP_FLAGS as u16
+        ));
+    }
 
-        Ok(Self {
-            name: name.ok_or(Self::Error {})?,
-            id: id.ok_or(Self::Error {})?,
-        })
+    #[test]
+    fn try_from_attributes() {
+        let attrs = vec![
+            ControllerAttribute::FamilyId(16),
+            ControllerAttribute::FamilyName("nlctrl".to_string()),
+            ControllerAttribute::Version(2),
+            ControllerAttribute::HeaderSize(0),
+            ControllerAttribute::MaxAttr(10),
+            ControllerAttribute::Operations(vec![vec![ControllerAttributeOperation::Id(3)]].into()),
+        ];
+        assert!(matches!(
+            Family::try_from(attrs),
+            Err(MissingFamilyAttributeError::MissingOperationAttribute)
+        ));
+
+        let family = Family::try_from(vec![
+            ControllerAttribute::FamilyId(16),
+            ControllerAttribute::FamilyName("nlctrl".to_string()),
+            ControllerAttribute::Version(2),
+            ControllerAttribute::HeaderSize(0),
+            ControllerAttribute::MaxAttr(10),
+        ])
+        .unwrap();
+        assert_eq!(family.family_name, "nlctrl");
+        assert_eq!(family.operations, vec![]);
+
+        assert!(matches!(
+            Family::try_from(vec![ControllerAttribute::FamilyId(16)]),
+            Err(MissingFamilyAttributeError::MissingFamilyAttribute)
+        ));
     }
 }
@@ -4,6 +4,7 @@ use netlink15_genl::socket::GenlSocket;
 use netlink15_genl::GenericNetlinkHeader;
 use netlink15_genl::GenericNetlinkRequest;
 use nldl::message::NetlinkMessageType;
+use nldl::message::NetlinkPayloadRequest;
 
 pub type GetFamilyResult = Result<Vec<ControllerAttribute>, GenlCtrlCommandError>;
 
@@ -17,6 +18,7 @@ pub fn get_family(sock: &GenlSocket, family_name: String) -> GetFamilyResult {
     };
     let flags = (libc::NLM_F_REQUEST | libc::NLM_F_ACK) as u16;
 
+    genl_request.validate()?;
     sock.send(genl_request, flags)?;
     let resp = sock.recv::<Vec<ControllerAttribute>>()??;
 
@@ -33,7 +35,8 @@ mod tests {
     use netlink15_genl::GenericNetlinkHeader;
     use netlink15_genl::GenericNetlinkRequest;
     use netlink15_genl::GenericNetlinkResponse;
-    use nldl::attr::Nested;
+    use nldl::attr::Indexed;
+    use nldl::attr::NlaArray;
     use nldl::message::NetlinkPayloadRequest;
     use nldl::message::NetlinkPayloadResponse;
 
@@ -50,13 +53,15 @@ mod tests {
             };
 
             let mut buf = vec![];
-            genl_request.serialize(&mut buf);
+            genl_request.serialize(&mut buf).unwrap();
             buf
         };
 
+        // The attribute is padded to a 4 byte boundary, but the padding isn't
+        // included in its length.
         let expected = [
             0x03, 0x00, 0x00, 0x00, 0x0f, 0x00, 0x02, 0x00, 0x61, 0x63, 0x70, 0x69, 0x5f, 0x65,
-            0x76, 0x65, 0x6e, 0x74, 0x00,
+            0x76, 0x65, 0x6e, 0x74, 0x00, 0x00,
         ];
 
         assert_eq!(actual, expected);
@@ -85,10 +90,13 @@ mod tests {
                 ControllerAttribute::Version(1),
                 ControllerAttribute::HeaderSize(0),
                 ControllerAttribute::MaxAttr(1),
-                ControllerAttribute::MulticastGroups(vec![Nested(vec![
-                    ControllerAttributeMulticastGroup::Id(3),
-                    ControllerAttributeMulticastGroup::Name("acpi_mc_group".to_string()),
-                ])]),
+                ControllerAttribute::MulticastGroups(NlaArray(vec![Indexed {
+                    index: 1,
+                    value: vec![
+                        ControllerAttributeMulticastGroup::Id(3),
+                        ControllerAttributeMulticastGroup::Name("acpi_mc_group".to_string()),
+                    ],
+                }])),
             ],
         };
 
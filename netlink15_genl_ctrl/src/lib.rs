@@ -1,4 +1,6 @@
 use get_family::GetFamilyResult;
+use list::ListFamiliesResult;
+use list::ListLenientResult;
 use list::ListResult;
 use netlink15_genl::socket::GenlSocket;
 
@@ -11,6 +13,12 @@ mod list;
 pub trait NetlinkGenericController {
     fn get_family(&self, family_name: String) -> GetFamilyResult;
     fn list(&self) -> ListResult;
+    /// Like [list](Self::list), but decodes each family into a [Family](family::Family).
+    fn list_families(&self) -> ListFamiliesResult;
+    /// Like [list](Self::list), but a malformed attribute is kept as
+    /// [Unknown](attr::ControllerAttribute::Unknown) with a diagnostic instead
+    /// of failing the whole dump. See [Lenient](nldl::attr::Lenient).
+    fn list_lenient(&self) -> ListLenientResult;
 }
 
 impl NetlinkGenericController for GenlSocket {
@@ -21,4 +29,12 @@ impl NetlinkGenericController for GenlSocket {
     fn list(&self) -> ListResult {
         list::list(self)
     }
+
+    fn list_families(&self) -> ListFamiliesResult {
+        list::list_families(self)
+    }
+
+    fn list_lenient(&self) -> ListLenientResult {
+        list::list_lenient(self)
+    }
 }
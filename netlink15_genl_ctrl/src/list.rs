@@ -1,12 +1,16 @@
 use crate::attr::ControllerAttribute;
 use crate::err::GenlCtrlCommandError;
+use crate::family::Family;
 use netlink15_genl::socket::GenlSocket;
 use netlink15_genl::GenericNetlinkHeader;
 use netlink15_genl::GenericNetlinkRequest;
+use nldl::attr::Lenient;
 
 pub type ListResult = Result<Vec<Vec<ControllerAttribute>>, GenlCtrlCommandError>;
+pub type ListFamiliesResult = Result<Vec<Family>, GenlCtrlCommandError>;
+pub type ListLenientResult = Result<Vec<Lenient<ControllerAttribute>>, GenlCtrlCommandError>;
 
-pub fn list(sock: &GenlSocket) -> ListResult {
+fn send_list_request(sock: &GenlSocket) -> Result<(), GenlCtrlCommandError> {
     let genl_request = GenericNetlinkRequest {
         header: GenericNetlinkHeader {
             cmd: libc::CTRL_CMD_GETFAMILY as u8,
@@ -17,20 +21,43 @@ pub fn list(sock: &GenlSocket) -> ListResult {
     let flags = (libc::NLM_F_REQUEST | libc::NLM_F_DUMP) as u16;
 
     sock.send(genl_request, flags)?;
+    Ok(())
+}
+
+pub fn list(sock: &GenlSocket) -> ListResult {
+    send_list_request(sock)?;
     let resp = sock.recv_until_done_buffered::<Vec<ControllerAttribute>>()?;
 
     // TODO: There's a bit of unnecessary copying here.
     Ok(resp.into_iter().map(|message| message.payload).collect())
 }
 
+pub fn list_families(sock: &GenlSocket) -> ListFamiliesResult {
+    send_list_request(sock)?;
+    let resp = sock.recv_until_done_buffered::<Family>()?;
+
+    Ok(resp.into_iter().map(|message| message.payload).collect())
+}
+
+pub fn list_lenient(sock: &GenlSocket) -> ListLenientResult {
+    send_list_request(sock)?;
+    let resp = sock.recv_until_done_buffered::<Lenient<ControllerAttribute>>()?;
+
+    Ok(resp.into_iter().map(|message| message.payload).collect())
+}
+
 #[cfg(test)]
 mod tests {
-    use crate::attr::ControllerAttribute;
+    use crate::attr::ControllerAttributeVisitor;
     use crate::family::Family;
     use crate::family::FamilyMulticastGroup;
     use crate::family::FamilyOperation;
+    use crate::family::OperationFlags;
+    use netlink15_genl::visit_generic_messages;
     use netlink15_genl::GenericNetlinkResponse;
     use nldl::message::utils::create_message_iterator;
+    use nldl::message::utils::VisitMessagesStatus;
+    use nldl::message::Flags;
     use nldl::message::NetlinkMessageType;
 
     fn expect_protocol_message<T>(message_type: NetlinkMessageType<T>) -> T {
@@ -40,6 +67,14 @@ mod tests {
         }
     }
 
+    struct FamilyNames(Vec<String>);
+
+    impl<'a> ControllerAttributeVisitor<'a> for FamilyNames {
+        fn visit_family_name(&mut self, value: String) {
+            self.0.push(value);
+        }
+    }
+
     #[test]
     fn test_response_deserialization() -> anyhow::Result<()> {
         // This buffer was received from running "genl ctrl list" on Ubuntu 20.10
@@ -307,8 +342,14 @@ mod tests {
                 header_size: 0,
                 max_attr: 8,
                 operations: vec![
-                    FamilyOperation { id: 3, flags: 14 },
-                    FamilyOperation { id: 10, flags: 12 },
+                    FamilyOperation {
+                        id: 3,
+                        flags: Flags(OperationFlags::from_bits_retain(14)),
+                    },
+                    FamilyOperation {
+                        id: 10,
+                        flags: Flags(OperationFlags::from_bits_retain(12)),
+                    },
                 ],
                 multicast_groups: vec![FamilyMulticastGroup {
                     name: "notify".to_string(),
@@ -334,51 +375,186 @@ mod tests {
                 header_size: 0,
                 max_attr: 144,
                 operations: vec![
-                    FamilyOperation { id: 1, flags: 14 },
-                    FamilyOperation { id: 5, flags: 14 },
-                    FamilyOperation { id: 6, flags: 11 },
-                    FamilyOperation { id: 9, flags: 11 },
-                    FamilyOperation { id: 10, flags: 11 },
-                    FamilyOperation { id: 11, flags: 14 },
-                    FamilyOperation { id: 15, flags: 14 },
-                    FamilyOperation { id: 16, flags: 11 },
-                    FamilyOperation { id: 19, flags: 14 },
-                    FamilyOperation { id: 20, flags: 11 },
-                    FamilyOperation { id: 23, flags: 14 },
-                    FamilyOperation { id: 24, flags: 11 },
-                    FamilyOperation { id: 27, flags: 11 },
-                    FamilyOperation { id: 28, flags: 11 },
-                    FamilyOperation { id: 29, flags: 11 },
-                    FamilyOperation { id: 30, flags: 11 },
-                    FamilyOperation { id: 31, flags: 10 },
-                    FamilyOperation { id: 32, flags: 10 },
-                    FamilyOperation { id: 33, flags: 10 },
-                    FamilyOperation { id: 34, flags: 11 },
-                    FamilyOperation { id: 35, flags: 11 },
-                    FamilyOperation { id: 36, flags: 10 },
-                    FamilyOperation { id: 37, flags: 11 },
-                    FamilyOperation { id: 38, flags: 14 },
-                    FamilyOperation { id: 39, flags: 11 },
-                    FamilyOperation { id: 47, flags: 14 },
-                    FamilyOperation { id: 48, flags: 11 },
-                    FamilyOperation { id: 42, flags: 15 },
-                    FamilyOperation { id: 44, flags: 11 },
-                    FamilyOperation { id: 45, flags: 11 },
-                    FamilyOperation { id: 46, flags: 13 },
-                    FamilyOperation { id: 51, flags: 14 },
-                    FamilyOperation { id: 52, flags: 14 },
-                    FamilyOperation { id: 53, flags: 11 },
-                    FamilyOperation { id: 54, flags: 11 },
-                    FamilyOperation { id: 55, flags: 11 },
-                    FamilyOperation { id: 56, flags: 13 },
-                    FamilyOperation { id: 57, flags: 11 },
-                    FamilyOperation { id: 58, flags: 11 },
-                    FamilyOperation { id: 61, flags: 14 },
-                    FamilyOperation { id: 62, flags: 11 },
-                    FamilyOperation { id: 65, flags: 14 },
-                    FamilyOperation { id: 66, flags: 11 },
-                    FamilyOperation { id: 69, flags: 14 },
-                    FamilyOperation { id: 70, flags: 11 },
+                    FamilyOperation {
+                        id: 1,
+                        flags: Flags(OperationFlags::from_bits_retain(14)),
+                    },
+                    FamilyOperation {
+                        id: 5,
+                        flags: Flags(OperationFlags::from_bits_retain(14)),
+                    },
+                    FamilyOperation {
+                        id: 6,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 9,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 10,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 11,
+                        flags: Flags(OperationFlags::from_bits_retain(14)),
+                    },
+                    FamilyOperation {
+                        id: 15,
+                        flags: Flags(OperationFlags::from_bits_retain(14)),
+                    },
+                    FamilyOperation {
+                        id: 16,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 19,
+                        flags: Flags(OperationFlags::from_bits_retain(14)),
+                    },
+                    FamilyOperation {
+                        id: 20,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 23,
+                        flags: Flags(OperationFlags::from_bits_retain(14)),
+                    },
+                    FamilyOperation {
+                        id: 24,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 27,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 28,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 29,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 30,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 31,
+                        flags: Flags(OperationFlags::from_bits_retain(10)),
+                    },
+                    FamilyOperation {
+                        id: 32,
+                        flags: Flags(OperationFlags::from_bits_retain(10)),
+                    },
+                    FamilyOperation {
+                        id: 33,
+                        flags: Flags(OperationFlags::from_bits_retain(10)),
+                    },
+                    FamilyOperation {
+                        id: 34,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 35,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 36,
+                        flags: Flags(OperationFlags::from_bits_retain(10)),
+                    },
+                    FamilyOperation {
+                        id: 37,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 38,
+                        flags: Flags(OperationFlags::from_bits_retain(14)),
+                    },
+                    FamilyOperation {
+                        id: 39,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 47,
+                        flags: Flags(OperationFlags::from_bits_retain(14)),
+                    },
+                    FamilyOperation {
+                        id: 48,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 42,
+                        flags: Flags(OperationFlags::from_bits_retain(15)),
+                    },
+                    FamilyOperation {
+                        id: 44,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 45,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 46,
+                        flags: Flags(OperationFlags::from_bits_retain(13)),
+                    },
+                    FamilyOperation {
+                        id: 51,
+                        flags: Flags(OperationFlags::from_bits_retain(14)),
+                    },
+                    FamilyOperation {
+                        id: 52,
+                        flags: Flags(OperationFlags::from_bits_retain(14)),
+                    },
+                    FamilyOperation {
+                        id: 53,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 54,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 55,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 56,
+                        flags: Flags(OperationFlags::from_bits_retain(13)),
+                    },
+                    FamilyOperation {
+                        id: 57,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 58,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 61,
+                        flags: Flags(OperationFlags::from_bits_retain(14)),
+                    },
+                    FamilyOperation {
+                        id: 62,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 65,
+                        flags: Flags(OperationFlags::from_bits_retain(14)),
+                    },
+                    FamilyOperation {
+                        id: 66,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 69,
+                        flags: Flags(OperationFlags::from_bits_retain(14)),
+                    },
+                    FamilyOperation {
+                        id: 70,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
                 ],
                 multicast_groups: vec![FamilyMulticastGroup {
                     name: "config".to_string(),
@@ -392,33 +568,114 @@ mod tests {
                 header_size: 0,
                 max_attr: 0,
                 operations: vec![
-                    FamilyOperation { id: 1, flags: 6 },
-                    FamilyOperation { id: 2, flags: 6 },
-                    FamilyOperation { id: 3, flags: 18 },
-                    FamilyOperation { id: 4, flags: 6 },
-                    FamilyOperation { id: 5, flags: 18 },
-                    FamilyOperation { id: 6, flags: 6 },
-                    FamilyOperation { id: 7, flags: 6 },
-                    FamilyOperation { id: 8, flags: 18 },
-                    FamilyOperation { id: 9, flags: 22 },
-                    FamilyOperation { id: 10, flags: 18 },
-                    FamilyOperation { id: 11, flags: 6 },
-                    FamilyOperation { id: 12, flags: 18 },
-                    FamilyOperation { id: 13, flags: 6 },
-                    FamilyOperation { id: 14, flags: 18 },
-                    FamilyOperation { id: 15, flags: 6 },
-                    FamilyOperation { id: 16, flags: 18 },
-                    FamilyOperation { id: 17, flags: 6 },
-                    FamilyOperation { id: 18, flags: 18 },
-                    FamilyOperation { id: 19, flags: 6 },
-                    FamilyOperation { id: 20, flags: 18 },
-                    FamilyOperation { id: 21, flags: 6 },
-                    FamilyOperation { id: 22, flags: 18 },
-                    FamilyOperation { id: 23, flags: 6 },
-                    FamilyOperation { id: 24, flags: 18 },
-                    FamilyOperation { id: 25, flags: 6 },
-                    FamilyOperation { id: 26, flags: 18 },
-                    FamilyOperation { id: 27, flags: 18 },
+                    FamilyOperation {
+                        id: 1,
+                        flags: Flags(OperationFlags::from_bits_retain(6)),
+                    },
+                    FamilyOperation {
+                        id: 2,
+                        flags: Flags(OperationFlags::from_bits_retain(6)),
+                    },
+                    FamilyOperation {
+                        id: 3,
+                        flags: Flags(OperationFlags::from_bits_retain(18)),
+                    },
+                    FamilyOperation {
+                        id: 4,
+                        flags: Flags(OperationFlags::from_bits_retain(6)),
+                    },
+                    FamilyOperation {
+                        id: 5,
+                        flags: Flags(OperationFlags::from_bits_retain(18)),
+                    },
+                    FamilyOperation {
+                        id: 6,
+                        flags: Flags(OperationFlags::from_bits_retain(6)),
+                    },
+                    FamilyOperation {
+                        id: 7,
+                        flags: Flags(OperationFlags::from_bits_retain(6)),
+                    },
+                    FamilyOperation {
+                        id: 8,
+                        flags: Flags(OperationFlags::from_bits_retain(18)),
+                    },
+                    FamilyOperation {
+                        id: 9,
+                        flags: Flags(OperationFlags::from_bits_retain(22)),
+                    },
+                    FamilyOperation {
+                        id: 10,
+                        flags: Flags(OperationFlags::from_bits_retain(18)),
+                    },
+                    FamilyOperation {
+                        id: 11,
+                        flags: Flags(OperationFlags::from_bits_retain(6)),
+                    },
+                    FamilyOperation {
+                        id: 12,
+                        flags: Flags(OperationFlags::from_bits_retain(18)),
+                    },
+                    FamilyOperation {
+                        id: 13,
+                        flags: Flags(OperationFlags::from_bits_retain(6)),
+                    },
+                    FamilyOperation {
+                        id: 14,
+                        flags: Flags(OperationFlags::from_bits_retain(18)),
+                    },
+                    FamilyOperation {
+                        id: 15,
+                        flags: Flags(OperationFlags::from_bits_retain(6)),
+                    },
+                    FamilyOperation {
+                        id: 16,
+                        flags: Flags(OperationFlags::from_bits_retain(18)),
+                    },
+                    FamilyOperation {
+                        id: 17,
+                        flags: Flags(OperationFlags::from_bits_retain(6)),
+                    },
+                    FamilyOperation {
+                        id: 18,
+                        flags: Flags(OperationFlags::from_bits_retain(18)),
+                    },
+                    FamilyOperation {
+                        id: 19,
+                        flags: Flags(OperationFlags::from_bits_retain(6)),
+                    },
+                    FamilyOperation {
+                        id: 20,
+                        flags: Flags(OperationFlags::from_bits_retain(18)),
+                    },
+                    FamilyOperation {
+                        id: 21,
+                        flags: Flags(OperationFlags::from_bits_retain(6)),
+                    },
+                    FamilyOperation {
+                        id: 22,
+                        flags: Flags(OperationFlags::from_bits_retain(18)),
+                    },
+                    FamilyOperation {
+                        id: 23,
+                        flags: Flags(OperationFlags::from_bits_retain(6)),
+                    },
+                    FamilyOperation {
+                        id: 24,
+                        flags: Flags(OperationFlags::from_bits_retain(18)),
+                    },
+                    FamilyOperation {
+                        id: 25,
+                        flags: Flags(OperationFlags::from_bits_retain(6)),
+                    },
+                    FamilyOperation {
+                        id: 26,
+                        flags: Flags(OperationFlags::from_bits_retain(18)),
+                    },
+                    FamilyOperation {
+                        id: 27,
+                        flags: Flags(OperationFlags::from_bits_retain(18)),
+                    },
                 ],
                 multicast_groups: vec![FamilyMulticastGroup {
                     name: "monitor".to_string(),
@@ -432,14 +689,38 @@ mod tests {
                 header_size: 0,
                 max_attr: 12,
                 operations: vec![
-                    FamilyOperation { id: 1, flags: 11 },
-                    FamilyOperation { id: 2, flags: 11 },
-                    FamilyOperation { id: 3, flags: 12 },
-                    FamilyOperation { id: 4, flags: 11 },
-                    FamilyOperation { id: 5, flags: 11 },
-                    FamilyOperation { id: 6, flags: 10 },
-                    FamilyOperation { id: 7, flags: 12 },
-                    FamilyOperation { id: 8, flags: 10 },
+                    FamilyOperation {
+                        id: 1,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 2,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 3,
+                        flags: Flags(OperationFlags::from_bits_retain(12)),
+                    },
+                    FamilyOperation {
+                        id: 4,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 5,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 6,
+                        flags: Flags(OperationFlags::from_bits_retain(10)),
+                    },
+                    FamilyOperation {
+                        id: 7,
+                        flags: Flags(OperationFlags::from_bits_retain(12)),
+                    },
+                    FamilyOperation {
+                        id: 8,
+                        flags: Flags(OperationFlags::from_bits_retain(10)),
+                    },
                 ],
                 multicast_groups: vec![],
             },
@@ -450,10 +731,22 @@ mod tests {
                 header_size: 0,
                 max_attr: 12,
                 operations: vec![
-                    FamilyOperation { id: 1, flags: 11 },
-                    FamilyOperation { id: 2, flags: 11 },
-                    FamilyOperation { id: 3, flags: 10 },
-                    FamilyOperation { id: 4, flags: 12 },
+                    FamilyOperation {
+                        id: 1,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 2,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 3,
+                        flags: Flags(OperationFlags::from_bits_retain(10)),
+                    },
+                    FamilyOperation {
+                        id: 4,
+                        flags: Flags(OperationFlags::from_bits_retain(12)),
+                    },
                 ],
                 multicast_groups: vec![],
             },
@@ -464,10 +757,22 @@ mod tests {
                 header_size: 0,
                 max_attr: 2,
                 operations: vec![
-                    FamilyOperation { id: 1, flags: 11 },
-                    FamilyOperation { id: 2, flags: 11 },
-                    FamilyOperation { id: 3, flags: 10 },
-                    FamilyOperation { id: 4, flags: 12 },
+                    FamilyOperation {
+                        id: 1,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 2,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 3,
+                        flags: Flags(OperationFlags::from_bits_retain(10)),
+                    },
+                    FamilyOperation {
+                        id: 4,
+                        flags: Flags(OperationFlags::from_bits_retain(12)),
+                    },
                 ],
                 multicast_groups: vec![],
             },
@@ -478,14 +783,38 @@ mod tests {
                 header_size: 0,
                 max_attr: 7,
                 operations: vec![
-                    FamilyOperation { id: 3, flags: 11 },
-                    FamilyOperation { id: 4, flags: 11 },
-                    FamilyOperation { id: 5, flags: 12 },
-                    FamilyOperation { id: 6, flags: 11 },
-                    FamilyOperation { id: 7, flags: 11 },
-                    FamilyOperation { id: 8, flags: 12 },
-                    FamilyOperation { id: 1, flags: 11 },
-                    FamilyOperation { id: 2, flags: 10 },
+                    FamilyOperation {
+                        id: 3,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 4,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 5,
+                        flags: Flags(OperationFlags::from_bits_retain(12)),
+                    },
+                    FamilyOperation {
+                        id: 6,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 7,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 8,
+                        flags: Flags(OperationFlags::from_bits_retain(12)),
+                    },
+                    FamilyOperation {
+                        id: 1,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 2,
+                        flags: Flags(OperationFlags::from_bits_retain(10)),
+                    },
                 ],
                 multicast_groups: vec![],
             },
@@ -508,8 +837,14 @@ mod tests {
                 header_size: 0,
                 max_attr: 13,
                 operations: vec![
-                    FamilyOperation { id: 1, flags: 14 },
-                    FamilyOperation { id: 2, flags: 11 },
+                    FamilyOperation {
+                        id: 1,
+                        flags: Flags(OperationFlags::from_bits_retain(14)),
+                    },
+                    FamilyOperation {
+                        id: 2,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
                 ],
                 multicast_groups: vec![],
             },
@@ -520,12 +855,30 @@ mod tests {
                 header_size: 0,
                 max_attr: 3,
                 operations: vec![
-                    FamilyOperation { id: 1, flags: 11 },
-                    FamilyOperation { id: 2, flags: 11 },
-                    FamilyOperation { id: 4, flags: 11 },
-                    FamilyOperation { id: 3, flags: 14 },
-                    FamilyOperation { id: 5, flags: 11 },
-                    FamilyOperation { id: 6, flags: 10 },
+                    FamilyOperation {
+                        id: 1,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 2,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 4,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 3,
+                        flags: Flags(OperationFlags::from_bits_retain(14)),
+                    },
+                    FamilyOperation {
+                        id: 5,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 6,
+                        flags: Flags(OperationFlags::from_bits_retain(10)),
+                    },
                 ],
                 multicast_groups: vec![FamilyMulticastGroup {
                     name: "mptcp_pm_cmds".to_string(),
@@ -539,11 +892,26 @@ mod tests {
                 header_size: 0,
                 max_attr: 22,
                 operations: vec![
-                    FamilyOperation { id: 2, flags: 11 },
-                    FamilyOperation { id: 3, flags: 10 },
-                    FamilyOperation { id: 4, flags: 10 },
-                    FamilyOperation { id: 6, flags: 10 },
-                    FamilyOperation { id: 8, flags: 10 },
+                    FamilyOperation {
+                        id: 2,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 3,
+                        flags: Flags(OperationFlags::from_bits_retain(10)),
+                    },
+                    FamilyOperation {
+                        id: 4,
+                        flags: Flags(OperationFlags::from_bits_retain(10)),
+                    },
+                    FamilyOperation {
+                        id: 6,
+                        flags: Flags(OperationFlags::from_bits_retain(10)),
+                    },
+                    FamilyOperation {
+                        id: 8,
+                        flags: Flags(OperationFlags::from_bits_retain(10)),
+                    },
                 ],
                 multicast_groups: vec![FamilyMulticastGroup {
                     name: "events".to_string(),
@@ -557,10 +925,22 @@ mod tests {
                 header_size: 0,
                 max_attr: 7,
                 operations: vec![
-                    FamilyOperation { id: 1, flags: 11 },
-                    FamilyOperation { id: 2, flags: 13 },
-                    FamilyOperation { id: 3, flags: 11 },
-                    FamilyOperation { id: 4, flags: 11 },
+                    FamilyOperation {
+                        id: 1,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 2,
+                        flags: Flags(OperationFlags::from_bits_retain(13)),
+                    },
+                    FamilyOperation {
+                        id: 3,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 4,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
                 ],
                 multicast_groups: vec![],
             },
@@ -571,26 +951,75 @@ mod tests {
                 header_size: 0,
                 max_attr: 4,
                 operations: vec![
-                    FamilyOperation { id: 1, flags: 11 },
-                    FamilyOperation { id: 4, flags: 10 },
+                    FamilyOperation {
+                        id: 1,
+                        flags: Flags(OperationFlags::from_bits_retain(11)),
+                    },
+                    FamilyOperation {
+                        id: 4,
+                        flags: Flags(OperationFlags::from_bits_retain(10)),
+                    },
                 ],
                 multicast_groups: vec![],
             },
         ];
 
-        let actual =
-            create_message_iterator::<GenericNetlinkResponse<Vec<ControllerAttribute>>>(recv_bytes)
-                .map(|read_message_result| {
-                    read_message_result
-                        .map(|message| message.payload)
-                        .map(expect_protocol_message)
-                        .map(|genl_message| genl_message.payload)
-                })
-                .flat_map(|read_message_result| read_message_result.map(|attrs| attrs.try_into()))
-                .collect::<Result<Vec<Family>, _>>()?;
+        // Visiting the same buffer sees every family without collecting messages.
+        let mut visited_names = FamilyNames(vec![]);
+        let status = visit_generic_messages(&recv_bytes, &mut |_, payload: &[u8]| {
+            visited_names.visit_attributes(payload)
+        })?;
+        assert_eq!(status, VisitMessagesStatus::Incomplete);
+        assert_eq!(
+            visited_names.0,
+            expected
+                .iter()
+                .map(|family| family.family_name.clone())
+                .collect::<Vec<_>>()
+        );
+
+        let actual = create_message_iterator::<GenericNetlinkResponse<Family>>(recv_bytes)
+            .map(|read_message_result| {
+                read_message_result
+                    .map(|message| message.payload)
+                    .map(expect_protocol_message)
+                    .map(|genl_message| genl_message.payload)
+            })
+            .collect::<Result<Vec<Family>, _>>()?;
 
         assert_eq!(actual, expected);
 
         Ok(())
     }
+
+    #[test]
+    fn widened_family_id_is_kept_as_unknown() -> anyhow::Result<()> {
+        use crate::attr::ControllerAttribute;
+        use nldl::attr::Lenient;
+        use nldl::attr::UnknownAttribute;
+        use nldl::message::NetlinkPayloadResponse;
+
+        // CTRL_ATTR_FAMILY_ID widened to a u32, then CTRL_ATTR_FAMILY_NAME "nlctrl".
+        let payload = [
+            0x08, 0x00, 0x01, 0x00, 0x10, 0x00, 0x00, 0x00, 0x0B, 0x00, 0x02, 0x00, 0x6E, 0x6C,
+            0x63, 0x74, 0x72, 0x6C, 0x00, 0x00,
+        ];
+        assert!(Vec::<ControllerAttribute>::deserialize(&payload).is_err());
+
+        let lenient = Lenient::<ControllerAttribute>::deserialize(&payload)?;
+        assert!(matches!(
+            lenient.attrs[..],
+            [
+                ControllerAttribute::Unknown(UnknownAttribute { ty: 1, .. }),
+                ControllerAttribute::FamilyName(_),
+            ]
+        ));
+        assert_eq!(lenient.diagnostics.len(), 1);
+        assert_eq!(
+            lenient.diagnostics[0].nla_type_id,
+            libc::CTRL_ATTR_FAMILY_ID as u16
+        );
+
+        Ok(())
+    }
 }
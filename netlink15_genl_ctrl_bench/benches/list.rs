@@ -0,0 +1,137 @@
+//! Compares decoding a large CTRL_CMD_GETFAMILY dump into a Vec (as
+//! recv_until_done_buffered does) against visiting it in place.
+
+use criterion::black_box;
+use criterion::criterion_group;
+use criterion::criterion_main;
+use criterion::Criterion;
+use criterion::Throughput;
+use netlink15_genl::visit_generic_messages;
+use netlink15_genl::GenericNetlinkHeader;
+use netlink15_genl::GenericNetlinkRequest;
+use netlink15_genl::GenericNetlinkResponse;
+use netlink15_genl_ctrl::attr::ControllerAttribute;
+use netlink15_genl_ctrl::attr::ControllerAttributeMulticastGroup;
+use netlink15_genl_ctrl::attr::ControllerAttributeOperation;
+use netlink15_genl_ctrl::attr::ControllerAttributeVisitor;
+use nldl::attr::NlaArray;
+use nldl::message::utils::create_message_iterator;
+use nldl::message::NetlinkMessageHeader;
+use nldl::message::NetlinkMessageRequest;
+use nldl::message::NetlinkMessageType;
+use std::convert::Infallible;
+
+const FAMILIES: u16 = 10_000;
+
+fn dump_bytes() -> Vec<u8> {
+    let mut buf = vec![];
+
+    for id in 0..FAMILIES {
+        let attrs = vec![
+            ControllerAttribute::FamilyName(format!("family{}", id)),
+            ControllerAttribute::FamilyId(id),
+            ControllerAttribute::Version(1),
+            ControllerAttribute::HeaderSize(0),
+            ControllerAttribute::MaxAttr(8),
+            ControllerAttribute::Operations(
+                (0..4)
+                    .map(|op| {
+                        vec![
+                            ControllerAttributeOperation::Id(op),
+                            ControllerAttributeOperation::Flags(0x0e),
+                        ]
+                    })
+                    .collect::<NlaArray<_>>(),
+            ),
+            ControllerAttribute::MulticastGroups(NlaArray::from(vec![vec![
+                ControllerAttributeMulticastGroup::Name("events".to_string()),
+                ControllerAttributeMulticastGroup::Id(u32::from(id)),
+            ]])),
+        ];
+
+        buf.extend(
+            nldl::serialize(&NetlinkMessageRequest {
+                header: header(libc::GENL_ID_CTRL as u16),
+                payload: NetlinkMessageType::ProtocolMessage(GenericNetlinkRequest {
+                    header: GenericNetlinkHeader {
+                        cmd: libc::CTRL_CMD_NEWFAMILY as u8,
+                        version: 2,
+                    },
+                    payload: attrs,
+                }),
+            })
+            .unwrap(),
+        );
+    }
+
+    buf.extend(
+        nldl::serialize(&NetlinkMessageRequest {
+            header: header(libc::NLMSG_DONE as u16),
+            payload: NetlinkMessageType::<u32>::Done,
+        })
+        .unwrap(),
+    );
+
+    buf
+}
+
+fn header(ty: u16) -> NetlinkMessageHeader {
+    NetlinkMessageHeader {
+        ty,
+        flags: libc::NLM_F_MULTI as u16,
+        seq: 1,
+        pid: 0,
+    }
+}
+
+#[derive(Default)]
+struct FamilyIdSum(u64);
+
+impl<'a> ControllerAttributeVisitor<'a> for FamilyIdSum {
+    fn visit_family_id(&mut self, value: u16) {
+        self.0 += u64::from(value);
+    }
+}
+
+fn buffered(buf: &[u8]) -> u64 {
+    let messages: Vec<GenericNetlinkResponse<Vec<ControllerAttribute>>> =
+        create_message_iterator(buf.to_vec())
+            .filter_map(|message| match message.unwrap().payload {
+                NetlinkMessageType::ProtocolMessage(message) => Some(message),
+                _ => None,
+            })
+            .collect();
+
+    messages
+        .iter()
+        .flat_map(|message| &message.payload)
+        .filter_map(|attr| match attr {
+            ControllerAttribute::FamilyId(id) => Some(u64::from(*id)),
+            _ => None,
+        })
+        .sum()
+}
+
+fn visited(buf: &[u8]) -> u64 {
+    let mut sum = FamilyIdSum::default();
+    visit_generic_messages(buf, &mut |_header, payload: &[u8]| {
+        sum.visit_attributes(payload).unwrap();
+        Ok::<_, Infallible>(())
+    })
+    .unwrap();
+    sum.0
+}
+
+fn bench_list(c: &mut Criterion) {
+    let buf = dump_bytes();
+    assert_eq!(buffered(&buf), visited(&buf));
+
+    let mut group = c.benchmark_group("list");
+    group.throughput(Throughput::Bytes(buf.len() as u64));
+    group.bench_function("buffered", |b| b.iter(|| buffered(black_box(&buf))));
+    group.bench_function("visitor", |b| b.iter(|| visited(black_box(&buf))));
+    group.finish();
+}
+
+criterion_group!(benches, bench_list);
+criterion_main!(benches);
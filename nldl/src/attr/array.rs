@@ -0,0 +1,174 @@
+use super::for_each_attribute;
+use super::DeserializeError;
+use super::ParseNetlinkAttributeFromBufferError;
+use super::PolicyError;
+use crate::attribute_len;
+use crate::linux::NLA_F_NESTED;
+use crate::message::NetlinkPayloadRequest;
+use crate::message::NetlinkPayloadResponse;
+use crate::write_attribute;
+use crate::SerializeError;
+use bytes::BufMut;
+
+/// An element of an [NlaArray] together with its index.
+#[derive(Debug, PartialEq, Eq)]
+pub struct Indexed<T> {
+    /// Written as the element's attribute type ID, so it must fit in a `u16`
+    /// for the array to serialize.
+    pub index: usize,
+    pub value: T,
+}
+
+/// An array of nested attributes where each element's attribute type ID is its
+/// index, such as `CTRL_ATTR_OPS` and `CTRL_ATTR_MCAST_GROUPS`.
+///
+/// Arrays built with [FromIterator] or `From<Vec<T>>` are numbered from
+/// `1`, matching libnl's `nla_nest_start(msg, i)` loops. Received arrays keep
+/// the indices sent by the kernel, and those indices are written back unchanged
+/// when serialized.
+#[derive(Debug, PartialEq, Eq)]
+pub struct NlaArray<T>(pub Vec<Indexed<T>>);
+
+impl<T> NlaArray<T> {
+    pub fn values(&self) -> impl Iterator<Item = &T> {
+        self.0.iter().map(|element| &element.value)
+    }
+
+    pub fn into_values(self) -> impl Iterator<Item = T> {
+        self.0.into_iter().map(|element| element.value)
+    }
+}
+
+impl<T> FromIterator<T> for NlaArray<T> {
+    fn from_iter<I: IntoIterator<Item = T>>(iter: I) -> Self {
+        Self(
+            (iter.into_iter().enumerate())
+                .map(|(i, value)| Indexed {
+                    index: i + 1,
+                    value,
+                })
+                .collect(),
+        )
+    }
+}
+
+impl<T> From<Vec<T>> for NlaArray<T> {
+    fn from(values: Vec<T>) -> Self {
+        values.into_iter().collect()
+    }
+}
+
+impl<T: NetlinkPayloadRequest> NetlinkPayloadRequest for NlaArray<T> {
+    const NLA_FLAGS: u16 = NLA_F_NESTED;
+
+    fn serialized_len(&self) -> usize {
+        (self.values())
+            .map(|value| attribute_len(value.serialized_len()))
+            .sum()
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        for Indexed { index, value } in &self.0 {
+            let ty = u16::try_from(*index)
+                .map_err(|_| SerializeError::ArrayIndexTooLarge { index: *index })?;
+            write_attribute(
+                buf,
+                std::any::type_name::<Self>(),
+                ty | T::NLA_FLAGS,
+                value.serialized_len(),
+                |buf| value.serialize(buf),
+            )?;
+        }
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<(), PolicyError> {
+        for Indexed { index, value } in &self.0 {
+            // Serializing rejects indices that don't fit, so the path only
+            // needs to point somewhere near the element.
+            let ty = u16::try_from(*index).unwrap_or(u16::MAX);
+            value.validate().map_err(|err| err.within("NlaArray", ty))?;
+        }
+        Ok(())
+    }
+}
+
+impl<T: NetlinkPayloadResponse> NetlinkPayloadResponse for NlaArray<T>
+where
+    T::Error: Send + Sync + 'static,
+{
+    type Error = ParseNetlinkAttributeFromBufferError;
+
+    fn deserialize(buf: &[u8]) -> Result<Self, Self::Error> {
+        let mut elements = vec![];
+
+        for_each_attribute(buf, |index, payload| {
+            let value = T::deserialize(payload).map_err(|err| DeserializeError {
+                attribute_struct_name: "NlaArray",
+                nla_type_id: index,
+                source: Box::new(err),
+            })?;
+            elements.push(Indexed {
+                index: usize::from(index),
+                value,
+            });
+            Ok(())
+        })?;
+
+        Ok(Self(elements))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_with_one_based_indices() {
+        let array: NlaArray<u32> = vec![7, 9].into();
+
+        let mut buf = vec![];
+        array.serialize(&mut buf).unwrap();
+
+        #[rustfmt::skip]
+        let expected = [
+            0x08, 0x00, 0x01, 0x00, 0x07, 0x00, 0x00, 0x00,
+            0x08, 0x00, 0x02, 0x00, 0x09, 0x00, 0x00, 0x00,
+        ];
+        assert_eq!(buf, expected);
+    }
+
+    #[test]
+    fn deserialize_keeps_received_indices() {
+        #[rustfmt::skip]
+        let recv_bytes = [
+            0x08, 0x00, 0x03, 0x00, 0x07, 0x00, 0x00, 0x00,
+            0x08, 0x00, 0x05, 0x00, 0x09, 0x00, 0x00, 0x00,
+        ];
+
+        let array = NlaArray::<u32>::deserialize(&recv_bytes).unwrap();
+        assert_eq!(
+            array,
+            NlaArray(vec![
+                Indexed { index: 3, value: 7 },
+                Indexed { index: 5, value: 9 },
+            ])
+        );
+
+        let mut buf = vec![];
+        array.serialize(&mut buf).unwrap();
+        assert_eq!(buf, recv_bytes);
+    }
+
+    #[test]
+    fn index_past_u16_max_is_an_error() {
+        let array: NlaArray<()> = vec![(); 65536].into();
+        assert_eq!(array.0[65535].index, 65536);
+
+        let mut buf = vec![];
+        assert_eq!(
+            array.serialize(&mut buf),
+            Err(SerializeError::ArrayIndexTooLarge { index: 65536 })
+        );
+    }
+}
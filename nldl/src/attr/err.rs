@@ -9,3 +9,33 @@ pub struct DeserializeError {
     #[source]
     pub source: Box<dyn std::error::Error + Send + Sync>,
 }
+
+/// Returned when a struct deriving `Deserialize` doesn't receive an attribute
+/// for one of its required (non-`Option`, non-`Vec`) fields.
+#[derive(Debug, thiserror::Error)]
+#[error("netlink attribute {nla_type_id} for required field \"{field_name}\" of {attribute_struct_name} is missing")]
+pub struct MissingAttributeError {
+    pub attribute_struct_name: &'static str,
+    pub field_name: &'static str,
+
+    /// The Netlink attribute type ID the field is annotated with.
+    pub nla_type_id: u16,
+}
+
+/// Returned when a buffer of attributes is deserialized strictly, like the
+/// kernel's `NL_VALIDATE_STRICT`. See [check_strict](super::check_strict).
+#[derive(Debug, thiserror::Error, PartialEq, Eq, Clone)]
+pub enum StrictError {
+    #[error("netlink attribute {nla_type_id} is unknown to {attribute_struct_name}")]
+    UnknownAttribute {
+        attribute_struct_name: &'static str,
+        nla_type_id: u16,
+    },
+    #[error("netlink attribute {nla_type_id} of {attribute_struct_name} appears more than once")]
+    DuplicateAttribute {
+        attribute_struct_name: &'static str,
+        nla_type_id: u16,
+    },
+    #[error("found {len} bytes of trailing data after the last netlink attribute")]
+    TrailingBytes { len: usize },
+}
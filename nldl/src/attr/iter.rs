@@ -0,0 +1,188 @@
+use super::raw::RawNetlinkAttribute;
+use super::DeserializeBorrowed;
+use super::DeserializeError;
+use super::ParseRawNetlinkAttributeError;
+use crate::linux::nla_align;
+use crate::linux::NLA_F_NESTED;
+use crate::linux::NLA_F_NET_BYTEORDER;
+use crate::message::NetlinkPayloadResponseBorrowed;
+
+/// A netlink attribute borrowed from a buffer.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Attr<'a> {
+    /// The attribute type ID with flag bits masked off.
+    pub ty: u16,
+    /// The `NLA_F_*` flag bits of the `nla_type` header field.
+    pub flags: u16,
+    pub payload: &'a [u8],
+}
+
+impl<'a> Attr<'a> {
+    pub fn is_nested(&self) -> bool {
+        self.flags & NLA_F_NESTED != 0
+    }
+
+    pub fn is_net_byteorder(&self) -> bool {
+        self.flags & NLA_F_NET_BYTEORDER != 0
+    }
+
+    /// Iterates over the child attributes of a nested attribute.
+    ///
+    /// Many families don't set `NLA_F_NESTED`, so the flag isn't checked.
+    pub fn nested(&self) -> AttrIter<'a> {
+        AttrIter::new(self.payload)
+    }
+
+    /// Decodes this attribute into an attribute enum.
+    pub fn decode<T: DeserializeBorrowed<'a>>(&self) -> Result<T, DeserializeError> {
+        T::deserialize_borrowed_with_flags(self.ty, self.flags, self.payload)
+    }
+
+    /// Decodes only the payload, e.g. as a `u32` or `&str`.
+    pub fn payload_as<T: NetlinkPayloadResponseBorrowed<'a>>(&self) -> Result<T, T::Error> {
+        T::deserialize_borrowed(self.payload)
+    }
+}
+
+/// Lazily walks the netlink attributes in a buffer without allocating.
+///
+/// ```
+/// use nldl::attr::AttrIter;
+///
+/// // CTRL_ATTR_FAMILY_ID (1) = 16, CTRL_ATTR_FAMILY_NAME (2) = "nlctrl"
+/// let buf = [
+///     0x06, 0x00, 0x01, 0x00, 0x10, 0x00, 0x00, 0x00,
+///     0x0b, 0x00, 0x02, 0x00, 0x6e, 0x6c, 0x63, 0x74, 0x72, 0x6c, 0x00, 0x00,
+/// ];
+///
+/// let name = AttrIter::new(&buf).find_by_type(2).unwrap().unwrap();
+/// assert_eq!(name.payload_as::<&str>(), Ok("nlctrl"));
+/// ```
+///
+/// Iteration stops after the first malformed attribute header. To decode every
+/// attribute into an attribute enum instead, see [NestedRef](super::NestedRef).
+#[derive(Clone, Debug)]
+pub struct AttrIter<'a> {
+    view: &'a [u8],
+}
+
+impl<'a> AttrIter<'a> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self { view: buf }
+    }
+
+    /// The bytes that haven't been iterated over yet.
+    pub fn remaining(&self) -> &'a [u8] {
+        self.view
+    }
+
+    /// Returns the first attribute with the given type ID.
+    pub fn find_by_type(
+        mut self,
+        ty: u16,
+    ) -> Option<Result<Attr<'a>, ParseRawNetlinkAttributeError>> {
+        self.find(|attr| !matches!(attr, Ok(attr) if attr.ty != ty))
+    }
+}
+
+impl<'a> Iterator for AttrIter<'a> {
+    type Item = Result<Attr<'a>, ParseRawNetlinkAttributeError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.view.is_empty() {
+            return None;
+        }
+
+        let raw = match RawNetlinkAttribute::try_from(self.view) {
+            Ok(raw) => raw,
+            Err(err) => {
+                self.view = &[];
+                return Some(Err(err));
+            }
+        };
+
+        // The final attribute in a buffer may not be followed by padding.
+        let aligned_len = nla_align(usize::from(raw.len)).min(self.view.len());
+        self.view = &self.view[aligned_len..];
+
+        Some(Ok(Attr {
+            ty: raw.ty,
+            flags: raw.flags,
+            payload: raw.payload,
+        }))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[rustfmt::skip]
+    const BUF: [u8; 24] = [
+        // Unpadded 1 byte payload
+        0x05, 0x00, 0x01, 0x00, 0x07, 0x00, 0x00, 0x00,
+        // Nested attribute containing a u32
+        0x0c, 0x00, 0x02, 0x80,
+        0x08, 0x00, 0x01, 0x00, 0x2a, 0x00, 0x00, 0x00,
+        // Final attribute without trailing padding
+        0x04, 0x00, 0x03, 0x00,
+    ];
+
+    #[test]
+    fn yields_type_flags_and_payload() {
+        let attrs: Vec<_> = AttrIter::new(&BUF).collect::<Result<_, _>>().unwrap();
+        assert_eq!(attrs.len(), 3);
+        assert_eq!(
+            attrs[0],
+            Attr {
+                ty: 1,
+                flags: 0,
+                payload: &[0x07]
+            }
+        );
+        assert_eq!(attrs[1].ty, 2);
+        assert!(attrs[1].is_nested());
+        assert_eq!(attrs[2].payload, &[] as &[u8]);
+    }
+
+    #[test]
+    fn descends_into_nested_attributes() {
+        let nested = AttrIter::new(&BUF).find_by_type(2).unwrap().unwrap();
+        let child = nested.nested().find_by_type(1).unwrap().unwrap();
+        assert_eq!(child.payload_as::<u32>(), Ok(42));
+    }
+
+    #[test]
+    fn stops_after_malformed_header() {
+        let mut iter = AttrIter::new(&[0x08, 0x00]);
+        assert!(matches!(
+            iter.next(),
+            Some(Err(ParseRawNetlinkAttributeError::IncompleteHeader {
+                len: 2
+            }))
+        ));
+        assert!(iter.next().is_none());
+    }
+
+    #[test]
+    fn stops_after_header_shorter_than_itself() {
+        let mut iter = AttrIter::new(&[0, 0, 0, 0]);
+        assert!(matches!(
+            iter.next(),
+            Some(Err(ParseRawNetlinkAttributeError::InvalidLength {
+                nla_len: 0
+            }))
+        ));
+        assert!(iter.next().is_none());
+
+        let mut iter = AttrIter::new(&[4, 0, 1, 0, 2, 0, 2, 0]);
+        assert_eq!(iter.next().unwrap().unwrap().ty, 1);
+        assert!(matches!(
+            iter.next(),
+            Some(Err(ParseRawNetlinkAttributeError::InvalidLength {
+                nla_len: 2
+            }))
+        ));
+        assert!(iter.next().is_none());
+    }
+}
@@ -0,0 +1,167 @@
+use super::Attr;
+use super::AttrIter;
+use super::Deserialize;
+use super::DeserializeError;
+use super::DeserializeStrict;
+use super::ParseNetlinkAttributeFromBufferError;
+use super::PolicyError;
+use crate::message::NetlinkPayloadResponse;
+use std::error::Error;
+use std::fmt::Debug;
+
+/// Attribute enums deserialized leniently.
+///
+/// A known attribute that fails to decode, e.g. because a newer kernel widened
+/// it, is kept as the enum's `#[nla_type(_)]` variant and the failure is
+/// recorded in [diagnostics](Self::diagnostics). The remaining attributes
+/// still decode. Enums without a wildcard variant fail as usual.
+///
+/// ```
+/// use nldl::attr::Lenient;
+/// use nldl::attr::UnknownAttribute;
+/// use nldl::message::NetlinkPayloadResponse;
+///
+/// #[derive(Debug, PartialEq, nldl::attr::Deserialize)]
+/// enum ControllerAttribute {
+///     #[nla_type(1)]
+///     FamilyId(u16),
+///     #[nla_type(_)]
+///     Unknown(UnknownAttribute),
+/// }
+///
+/// // CTRL_ATTR_FAMILY_ID with a 4 byte payload
+/// let buf = [0x08, 0x00, 0x01, 0x00, 0x10, 0x00, 0x00, 0x00];
+///
+/// let lenient = Lenient::<ControllerAttribute>::deserialize(&buf).unwrap();
+/// assert!(matches!(lenient.attrs[..], [ControllerAttribute::Unknown(UnknownAttribute { ty: 1, .. })]));
+/// assert_eq!(lenient.diagnostics[0].nla_type_id, 1);
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct Lenient<T> {
+    pub attrs: Vec<T>,
+    pub diagnostics: Vec<LenientDiagnostic>,
+}
+
+/// Attribute structs deserialized leniently.
+///
+/// A field whose attribute fails to decode is filled as if the attribute were
+/// missing and the failure is recorded in [diagnostics](Self::diagnostics).
+/// `Option`, `Vec` and `#[nla_type(.., default)]` fields are left empty. A
+/// required field that fails to decode still fails the whole struct.
+///
+/// ```
+/// use nldl::attr::LenientStruct;
+/// use nldl::message::NetlinkPayloadResponse;
+///
+/// #[derive(Debug, PartialEq, nldl::attr::Deserialize)]
+/// struct Family {
+///     #[nla_type(1)]
+///     family_id: Option<u16>,
+///     #[nla_type(2)]
+///     family_name: String,
+/// }
+///
+/// // CTRL_ATTR_FAMILY_ID with a 4 byte payload, then CTRL_ATTR_FAMILY_NAME
+/// let buf = [
+///     0x08, 0x00, 0x01, 0x00, 0x10, 0x00, 0x00, 0x00, 0x07, 0x00, 0x02, 0x00, b'a', b'b',
+///     0x00, 0x00,
+/// ];
+///
+/// let lenient = LenientStruct::<Family>::deserialize(&buf).unwrap();
+/// assert_eq!(lenient.value.family_id, None);
+/// assert_eq!(lenient.value.family_name, "ab");
+/// assert_eq!(lenient.diagnostics[0].nla_type_id, 1);
+/// ```
+#[derive(Debug, PartialEq)]
+pub struct LenientStruct<T> {
+    pub value: T,
+    pub diagnostics: Vec<LenientDiagnostic>,
+}
+
+/// Implemented by structs deriving `Deserialize` without a lifetime
+/// parameter. See [LenientStruct].
+pub trait DeserializeLenient: Sized {
+    /// Deserializes a buffer of attributes, pushing a diagnostic for every
+    /// field that was skipped.
+    fn deserialize_lenient(
+        buf: &[u8],
+        diagnostics: &mut Vec<LenientDiagnostic>,
+    ) -> Result<Self, ParseNetlinkAttributeFromBufferError>;
+}
+
+/// Records a known attribute that [Lenient] kept as an unknown attribute, or
+/// that [LenientStruct] skipped.
+#[derive(thiserror::Error, Debug, PartialEq, Eq, Clone)]
+#[error(
+    "netlink attribute {nla_type_id} of {attribute_struct_name} could not be decoded: {reason}"
+)]
+pub struct LenientDiagnostic {
+    pub attribute_struct_name: &'static str,
+
+    /// The Netlink attribute type ID deserialization failed on.
+    pub nla_type_id: u16,
+
+    /// The chain of errors that caused the failure, joined with ": ".
+    pub reason: String,
+
+    /// Set if the attribute violated its `#[nla_policy(..)]`.
+    pub policy_error: Option<PolicyError>,
+}
+
+impl From<&DeserializeError> for LenientDiagnostic {
+    fn from(err: &DeserializeError) -> Self {
+        let mut reasons = vec![];
+        let mut current: Option<&(dyn Error + 'static)> = err.source();
+        while let Some(err) = current {
+            reasons.push(err.to_string());
+            current = err.source();
+        }
+
+        Self {
+            attribute_struct_name: err.attribute_struct_name,
+            nla_type_id: err.nla_type_id,
+            reason: reasons.join(": "),
+            policy_error: PolicyError::from_deserialize_error(err),
+        }
+    }
+}
+
+impl<T: Deserialize> NetlinkPayloadResponse for Lenient<T> {
+    type Error = ParseNetlinkAttributeFromBufferError;
+
+    fn deserialize(buf: &[u8]) -> Result<Self, Self::Error> {
+        if T::STRICT {
+            <Vec<T> as DeserializeStrict>::check_strict(buf)?;
+        }
+
+        let mut attrs = vec![];
+        let mut diagnostics = vec![];
+
+        for attr in AttrIter::new(buf) {
+            let Attr { ty, flags, payload } = attr?;
+            let attr = match T::deserialize_with_flags(ty, flags, payload) {
+                Ok(attr) => attr,
+                Err(err) => match T::fallback(ty, flags, payload) {
+                    Some(attr) => {
+                        diagnostics.push(LenientDiagnostic::from(&err));
+                        attr
+                    }
+                    None => return Err(err.into()),
+                },
+            };
+            attrs.push(attr);
+        }
+
+        Ok(Self { attrs, diagnostics })
+    }
+}
+
+impl<T: DeserializeLenient + Debug + PartialEq> NetlinkPayloadResponse for LenientStruct<T> {
+    type Error = ParseNetlinkAttributeFromBufferError;
+
+    fn deserialize(buf: &[u8]) -> Result<Self, Self::Error> {
+        let mut diagnostics = vec![];
+        let value = T::deserialize_lenient(buf, &mut diagnostics)?;
+        Ok(Self { value, diagnostics })
+    }
+}
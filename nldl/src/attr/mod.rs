@@ -1,56 +1,245 @@
-use super::linux::nlmsg_align;
+use super::attribute_len;
+use super::linux::NLA_F_NESTED;
 use super::message::NetlinkPayloadRequest;
 use super::message::NetlinkPayloadResponse;
-use super::write_to_buf_with_prefixed_u16_len;
+use super::write_attribute;
+use super::SerializeError;
+use bytes::BufMut;
 use raw::RawNetlinkAttribute;
 use std::fmt::Debug;
+use std::mem::size_of;
 pub use unknown::UnknownAttribute;
+pub use unknown::UnknownAttributeRef;
 
+mod array;
 mod err;
+mod iter;
+mod lenient;
 mod nested;
+mod nested_ref;
+pub mod policy;
 mod raw;
+pub mod schema;
+mod strict;
 mod unknown;
 
+pub use array::Indexed;
+pub use array::NlaArray;
 pub use err::DeserializeError;
+pub use err::MissingAttributeError;
+pub use err::StrictError;
+pub use iter::Attr;
+pub use iter::AttrIter;
+pub use lenient::DeserializeLenient;
+pub use lenient::Lenient;
+pub use lenient::LenientDiagnostic;
+pub use lenient::LenientStruct;
 pub use nested::Nested;
+pub use nested_ref::NestedRef;
+pub use policy::PolicyError;
+pub use policy::PolicyViolation;
 pub use raw::ParseRawNetlinkAttributeError;
+pub use schema::Schema;
+pub use strict::check_strict;
+pub use strict::deserialize_strict;
+pub use strict::DeserializeStrict;
 
 pub trait Serialize {
+    /// The value written to the attribute header's `nla_type` field. This is
+    /// the attribute type ID combined with any `NLA_F_*` flag bits.
     fn get_type(&self) -> u16;
-    fn serialize_payload(&self, buf: &mut Vec<u8>);
+
+    /// The number of bytes [serialize_payload](Self::serialize_payload)
+    /// writes. The full attribute's length, including its header and padding,
+    /// is available from [NetlinkPayloadRequest::serialized_len].
+    fn serialized_payload_len(&self) -> usize;
+
+    /// Fails with [SerializeError::AttributeTooLarge] if a nested attribute's
+    /// length doesn't fit in its `nla_len` field.
+    fn serialize_payload<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError>;
+
+    /// Checks the payload against the attribute's `#[nla_policy(..)]`
+    /// annotation, then validates any nested attributes.
+    fn validate_payload(&self) -> Result<(), PolicyError> {
+        Ok(())
+    }
 }
 
+/// The largest payload an attribute can carry. `nla_len` is a `u16` that also
+/// counts the 4 byte attribute header.
+pub const MAX_ATTRIBUTE_PAYLOAD_LEN: usize = u16::MAX as usize - size_of::<libc::nlattr>();
+
 pub trait Deserialize: Debug + Sized + PartialEq {
+    /// Set by `#[nldl(strict)]`. Buffers of these attributes are always
+    /// checked with [check_strict] before they're deserialized.
+    const STRICT: bool = false;
+
     fn deserialize(ty: u16, payload: &[u8]) -> Result<Self, DeserializeError>;
+
+    /// Like [deserialize](Self::deserialize), but also given the `NLA_F_*`
+    /// flag bits of the attribute header. Only types that keep attributes
+    /// as-is, like [UnknownAttribute], need them, so they're ignored by
+    /// default.
+    fn deserialize_with_flags(
+        ty: u16,
+        flags: u16,
+        payload: &[u8],
+    ) -> Result<Self, DeserializeError> {
+        let _ = flags;
+        Self::deserialize(ty, payload)
+    }
+
+    /// Whether `ty` is matched by something other than a wildcard variant.
+    /// Strict deserialization rejects attributes with any other type ID.
+    fn is_known_type(ty: u16) -> bool {
+        let _ = ty;
+        true
+    }
+
+    /// The wildcard variant holding an attribute as-is. [Lenient] uses this
+    /// to keep known attributes that fail to decode. Returns `None` for enums
+    /// without a wildcard variant and for strict enums.
+    fn fallback(ty: u16, flags: u16, payload: &[u8]) -> Option<Self> {
+        let _ = (ty, flags, payload);
+        None
+    }
 }
 
-impl<T: Serialize> NetlinkPayloadRequest for T {
-    fn serialize(&self, buf: &mut Vec<u8>) {
-        write_to_buf_with_prefixed_u16_len(buf, |buf| {
-            buf.extend_from_slice(&self.get_type().to_ne_bytes()[..]);
-            self.serialize_payload(buf);
-        });
+/// Like [Deserialize], but the deserialized value may borrow from the payload.
+/// Deriving `Deserialize` on a type with a lifetime parameter implements this
+/// trait instead.
+///
+/// Every [Deserialize] type also implements this trait.
+pub trait DeserializeBorrowed<'a>: Debug + Sized + PartialEq {
+    /// See [Deserialize::STRICT].
+    const STRICT: bool = false;
+
+    fn deserialize_borrowed(ty: u16, payload: &'a [u8]) -> Result<Self, DeserializeError>;
+
+    /// See [Deserialize::deserialize_with_flags].
+    fn deserialize_borrowed_with_flags(
+        ty: u16,
+        flags: u16,
+        payload: &'a [u8],
+    ) -> Result<Self, DeserializeError> {
+        let _ = flags;
+        Self::deserialize_borrowed(ty, payload)
+    }
+
+    /// See [Deserialize::is_known_type].
+    fn is_known_type(ty: u16) -> bool {
+        let _ = ty;
+        true
+    }
+
+    /// See [Deserialize::fallback].
+    fn fallback(ty: u16, flags: u16, payload: &'a [u8]) -> Option<Self> {
+        let _ = (ty, flags, payload);
+        None
     }
 }
 
-impl<T: Deserialize> NetlinkPayloadResponse for T {
-    type Error = ParseNetlinkAttributeFromBufferError;
+impl<'a, T: Deserialize> DeserializeBorrowed<'a> for T {
+    const STRICT: bool = <T as Deserialize>::STRICT;
 
-    fn deserialize(buf: &[u8]) -> Result<Self, Self::Error> {
-        let raw = RawNetlinkAttribute::try_from(buf)?;
-        Deserialize::deserialize(raw.ty, raw.payload)
-            .map_err(ParseNetlinkAttributeFromBufferError::AttributeDeserializeError)
+    fn deserialize_borrowed(ty: u16, payload: &'a [u8]) -> Result<Self, DeserializeError> {
+        T::deserialize(ty, payload)
+    }
+
+    fn deserialize_borrowed_with_flags(
+        ty: u16,
+        flags: u16,
+        payload: &'a [u8],
+    ) -> Result<Self, DeserializeError> {
+        T::deserialize_with_flags(ty, flags, payload)
+    }
+
+    fn is_known_type(ty: u16) -> bool {
+        <T as Deserialize>::is_known_type(ty)
+    }
+
+    fn fallback(ty: u16, flags: u16, payload: &'a [u8]) -> Option<Self> {
+        <T as Deserialize>::fallback(ty, flags, payload)
+    }
+}
+
+impl<T: Serialize> NetlinkPayloadRequest for T {
+    const NLA_FLAGS: u16 = NLA_F_NESTED;
+
+    fn serialized_len(&self) -> usize {
+        attribute_len(self.serialized_payload_len())
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        write_attribute(
+            buf,
+            std::any::type_name::<T>(),
+            self.get_type(),
+            self.serialized_payload_len(),
+            |buf| self.serialize_payload(buf),
+        )
+    }
+
+    fn validate(&self) -> Result<(), PolicyError> {
+        self.validate_payload()
     }
 }
 
 impl<T: Serialize> NetlinkPayloadRequest for Vec<T> {
-    fn serialize(&self, buf: &mut Vec<u8>) {
+    const NLA_FLAGS: u16 = NLA_F_NESTED;
+
+    fn serialized_len(&self) -> usize {
+        self.iter().map(NetlinkPayloadRequest::serialized_len).sum()
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
         for attr in self {
-            attr.serialize(buf);
+            attr.serialize(buf)?;
         }
+        Ok(())
+    }
+
+    fn validate(&self) -> Result<(), PolicyError> {
+        self.iter().try_for_each(NetlinkPayloadRequest::validate)
     }
 }
 
+/// Splits `items` into consecutive groups whose serialized lengths each add up
+/// to at most `max_len` bytes, preserving order.
+///
+/// Useful when a list of nested attributes is too long for a single parent
+/// attribute (see [MAX_ATTRIBUTE_PAYLOAD_LEN]) or message, and the protocol
+/// allows sending it across several requests.
+pub fn fragment<T: NetlinkPayloadRequest>(
+    items: impl IntoIterator<Item = T>,
+    max_len: usize,
+) -> Result<Vec<Vec<T>>, SerializeError> {
+    let mut fragments = vec![];
+    let mut current = vec![];
+    let mut current_len = 0;
+
+    for item in items {
+        let len = item.serialized_len();
+        if len > max_len {
+            return Err(SerializeError::ItemTooLarge { len, max_len });
+        }
+
+        if current_len + len > max_len {
+            fragments.push(std::mem::take(&mut current));
+            current_len = 0;
+        }
+
+        current_len += len;
+        current.push(item);
+    }
+
+    if !current.is_empty() {
+        fragments.push(current);
+    }
+
+    Ok(fragments)
+}
+
 #[derive(thiserror::Error, Debug)]
 pub enum ParseNetlinkAttributeFromBufferError {
     #[error(
@@ -61,24 +250,132 @@ pub enum ParseNetlinkAttributeFromBufferError {
 
     #[error("transparent")]
     AttributeDeserializeError(#[from] DeserializeError),
+
+    #[error(transparent)]
+    MissingAttribute(#[from] MissingAttributeError),
+
+    #[error(transparent)]
+    Strict(#[from] StrictError),
 }
 
-impl<T: Deserialize> NetlinkPayloadResponse for Vec<T> {
+impl ParseNetlinkAttributeFromBufferError {
+    /// Returns the policy violation that caused this error, if any.
+    pub fn policy_error(&self) -> Option<PolicyError> {
+        match self {
+            Self::AttributeDeserializeError(err) => PolicyError::from_deserialize_error(err),
+            _ => None,
+        }
+    }
+
+    /// Returns the strict mode check that caused this error, if any. Checks
+    /// of nested attribute sets are found through the chain of errors.
+    pub fn strict_error(&self) -> Option<StrictError> {
+        let mut current: Option<&(dyn std::error::Error + 'static)> = Some(self);
+
+        while let Some(err) = current {
+            if let Some(Self::Strict(err)) = err.downcast_ref::<Self>() {
+                return Some(err.clone());
+            }
+            if let Some(err) = err.downcast_ref::<StrictError>() {
+                return Some(err.clone());
+            }
+            current = err.source();
+        }
+
+        None
+    }
+}
+
+/// Deserializes the first netlink attribute in a buffer. Bytes beyond the
+/// attribute's length are ignored.
+///
+/// Types deriving `Deserialize` on an enum use this for their
+/// [NetlinkPayloadResponse] implementation.
+pub fn deserialize_attribute<T: Deserialize>(
+    buf: &[u8],
+) -> Result<T, ParseNetlinkAttributeFromBufferError> {
+    let raw = RawNetlinkAttribute::try_from(buf)?;
+    Ok(T::deserialize_with_flags(raw.ty, raw.flags, raw.payload)?)
+}
+
+/// Opts a hand-written [Deserialize] type into a [NetlinkPayloadResponse]
+/// implementation that decodes a single attribute with
+/// [deserialize_attribute]. Types deriving `Deserialize` get that
+/// implementation generated instead.
+///
+/// ```
+/// use nldl::attr::{Deserialize, DeserializeError, SingleAttribute};
+/// use nldl::message::NetlinkPayloadResponse;
+///
+/// #[derive(Debug, PartialEq)]
+/// struct Raw(u16, Vec<u8>);
+///
+/// impl Deserialize for Raw {
+///     fn deserialize(ty: u16, payload: &[u8]) -> Result<Self, DeserializeError> {
+///         Ok(Self(ty, payload.to_vec()))
+///     }
+/// }
+///
+/// impl SingleAttribute for Raw {}
+///
+/// let buf = [0x05, 0x00, 0x07, 0x00, 0x2a, 0x00, 0x00, 0x00];
+/// let raw = <Raw as NetlinkPayloadResponse>::deserialize(&buf).unwrap();
+/// assert_eq!(raw, Raw(7, vec![0x2a]));
+/// ```
+pub trait SingleAttribute: Deserialize {}
+
+impl<T: SingleAttribute> NetlinkPayloadResponse for T {
     type Error = ParseNetlinkAttributeFromBufferError;
 
     fn deserialize(buf: &[u8]) -> Result<Self, Self::Error> {
-        let mut attrs = vec![];
-        let mut view = buf;
+        deserialize_attribute(buf)
+    }
+}
+
+/// Borrowing version of [deserialize_attribute].
+pub fn deserialize_attribute_borrowed<'a, T: DeserializeBorrowed<'a>>(
+    buf: &'a [u8],
+) -> Result<T, ParseNetlinkAttributeFromBufferError> {
+    let raw = RawNetlinkAttribute::try_from(buf)?;
+    Ok(T::deserialize_borrowed_with_flags(
+        raw.ty,
+        raw.flags,
+        raw.payload,
+    )?)
+}
+
+/// Calls `f` with the type ID and payload of every netlink attribute in a
+/// buffer, stopping at the first error.
+///
+/// Types deriving `Deserialize` on a struct use this to fill their fields.
+pub fn for_each_attribute<'a, F>(
+    buf: &'a [u8],
+    mut f: F,
+) -> Result<(), ParseNetlinkAttributeFromBufferError>
+where
+    F: FnMut(u16, &'a [u8]) -> Result<(), DeserializeError>,
+{
+    for attr in AttrIter::new(buf) {
+        let Attr { ty, payload, .. } = attr?;
+        f(ty, payload)?;
+    }
+
+    Ok(())
+}
+
+impl<T: Deserialize> NetlinkPayloadResponse for Vec<T> {
+    type Error = ParseNetlinkAttributeFromBufferError;
 
-        while !view.is_empty() {
-            let raw = RawNetlinkAttribute::try_from(view)?;
-            let RawNetlinkAttribute { len, ty, payload } = raw;
+    fn deserialize(buf: &[u8]) -> Result<Self, Self::Error> {
+        if T::STRICT {
+            <Self as DeserializeStrict>::check_strict(buf)?;
+        }
 
-            view = &view[nlmsg_align(usize::from(len))..];
+        let mut attrs = vec![];
 
-            let attr = T::deserialize(ty, payload)
-                .map_err(ParseNetlinkAttributeFromBufferError::AttributeDeserializeError)?;
-            attrs.push(attr);
+        for attr in AttrIter::new(buf) {
+            let Attr { ty, flags, payload } = attr?;
+            attrs.push(T::deserialize_with_flags(ty, flags, payload)?);
         }
 
         Ok(attrs)
@@ -88,4 +385,6 @@ impl<T: Deserialize> NetlinkPayloadResponse for Vec<T> {
 #[cfg(feature = "nldl_derive")]
 pub use nldl_derive::NetlinkAttributeDeserializable as Deserialize;
 #[cfg(feature = "nldl_derive")]
+pub use nldl_derive::NetlinkAttributeSchema as Schema;
+#[cfg(feature = "nldl_derive")]
 pub use nldl_derive::NetlinkAttributeSerializable as Serialize;
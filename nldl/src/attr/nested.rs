@@ -1,11 +1,19 @@
+use super::deserialize_attribute;
 use super::Deserialize;
 use super::DeserializeError;
+use super::ParseNetlinkAttributeFromBufferError;
+use super::PolicyError;
 use super::Serialize;
+use crate::linux::NLA_F_NESTED;
 use crate::message::NetlinkPayloadRequest;
 use crate::message::NetlinkPayloadResponse;
+use crate::SerializeError;
+use bytes::BufMut;
 
 /// Wraps zero or more child netlink attributes. The Netlink attribute type ID
-/// (`nla_type`) for this container will always be `0`.
+/// (`nla_type`) for this container will always be `0`, with the
+/// `NLA_F_NESTED` flag set when serialized. See [NlaArray](super::NlaArray)
+/// for arrays that number their elements.
 #[derive(Debug, PartialEq, Eq)]
 pub struct Nested<T>(pub Vec<T>);
 
@@ -13,11 +21,20 @@ pub const NESTED_ATTR_NLA_TYPE: u16 = 0;
 
 impl<T: Serialize> Serialize for Nested<T> {
     fn get_type(&self) -> u16 {
-        NESTED_ATTR_NLA_TYPE
+        NESTED_ATTR_NLA_TYPE | NLA_F_NESTED
     }
 
-    fn serialize_payload(&self, buf: &mut Vec<u8>) {
-        NetlinkPayloadRequest::serialize(&self.0, buf);
+    fn serialized_payload_len(&self) -> usize {
+        NetlinkPayloadRequest::serialized_len(&self.0)
+    }
+
+    fn serialize_payload<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        NetlinkPayloadRequest::serialize(&self.0, buf)
+    }
+
+    fn validate_payload(&self) -> Result<(), PolicyError> {
+        NetlinkPayloadRequest::validate(&self.0)
+            .map_err(|err| err.within("Nested", NESTED_ATTR_NLA_TYPE))
     }
 }
 
@@ -33,3 +50,11 @@ impl<T: Deserialize> Deserialize for Nested<T> {
         Ok(Self(attributes))
     }
 }
+
+impl<T: Deserialize> NetlinkPayloadResponse for Nested<T> {
+    type Error = ParseNetlinkAttributeFromBufferError;
+
+    fn deserialize(buf: &[u8]) -> Result<Self, Self::Error> {
+        deserialize_attribute(buf)
+    }
+}
@@ -0,0 +1,108 @@
+use super::AttrIter;
+use super::DeserializeBorrowed;
+use super::ParseNetlinkAttributeFromBufferError;
+use crate::linux::NLA_F_NESTED;
+use crate::message::NetlinkPayloadRequest;
+use crate::message::NetlinkPayloadResponseBorrowed;
+use crate::SerializeError;
+use bytes::BufMut;
+use std::fmt;
+use std::marker::PhantomData;
+
+/// A nested attribute set that's decoded lazily from the receive buffer.
+///
+/// Deserializing a `NestedRef` never fails or allocates. Each child attribute
+/// is decoded as `T` when [NestedRef::iter] reaches it.
+pub struct NestedRef<'a, T> {
+    buf: &'a [u8],
+    ty: PhantomData<fn() -> T>,
+}
+
+impl<'a, T> NestedRef<'a, T> {
+    pub fn new(buf: &'a [u8]) -> Self {
+        Self {
+            buf,
+            ty: PhantomData,
+        }
+    }
+
+    /// The payload containing the child attributes.
+    pub fn as_bytes(&self) -> &'a [u8] {
+        self.buf
+    }
+
+    pub fn iter(&self) -> NestedRefIter<'a, T> {
+        NestedRefIter {
+            attrs: AttrIter::new(self.buf),
+            ty: PhantomData,
+        }
+    }
+}
+
+// Implemented manually since the derived versions would place bounds on T.
+impl<T> Clone for NestedRef<'_, T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for NestedRef<'_, T> {}
+
+impl<T> fmt::Debug for NestedRef<'_, T> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("NestedRef").field(&self.buf).finish()
+    }
+}
+
+impl<T> PartialEq for NestedRef<'_, T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.buf == other.buf
+    }
+}
+
+impl<T> Eq for NestedRef<'_, T> {}
+
+impl<'a, T: DeserializeBorrowed<'a>> IntoIterator for NestedRef<'a, T> {
+    type Item = Result<T, ParseNetlinkAttributeFromBufferError>;
+    type IntoIter = NestedRefIter<'a, T>;
+
+    fn into_iter(self) -> Self::IntoIter {
+        self.iter()
+    }
+}
+
+pub struct NestedRefIter<'a, T> {
+    attrs: AttrIter<'a>,
+    ty: PhantomData<fn() -> T>,
+}
+
+impl<'a, T: DeserializeBorrowed<'a>> Iterator for NestedRefIter<'a, T> {
+    type Item = Result<T, ParseNetlinkAttributeFromBufferError>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        let attr = self.attrs.next()?;
+        Some(attr.map_err(Into::into).and_then(|attr| Ok(attr.decode()?)))
+    }
+}
+
+/// Child attributes are written back out unchanged.
+impl<T> NetlinkPayloadRequest for NestedRef<'_, T> {
+    const NLA_FLAGS: u16 = NLA_F_NESTED;
+
+    fn serialized_len(&self) -> usize {
+        self.buf.len()
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        buf.put_slice(self.buf);
+        Ok(())
+    }
+}
+
+impl<'a, T> NetlinkPayloadResponseBorrowed<'a> for NestedRef<'a, T> {
+    type Error = std::convert::Infallible;
+
+    fn deserialize_borrowed(buf: &'a [u8]) -> Result<Self, Self::Error> {
+        Ok(Self::new(buf))
+    }
+}
@@ -0,0 +1,224 @@
+//! Runtime support for `#[nla_policy(..)]` annotations, which mirror the
+//! kernel's `struct nla_policy` tables.
+//!
+//! ```
+//! #[derive(Debug, PartialEq, nldl::attr::Serialize, nldl::attr::Deserialize)]
+//! pub enum ExampleAttribute {
+//!     #[nla_type(1)]
+//!     #[nla_policy(range = 1..=4094)]
+//!     VlanId(u16),
+//!     #[nla_type(2)]
+//!     #[nla_policy(max_strlen = 15)]
+//!     IfName(String),
+//!     #[nla_type(3)]
+//!     #[nla_policy(min_len = 6, max_len = 6)]
+//!     Address(Vec<u8>),
+//!     #[nla_type(_)]
+//!     Unknown(nldl::attr::UnknownAttribute),
+//! }
+//! ```
+//!
+//! Policies are checked while deserializing. Call
+//! [NetlinkPayloadRequest::validate] to check them before sending.
+
+use super::DeserializeError;
+use crate::message::NetlinkPayloadRequest;
+use std::fmt;
+use std::ops::RangeBounds;
+
+/// A single constraint from an attribute's policy that a payload didn't meet.
+#[derive(thiserror::Error, Debug, PartialEq, Eq, Clone)]
+pub enum PolicyViolation {
+    #[error("payload is {len} bytes, shorter than the minimum of {min_len}")]
+    TooShort { len: usize, min_len: usize },
+    #[error("payload is {len} bytes, longer than the maximum of {max_len}")]
+    TooLong { len: usize, max_len: usize },
+    #[error("string is {len} bytes, longer than the maximum of {max_len}")]
+    StringTooLong { len: usize, max_len: usize },
+    #[error("value {value} is outside of the allowed range {range}")]
+    OutOfRange { value: i128, range: &'static str },
+}
+
+/// Identifies one attribute on the way from the outermost attribute to the
+/// one that violated its policy.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub struct AttributePathSegment {
+    pub attribute_struct_name: &'static str,
+
+    /// The Netlink attribute type ID, without flag bits.
+    pub nla_type_id: u16,
+}
+
+impl fmt::Display for AttributePathSegment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}({})", self.attribute_struct_name, self.nla_type_id)
+    }
+}
+
+#[derive(thiserror::Error, Debug, PartialEq, Eq, Clone)]
+#[error("netlink attribute {} violates its policy: {violation}", DisplayPath(.path))]
+pub struct PolicyError {
+    /// Outermost attribute first.
+    pub path: Vec<AttributePathSegment>,
+    pub violation: PolicyViolation,
+}
+
+impl PolicyError {
+    pub fn new(
+        attribute_struct_name: &'static str,
+        nla_type_id: u16,
+        violation: PolicyViolation,
+    ) -> Self {
+        Self {
+            path: vec![AttributePathSegment {
+                attribute_struct_name,
+                nla_type_id,
+            }],
+            violation,
+        }
+    }
+
+    /// Prepends the attribute containing the one that failed validation.
+    pub fn within(mut self, attribute_struct_name: &'static str, nla_type_id: u16) -> Self {
+        self.path.insert(
+            0,
+            AttributePathSegment {
+                attribute_struct_name,
+                nla_type_id,
+            },
+        );
+        self
+    }
+
+    /// Finds the policy violation that caused a deserialization error, if
+    /// any, and reconstructs the path of attributes leading to it.
+    pub fn from_deserialize_error(err: &DeserializeError) -> Option<Self> {
+        let mut path = vec![];
+        let mut current: Option<&(dyn std::error::Error + 'static)> = Some(err);
+
+        while let Some(err) = current {
+            if let Some(err) = err.downcast_ref::<DeserializeError>() {
+                path.push(AttributePathSegment {
+                    attribute_struct_name: err.attribute_struct_name,
+                    nla_type_id: err.nla_type_id,
+                });
+            } else if let Some(violation) = err.downcast_ref::<PolicyViolation>() {
+                return Some(Self {
+                    path,
+                    violation: violation.clone(),
+                });
+            }
+            current = err.source();
+        }
+
+        None
+    }
+}
+
+struct DisplayPath<'a>(&'a [AttributePathSegment]);
+
+impl fmt::Display for DisplayPath<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (i, segment) in self.0.iter().enumerate() {
+            if i > 0 {
+                f.write_str(" > ")?;
+            }
+            write!(f, "{}", segment)?;
+        }
+        Ok(())
+    }
+}
+
+/// Checks a payload's length against `min_len` and `max_len`.
+pub fn check_len(
+    len: usize,
+    min_len: Option<usize>,
+    max_len: Option<usize>,
+) -> Result<(), PolicyViolation> {
+    match (min_len, max_len) {
+        (Some(min_len), _) if len < min_len => Err(PolicyViolation::TooShort { len, min_len }),
+        (_, Some(max_len)) if len > max_len => Err(PolicyViolation::TooLong { len, max_len }),
+        _ => Ok(()),
+    }
+}
+
+/// Checks the length of a received string payload, excluding the trailing
+/// NUL byte and anything after it. Like the kernel's `NLA_STRING`.
+pub fn check_received_strlen(payload: &[u8], max_len: usize) -> Result<(), PolicyViolation> {
+    let len = (payload.iter())
+        .position(|&byte| byte == 0)
+        .unwrap_or(payload.len());
+    check_strlen(len, max_len)
+}
+
+/// Checks the length of a string payload about to be sent. String payloads
+/// are serialized with a trailing NUL byte.
+pub fn check_sent_strlen<T: NetlinkPayloadRequest + ?Sized>(
+    val: &T,
+    max_len: usize,
+) -> Result<(), PolicyViolation> {
+    check_strlen(val.serialized_len().saturating_sub(1), max_len)
+}
+
+fn check_strlen(len: usize, max_len: usize) -> Result<(), PolicyViolation> {
+    if len > max_len {
+        return Err(PolicyViolation::StringTooLong { len, max_len });
+    }
+    Ok(())
+}
+
+/// Checks an integer value against a range such as `1..=4094`.
+pub fn check_range<T, R>(
+    value: &T,
+    range: R,
+    range_str: &'static str,
+) -> Result<(), PolicyViolation>
+where
+    T: PartialOrd + Copy + Into<i128>,
+    R: RangeBounds<T>,
+{
+    if !range.contains(value) {
+        return Err(PolicyViolation::OutOfRange {
+            value: (*value).into(),
+            range: range_str,
+        });
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn checks_len_bounds() {
+        assert_eq!(check_len(4, Some(4), Some(8)), Ok(()));
+        assert_eq!(
+            check_len(3, Some(4), None),
+            Err(PolicyViolation::TooShort { len: 3, min_len: 4 })
+        );
+        assert_eq!(
+            check_len(9, None, Some(8)),
+            Err(PolicyViolation::TooLong { len: 9, max_len: 8 })
+        );
+    }
+
+    #[test]
+    fn received_strlen_excludes_nul() {
+        assert_eq!(check_received_strlen(b"eth0\0", 4), Ok(()));
+        assert_eq!(
+            check_received_strlen(b"eth10\0", 4),
+            Err(PolicyViolation::StringTooLong { len: 5, max_len: 4 })
+        );
+    }
+
+    #[test]
+    fn display_includes_path() {
+        let err = PolicyError::new("Inner", 2, PolicyViolation::TooShort { len: 1, min_len: 4 })
+            .within("Outer", 7);
+        assert_eq!(
+            err.to_string(),
+            "netlink attribute Outer(7) > Inner(2) violates its policy: payload is 1 bytes, shorter than the minimum of 4"
+        );
+    }
+}
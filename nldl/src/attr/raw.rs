@@ -1,3 +1,4 @@
+use crate::linux::NLA_TYPE_MASK;
 use crate::utils::nla_get_u16;
 use crate::utils::ParseNlaIntError;
 use std::mem::size_of;
@@ -9,7 +10,11 @@ use std::mem::size_of;
 #[derive(Debug, PartialEq, Eq)]
 pub(crate) struct RawNetlinkAttribute<'a> {
     pub len: u16,
+    /// The attribute type ID with the `NLA_F_NESTED` and `NLA_F_NET_BYTEORDER`
+    /// flag bits masked off.
     pub ty: u16,
+    /// The flag bits of the `nla_type` header field.
+    pub flags: u16,
     pub payload: &'a [u8],
 }
 
@@ -17,6 +22,8 @@ pub(crate) struct RawNetlinkAttribute<'a> {
 pub enum ParseRawNetlinkAttributeError {
     #[error("Found a netlink attribute with an incomplete header. Saw {len} bytes, but at least {} were expected", size_of::<libc::nlattr>())]
     IncompleteHeader { len: usize },
+    #[error("Found a netlink attribute whose header declares a length of {nla_len} bytes, which is shorter than the {} byte header", size_of::<libc::nlattr>())]
+    InvalidLength { nla_len: u16 },
     #[error("Found a netlink attribute with an insufficiently sized payload buffer. Expected a payload with {expected} bytes (determined from the header) but saw {actual} bytes.")]
     UnexpectedEndOfPayloadBuffer { actual: usize, expected: usize },
     #[error(transparent)]
@@ -40,9 +47,13 @@ impl<'a> TryFrom<&'a [u8]> for RawNetlinkAttribute<'a> {
         let (header_bytes, remaining) = buf.split_at(size_of::<libc::nlattr>());
 
         let len = nla_get_u16(&header_bytes[0..size_of::<u16>()])?;
-        let ty = nla_get_u16(&header_bytes[size_of::<u16>()..2 * size_of::<u16>()])?;
+        let nla_type = nla_get_u16(&header_bytes[size_of::<u16>()..2 * size_of::<u16>()])?;
+        let ty = nla_type & NLA_TYPE_MASK;
+        let flags = nla_type & !NLA_TYPE_MASK;
         let payload = {
-            let payload_len = usize::from(len) - size_of::<libc::nlattr>();
+            let payload_len = usize::from(len)
+                .checked_sub(size_of::<libc::nlattr>())
+                .ok_or(Self::Error::InvalidLength { nla_len: len })?;
             if remaining.len() < payload_len {
                 return Err(Self::Error::UnexpectedEndOfPayloadBuffer {
                     actual: remaining.len(),
@@ -53,7 +64,12 @@ impl<'a> TryFrom<&'a [u8]> for RawNetlinkAttribute<'a> {
             &remaining[..payload_len]
         };
 
-        Ok(Self { len, ty, payload })
+        Ok(Self {
+            len,
+            ty,
+            flags,
+            payload,
+        })
     }
 }
 
@@ -70,6 +86,7 @@ mod tests {
             Ok(RawNetlinkAttribute {
                 len: 7,
                 ty: 0,
+                flags: 0,
                 payload: &[1, 1, 1]
             })
         );
@@ -82,12 +99,36 @@ mod tests {
             Ok(RawNetlinkAttribute {
                 len: 8,
                 ty: 1,
+                flags: 0,
                 payload: &[1, 1, 1, 1]
             }),
             "Only first attribute should be read from the buffer, safely ignoring extra data. This is the stated behavior in the method's documentation."
         );
     }
 
+    #[test]
+    fn flags_masked_from_type() {
+        assert_eq!(
+            RawNetlinkAttribute::try_from(&[8, 0, 6, 0x80, 1, 1, 1, 1][..]),
+            Ok(RawNetlinkAttribute {
+                len: 8,
+                ty: 6,
+                flags: libc::NLA_F_NESTED as u16,
+                payload: &[1, 1, 1, 1]
+            })
+        );
+
+        assert_eq!(
+            RawNetlinkAttribute::try_from(&[8, 0, 6, 0x40, 1, 1, 1, 1][..]),
+            Ok(RawNetlinkAttribute {
+                len: 8,
+                ty: 6,
+                flags: libc::NLA_F_NET_BYTEORDER as u16,
+                payload: &[1, 1, 1, 1]
+            })
+        );
+    }
+
     #[test]
     fn incomplete_header_detection() {
         assert_eq!(
@@ -112,6 +153,19 @@ mod tests {
         ));
     }
 
+    #[test]
+    fn invalid_length_detection() {
+        assert_eq!(
+            RawNetlinkAttribute::try_from(&[0, 0, 0, 0][..]),
+            Err(ParseRawNetlinkAttributeError::InvalidLength { nla_len: 0 })
+        );
+
+        assert_eq!(
+            RawNetlinkAttribute::try_from(&[3, 0, 1, 0, 1, 1, 1, 1][..]),
+            Err(ParseRawNetlinkAttributeError::InvalidLength { nla_len: 3 })
+        );
+    }
+
     #[test]
     fn incomplete_payload_detection() {
         assert_eq!(
@@ -119,6 +173,7 @@ mod tests {
             Ok(RawNetlinkAttribute {
                 len: 8,
                 ty: 0,
+                flags: 0,
                 payload: &[1, 1, 1, 1]
             })
         );
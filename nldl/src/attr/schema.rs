@@ -0,0 +1,117 @@
+//! Static descriptions of attribute enums and structs, generated by
+//! `#[derive(nldl::attr::Schema)]`. Useful for tools that print, fuzz or
+//! document attributes without hardcoding their type ids.
+//!
+//! ```
+//! use nldl::attr::schema::PayloadKind;
+//! use nldl::attr::Schema;
+//!
+//! #[derive(nldl::attr::Schema)]
+//! pub enum ExampleAttribute {
+//!     #[nla_type(1)]
+//!     VlanId(u16),
+//!     #[nla_type(2)]
+//!     Children(Vec<ExampleAttribute>),
+//!     #[nla_type(_)]
+//!     Unknown(nldl::attr::UnknownAttribute),
+//! }
+//!
+//! assert_eq!(ExampleAttribute::VLAN_ID_TYPE, 1);
+//!
+//! let schema = ExampleAttribute::schema();
+//! assert_eq!(schema.attribute(2).unwrap().name, "Children");
+//! assert!(matches!(schema.attributes[1].payload, PayloadKind::Nested(_)));
+//! assert_eq!(schema.wildcard, Some("Unknown"));
+//! ```
+//!
+//! Besides the [Schema] impl, the derive adds a `<VARIANT>_TYPE` constant
+//! for each variant (or field) holding its type id. Variants with a type id
+//! range get `<VARIANT>_TYPE_START` and `<VARIANT>_TYPE_END` instead.
+
+use super::nested::NESTED_ATTR_NLA_TYPE;
+use super::Nested;
+use crate::linux::NLA_TYPE_MASK;
+
+pub trait Schema {
+    const SCHEMA: AttributeSetSchema;
+
+    fn schema() -> &'static AttributeSetSchema {
+        &Self::SCHEMA
+    }
+}
+
+/// An attribute enum or struct.
+#[derive(Debug, Clone, Copy)]
+pub struct AttributeSetSchema {
+    /// The Rust type, e.g. `ControllerAttribute`.
+    pub name: &'static str,
+
+    /// Every variant or field with a type id, in declaration order.
+    pub attributes: &'static [AttributeSchema],
+
+    /// The variant marked with `#[nla_type(_)]`, if any.
+    pub wildcard: Option<&'static str>,
+}
+
+impl AttributeSetSchema {
+    /// The attribute with the given `nla_type`. Flag bits are ignored.
+    pub fn attribute(&self, nla_type: u16) -> Option<&AttributeSchema> {
+        let ty = nla_type & NLA_TYPE_MASK;
+        self.attributes
+            .iter()
+            .find(|attr| attr.type_ids.contains(ty))
+    }
+}
+
+/// A single variant or field.
+#[derive(Debug, Clone, Copy)]
+pub struct AttributeSchema {
+    /// The variant or field name, e.g. `FamilyId`.
+    pub name: &'static str,
+    pub type_ids: TypeIds,
+    pub payload: PayloadKind,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TypeIds {
+    /// `#[nla_type(A)]` or `#[nla_type(A | B)]`. The first id is the one
+    /// sent.
+    Ids(&'static [u16]),
+    /// `#[nla_type(A..=B)]`
+    Range { start: u16, end: u16 },
+}
+
+impl TypeIds {
+    pub fn contains(&self, ty: u16) -> bool {
+        match self {
+            Self::Ids(ids) => ids.contains(&ty),
+            Self::Range { start, end } => (*start..=*end).contains(&ty),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy)]
+pub enum PayloadKind {
+    /// Variants without an associated value, such as flags.
+    Empty,
+    /// A payload decoded by its own type, e.g. `u32` or `String`. Holds the
+    /// type as written.
+    Value(&'static str),
+    /// A sequence of attributes, e.g. `Vec<T>` or `NestedRef<'a, T>`.
+    Nested(fn() -> &'static AttributeSetSchema),
+    /// An [NlaArray](super::NlaArray). Each element's payload is described by
+    /// the inner kind.
+    Array(&'static PayloadKind),
+}
+
+impl<T: Schema> Schema for Nested<T> {
+    const SCHEMA: AttributeSetSchema = AttributeSetSchema {
+        name: "Nested",
+        attributes: &[AttributeSchema {
+            name: "Nested",
+            type_ids: TypeIds::Ids(&[NESTED_ATTR_NLA_TYPE]),
+            payload: PayloadKind::Nested(T::schema),
+        }],
+        wildcard: None,
+    };
+}
@@ -0,0 +1,152 @@
+use super::AttrIter;
+use super::Deserialize;
+use super::ParseNetlinkAttributeFromBufferError;
+use super::ParseRawNetlinkAttributeError;
+use super::StrictError;
+use crate::linux::NLA_TYPE_MASK;
+use crate::message::NetlinkPayloadResponseBorrowed;
+
+/// A buffer of netlink attributes that can be checked like the kernel's
+/// `NL_VALIDATE_STRICT` before it's deserialized.
+///
+/// Implemented for `Vec<T>` of attribute enums and for structs deriving
+/// `Deserialize`. Types marked with `#[nldl(strict)]` are always checked. Use
+/// [deserialize_strict] to check any other type for a single call.
+pub trait DeserializeStrict {
+    fn check_strict(buf: &[u8]) -> Result<(), ParseNetlinkAttributeFromBufferError>;
+}
+
+/// Deserializes a buffer of attributes, rejecting unknown type IDs, duplicate
+/// attributes and trailing bytes.
+///
+/// Strictness applies to this buffer only. Nested attribute sets are checked
+/// if their own type is marked with `#[nldl(strict)]`.
+pub fn deserialize_strict<'a, T>(buf: &'a [u8]) -> Result<T, ParseNetlinkAttributeFromBufferError>
+where
+    T: DeserializeStrict
+        + NetlinkPayloadResponseBorrowed<'a, Error = ParseNetlinkAttributeFromBufferError>,
+{
+    T::check_strict(buf)?;
+    T::deserialize_borrowed(buf)
+}
+
+/// Walks a buffer of attributes and fails on the first one that
+/// `is_known_type` rejects or that appears twice without `may_repeat`
+/// allowing it. Bytes after the last attribute that are too short to hold an
+/// attribute header, or whose header declares a length shorter than the
+/// header itself, are reported as [StrictError::TrailingBytes].
+///
+/// Payloads aren't decoded.
+pub fn check_strict<K, R>(
+    buf: &[u8],
+    attribute_struct_name: &'static str,
+    is_known_type: K,
+    may_repeat: R,
+) -> Result<(), ParseNetlinkAttributeFromBufferError>
+where
+    K: Fn(u16) -> bool,
+    R: Fn(u16) -> bool,
+{
+    // One bit for every possible type ID.
+    let mut seen = [0u64; (NLA_TYPE_MASK as usize + 1) / 64];
+
+    let mut attrs = AttrIter::new(buf);
+    loop {
+        let len = attrs.remaining().len();
+        let ty = match attrs.next() {
+            None => break,
+            Some(Ok(attr)) => attr.ty,
+            Some(Err(
+                ParseRawNetlinkAttributeError::IncompleteHeader { .. }
+                | ParseRawNetlinkAttributeError::InvalidLength { .. },
+            )) => return Err(StrictError::TrailingBytes { len }.into()),
+            Some(Err(err)) => return Err(err.into()),
+        };
+
+        if !is_known_type(ty) {
+            return Err(StrictError::UnknownAttribute {
+                attribute_struct_name,
+                nla_type_id: ty,
+            }
+            .into());
+        }
+
+        let (word, bit) = (usize::from(ty) / 64, 1 << (ty % 64));
+        if seen[word] & bit != 0 && !may_repeat(ty) {
+            return Err(StrictError::DuplicateAttribute {
+                attribute_struct_name,
+                nla_type_id: ty,
+            }
+            .into());
+        }
+        seen[word] |= bit;
+    }
+
+    Ok(())
+}
+
+impl<T: Deserialize> DeserializeStrict for Vec<T> {
+    fn check_strict(buf: &[u8]) -> Result<(), ParseNetlinkAttributeFromBufferError> {
+        check_strict(buf, std::any::type_name::<T>(), T::is_known_type, |_| false)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(buf: &[u8]) -> Result<(), ParseNetlinkAttributeFromBufferError> {
+        check_strict(buf, "Test", |ty| ty < 3, |ty| ty == 2)
+    }
+
+    fn strict_error(result: Result<(), ParseNetlinkAttributeFromBufferError>) -> StrictError {
+        result.unwrap_err().strict_error().unwrap()
+    }
+
+    #[test]
+    fn accepts_known_attributes() {
+        assert!(check(&[4, 0, 1, 0, 4, 0, 2, 0, 4, 0, 2, 0]).is_ok());
+    }
+
+    #[test]
+    fn rejects_unknown_attribute() {
+        assert_eq!(
+            strict_error(check(&[4, 0, 1, 0, 4, 0, 3, 0])),
+            StrictError::UnknownAttribute {
+                attribute_struct_name: "Test",
+                nla_type_id: 3
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_duplicate_attribute() {
+        assert_eq!(
+            strict_error(check(&[4, 0, 1, 0, 4, 0, 1, 0])),
+            StrictError::DuplicateAttribute {
+                attribute_struct_name: "Test",
+                nla_type_id: 1
+            }
+        );
+    }
+
+    #[test]
+    fn rejects_trailing_bytes() {
+        assert_eq!(
+            strict_error(check(&[4, 0, 1, 0, 0xff, 0xff])),
+            StrictError::TrailingBytes { len: 2 }
+        );
+    }
+
+    #[test]
+    fn rejects_trailing_header_shorter_than_itself() {
+        assert_eq!(
+            strict_error(check(&[4, 0, 1, 0, 0, 0, 0, 0])),
+            StrictError::TrailingBytes { len: 4 }
+        );
+        assert_eq!(
+            strict_error(check(&[4, 0, 1, 0, 3, 0, 2, 0, 0xff, 0xff])),
+            StrictError::TrailingBytes { len: 6 }
+        );
+    }
+}
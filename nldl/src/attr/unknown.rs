@@ -1,28 +1,110 @@
+use super::deserialize_attribute;
+use super::deserialize_attribute_borrowed;
 use super::Deserialize;
+use super::DeserializeBorrowed;
 use super::DeserializeError;
+use super::ParseNetlinkAttributeFromBufferError;
 use super::Serialize;
+use crate::message::NetlinkPayloadResponse;
+use crate::message::NetlinkPayloadResponseBorrowed;
+use crate::SerializeError;
+use bytes::BufMut;
 
 #[derive(Debug, PartialEq, Eq)]
 pub struct UnknownAttribute {
+    /// The attribute type ID with flag bits masked off.
     pub ty: u16,
+    /// The `NLA_F_*` flag bits of the `nla_type` header field, written back
+    /// unchanged when the attribute is serialized.
+    pub flags: u16,
     pub payload: Vec<u8>,
 }
 
 impl Serialize for UnknownAttribute {
     fn get_type(&self) -> u16 {
-        self.ty
+        self.ty | self.flags
     }
 
-    fn serialize_payload(&self, buf: &mut Vec<u8>) {
-        buf.extend_from_slice(&self.payload[..])
+    fn serialized_payload_len(&self) -> usize {
+        self.payload.len()
+    }
+
+    fn serialize_payload<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        buf.put_slice(&self.payload[..]);
+        Ok(())
     }
 }
 
 impl Deserialize for UnknownAttribute {
     fn deserialize(ty: u16, payload: &[u8]) -> Result<Self, DeserializeError> {
+        Self::deserialize_with_flags(ty, 0, payload)
+    }
+
+    fn deserialize_with_flags(
+        ty: u16,
+        flags: u16,
+        payload: &[u8],
+    ) -> Result<Self, DeserializeError> {
         Ok(Self {
             ty,
+            flags,
             payload: Vec::from(payload),
         })
     }
 }
+
+impl NetlinkPayloadResponse for UnknownAttribute {
+    type Error = ParseNetlinkAttributeFromBufferError;
+
+    fn deserialize(buf: &[u8]) -> Result<Self, Self::Error> {
+        deserialize_attribute(buf)
+    }
+}
+
+/// Borrowing version of [UnknownAttribute] for enums deriving `Deserialize`
+/// with a lifetime parameter.
+#[derive(Debug, PartialEq, Eq)]
+pub struct UnknownAttributeRef<'a> {
+    /// See [UnknownAttribute::ty].
+    pub ty: u16,
+    /// See [UnknownAttribute::flags].
+    pub flags: u16,
+    pub payload: &'a [u8],
+}
+
+impl Serialize for UnknownAttributeRef<'_> {
+    fn get_type(&self) -> u16 {
+        self.ty | self.flags
+    }
+
+    fn serialized_payload_len(&self) -> usize {
+        self.payload.len()
+    }
+
+    fn serialize_payload<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        buf.put_slice(self.payload);
+        Ok(())
+    }
+}
+
+impl<'a> DeserializeBorrowed<'a> for UnknownAttributeRef<'a> {
+    fn deserialize_borrowed(ty: u16, payload: &'a [u8]) -> Result<Self, DeserializeError> {
+        Self::deserialize_borrowed_with_flags(ty, 0, payload)
+    }
+
+    fn deserialize_borrowed_with_flags(
+        ty: u16,
+        flags: u16,
+        payload: &'a [u8],
+    ) -> Result<Self, DeserializeError> {
+        Ok(Self { ty, flags, payload })
+    }
+}
+
+impl<'a> NetlinkPayloadResponseBorrowed<'a> for UnknownAttributeRef<'a> {
+    type Error = ParseNetlinkAttributeFromBufferError;
+
+    fn deserialize_borrowed(buf: &'a [u8]) -> Result<Self, Self::Error> {
+        deserialize_attribute_borrowed(buf)
+    }
+}
@@ -46,51 +46,133 @@
 //! - `nldl` may also provide a common runtime library for sending/receiving Netlink messages derived from the library.
 //! - `serde` allows data structures to be serialized/deserialized into multiple different formats (e.g. json, toml). This flexibility may not be valuable for data structures specific to the Netlink protocol.
 
+use bytes::BufMut;
+use linux::nla_align;
 use std::mem::size_of;
+use utils::nla_put_u16;
 
 pub mod attr;
 mod linux;
 pub mod message;
 pub mod utils;
 
+// Generated code and custom payload types write through bytes::BufMut.
+pub use bytes;
+
+#[cfg(feature = "nldl_derive")]
+pub use nldl_derive::NetlinkEnum as Enum;
+#[cfg(feature = "nldl_derive")]
+pub use nldl_derive::NetlinkStruct as Struct;
+
 use message::*;
 
-pub fn serialize<T: NetlinkPayloadRequest>(message: &NetlinkMessageRequest<T>) -> Vec<u8> {
-    let mut bytes = vec![];
-    message.serialize(&mut bytes);
-    bytes
+pub fn serialize<T: NetlinkPayloadRequest>(
+    message: &NetlinkMessageRequest<T>,
+) -> Result<Vec<u8>, SerializeError> {
+    let mut bytes = Vec::with_capacity(message.serialized_len());
+    message.serialize(&mut bytes)?;
+    Ok(bytes)
 }
 
-fn write_to_buf_with_prefixed_u32_len<F>(buf: &mut Vec<u8>, write: F)
-where
-    F: FnOnce(&mut Vec<u8>),
-{
-    let num_bytes_before = buf.len();
-    buf.extend_from_slice(&[0u8; size_of::<u32>()]);
-    let len_bytes_range = num_bytes_before..buf.len();
+#[derive(thiserror::Error, Debug, PartialEq, Eq)]
+pub enum SerializeError {
+    #[error("Attribute {ty} ({attribute}) is {len} bytes long, which exceeds the maximum nla_len of {}.", u16::MAX)]
+    AttributeTooLarge {
+        /// The Rust type being serialized, e.g. `WgDeviceAttribute`.
+        attribute: &'static str,
+        /// The attribute type ID with flag bits masked off.
+        ty: u16,
+        /// The length including the attribute header.
+        len: usize,
+    },
+    #[error(
+        "The message is {len} bytes long, which exceeds the maximum nlmsg_len of {}.",
+        u32::MAX
+    )]
+    MessageTooLarge { len: usize },
+    #[error(
+        "Serializing the message requires {needed} bytes, but the buffer only has {available}."
+    )]
+    BufferTooSmall { needed: usize, available: usize },
+    #[error("An item of {len} bytes can't fit in a fragment of at most {max_len} bytes.")]
+    ItemTooLarge { len: usize, max_len: usize },
+    /// Returned for the `#[nla_value(_)]` variant of an [Enum] holding a value
+    /// that doesn't fit the enum's integer width, or that belongs to a named
+    /// variant and so wouldn't deserialize back into the same variant.
+    #[error("{value} can't be sent as an unknown value of {enum_name}. It's either out of range or has a named variant.")]
+    InvalidEnumValue { enum_name: &'static str, value: u32 },
+    /// Returned for a variant declared with a range of type ids, e.g.
+    /// `#[nla_type(0x100..=0x1ff)]`, holding an id outside that range.
+    #[error(
+        "Attribute {ty} ({attribute}) is outside the variant's type id range {start}..={end}."
+    )]
+    TypeIdOutOfRange {
+        /// The Rust type being serialized, e.g. `WgDeviceAttribute`.
+        attribute: &'static str,
+        ty: u16,
+        start: u16,
+        end: u16,
+    },
+    /// Returned for an [NlaArray](attr::NlaArray) element whose index doesn't
+    /// fit in the `u16` attribute type ID it's sent as.
+    #[error(
+        "NlaArray index {index} exceeds the maximum attribute type ID of {}.",
+        u16::MAX
+    )]
+    ArrayIndexTooLarge { index: usize },
+}
 
-    write(buf);
+/// Serializes a message into the start of a caller-provided buffer and returns
+/// the number of bytes written. Useful for reusing one buffer across requests.
+pub fn serialize_into<T: NetlinkPayloadRequest>(
+    message: &NetlinkMessageRequest<T>,
+    buf: &mut [u8],
+) -> Result<usize, SerializeError> {
+    let needed = message.serialized_len();
+    if buf.len() < needed {
+        return Err(SerializeError::BufferTooSmall {
+            needed,
+            available: buf.len(),
+        });
+    }
 
-    let num_bytes_after = buf.len();
-    // TODO: Propagate this error properly
-    let message_len = u32::try_from(num_bytes_after - num_bytes_before).unwrap();
+    let mut remaining = &mut buf[..needed];
+    message.serialize(&mut remaining)?;
+    Ok(needed)
+}
 
-    buf.splice(len_bytes_range, message_len.to_ne_bytes().iter().cloned());
+/// The number of bytes [write_attribute] writes for a payload of `payload_len`
+/// bytes, including trailing padding.
+fn attribute_len(payload_len: usize) -> usize {
+    nla_align(size_of::<libc::nlattr>() + payload_len)
 }
 
-fn write_to_buf_with_prefixed_u16_len<F>(buf: &mut Vec<u8>, write: F)
+/// Writes an attribute header followed by the payload. `attribute` names the
+/// type being serialized for [SerializeError::AttributeTooLarge].
+fn write_attribute<B, F>(
+    buf: &mut B,
+    attribute: &'static str,
+    ty: u16,
+    payload_len: usize,
+    write: F,
+) -> Result<(), SerializeError>
 where
-    F: FnOnce(&mut Vec<u8>),
+    B: BufMut,
+    F: FnOnce(&mut B) -> Result<(), SerializeError>,
 {
-    let num_bytes_before = buf.len();
-    buf.extend_from_slice(&[0u8; size_of::<u16>()]);
-    let len_bytes_range = num_bytes_before..buf.len();
-
-    write(buf);
+    let len = size_of::<libc::nlattr>() + payload_len;
+    let nla_len = u16::try_from(len).map_err(|_| SerializeError::AttributeTooLarge {
+        attribute,
+        ty: ty & linux::NLA_TYPE_MASK,
+        len,
+    })?;
+    nla_put_u16(buf, nla_len);
+    nla_put_u16(buf, ty);
 
-    let num_bytes_after = buf.len();
-    // TODO: Propagate this error properly
-    let message_len = u16::try_from(num_bytes_after - num_bytes_before).unwrap();
+    write(buf)?;
 
-    buf.splice(len_bytes_range, message_len.to_ne_bytes().iter().cloned());
+    // Similar to nla_put, the attribute length excludes padding, but the next
+    // attribute still starts on an aligned offset.
+    buf.put_bytes(0, nla_align(len) - len);
+    Ok(())
 }
@@ -6,3 +6,14 @@ pub const NLMSG_ALIGNTO: usize = libc::NLA_ALIGNTO as usize;
 pub const fn nlmsg_align(len: usize) -> usize {
     (len + NLMSG_ALIGNTO - 1) & !(NLMSG_ALIGNTO - 1)
 }
+
+pub const NLA_ALIGNTO: usize = libc::NLA_ALIGNTO as usize;
+
+// From netlink.h
+pub const fn nla_align(len: usize) -> usize {
+    (len + NLA_ALIGNTO - 1) & !(NLA_ALIGNTO - 1)
+}
+
+pub const NLA_F_NESTED: u16 = libc::NLA_F_NESTED as u16;
+pub const NLA_F_NET_BYTEORDER: u16 = libc::NLA_F_NET_BYTEORDER as u16;
+pub const NLA_TYPE_MASK: u16 = libc::NLA_TYPE_MASK as u16;
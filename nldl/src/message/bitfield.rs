@@ -0,0 +1,96 @@
+use super::NetlinkPayloadRequest;
+use super::NetlinkPayloadResponse;
+use crate::utils::nla_get_u32;
+use crate::utils::nla_put_u32;
+use crate::utils::ParseNlaIntError;
+use crate::SerializeError;
+use bytes::BufMut;
+use std::fmt::Debug;
+use std::mem::size_of;
+
+/// Values that can be stored in a [Bitfield32].
+pub trait Bitfield32Value: Debug + PartialEq {
+    fn from_bits(bits: u32) -> Self;
+    fn bits(&self) -> u32;
+}
+
+impl Bitfield32Value for u32 {
+    fn from_bits(bits: u32) -> Self {
+        bits
+    }
+
+    fn bits(&self) -> u32 {
+        *self
+    }
+}
+
+/// Payload of attributes with the NLA_BITFIELD32 policy, such as
+/// `TCA_ACT_FLAGS`. Mirrors `struct nla_bitfield32`.
+///
+/// Only the bits set in `selector` are meaningful in `value`. This allows
+/// requests to change some flags without affecting others.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Bitfield32<F = u32> {
+    pub value: F,
+    pub selector: F,
+}
+
+const BITFIELD32_LEN: usize = 2 * size_of::<u32>();
+
+impl<F: Bitfield32Value> NetlinkPayloadRequest for Bitfield32<F> {
+    fn serialized_len(&self) -> usize {
+        BITFIELD32_LEN
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        nla_put_u32(buf, self.value.bits());
+        nla_put_u32(buf, self.selector.bits());
+        Ok(())
+    }
+}
+
+impl<F: Bitfield32Value> NetlinkPayloadResponse for Bitfield32<F> {
+    type Error = ParseNlaIntError;
+
+    fn deserialize(buf: &[u8]) -> Result<Self, Self::Error> {
+        if buf.len() != BITFIELD32_LEN {
+            return Err(ParseNlaIntError::InvalidLength {
+                expected: BITFIELD32_LEN,
+                found: buf.len(),
+            });
+        }
+
+        Ok(Self {
+            value: F::from_bits(nla_get_u32(&buf[..size_of::<u32>()])?),
+            selector: F::from_bits(nla_get_u32(&buf[size_of::<u32>()..])?),
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let val = Bitfield32 {
+            value: 0x1,
+            selector: 0x3,
+        };
+        let mut buf = vec![];
+        val.serialize(&mut buf).unwrap();
+        assert_eq!(buf.len(), 8);
+        assert_eq!(Bitfield32::deserialize(&buf), Ok(val));
+    }
+
+    #[test]
+    fn invalid_length() {
+        assert_eq!(
+            Bitfield32::<u32>::deserialize(&[0; 4]),
+            Err(ParseNlaIntError::InvalidLength {
+                expected: 8,
+                found: 4
+            })
+        );
+    }
+}
@@ -0,0 +1,102 @@
+use super::NetlinkPayloadRequest;
+use super::NetlinkPayloadResponseBorrowed;
+use crate::utils::nla_get_str;
+use crate::utils::nla_put_string;
+use crate::utils::nla_string_len;
+use crate::utils::NlaGetStringError;
+use crate::SerializeError;
+use bytes::BufMut;
+use std::ffi::CStr;
+use std::ffi::FromBytesWithNulError;
+
+impl NetlinkPayloadRequest for &[u8] {
+    fn serialized_len(&self) -> usize {
+        self.len()
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        buf.put_slice(self);
+        Ok(())
+    }
+}
+
+impl<'a> NetlinkPayloadResponseBorrowed<'a> for &'a [u8] {
+    type Error = std::convert::Infallible;
+
+    fn deserialize_borrowed(buf: &'a [u8]) -> Result<Self, Self::Error> {
+        Ok(buf)
+    }
+}
+
+impl NetlinkPayloadRequest for &str {
+    fn serialized_len(&self) -> usize {
+        nla_string_len(self)
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        nla_put_string(buf, self);
+        Ok(())
+    }
+}
+
+/// Same null terminator handling as [String]: the payload must be
+/// null-terminated. Use [NlaStr](super::NlaStr) for attributes that may omit
+/// the terminator.
+impl<'a> NetlinkPayloadResponseBorrowed<'a> for &'a str {
+    type Error = NlaGetStringError;
+
+    fn deserialize_borrowed(buf: &'a [u8]) -> Result<Self, Self::Error> {
+        nla_get_str(buf)
+    }
+}
+
+impl NetlinkPayloadRequest for &CStr {
+    fn serialized_len(&self) -> usize {
+        self.to_bytes_with_nul().len()
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        buf.put_slice(self.to_bytes_with_nul());
+        Ok(())
+    }
+}
+
+/// Same null terminator handling as [CString](std::ffi::CString).
+impl<'a> NetlinkPayloadResponseBorrowed<'a> for &'a CStr {
+    type Error = FromBytesWithNulError;
+
+    fn deserialize_borrowed(buf: &'a [u8]) -> Result<Self, Self::Error> {
+        CStr::from_bytes_with_nul(buf)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn str_borrows_from_buffer() {
+        let buf = b"eth0\0";
+        let val = <&str>::deserialize_borrowed(buf).unwrap();
+        assert_eq!(val, "eth0");
+        assert_eq!(val.as_ptr(), buf.as_ptr());
+    }
+
+    #[test]
+    fn str_requires_terminator() {
+        assert!(matches!(
+            <&str>::deserialize_borrowed(b"eth0"),
+            Err(NlaGetStringError::NotNullTerminated(b'0'))
+        ));
+        assert!(matches!(
+            <&str>::deserialize_borrowed(b""),
+            Err(NlaGetStringError::NullBuffer)
+        ));
+    }
+
+    #[test]
+    fn blanket_impl_covers_owned_types() {
+        let val = <u32 as NetlinkPayloadResponseBorrowed>::deserialize_borrowed(&[1, 0, 0, 0]);
+        assert_eq!(val, Ok(u32::from_ne_bytes([1, 0, 0, 0])));
+    }
+}
@@ -0,0 +1,87 @@
+use super::NetlinkPayloadRequest;
+use super::NetlinkPayloadResponse;
+use crate::linux::NLA_F_NET_BYTEORDER;
+use crate::utils::nla_get_be16;
+use crate::utils::nla_get_be32;
+use crate::utils::nla_get_be64;
+use crate::utils::nla_put_be16;
+use crate::utils::nla_put_be32;
+use crate::utils::nla_put_be64;
+use crate::utils::ParseNlaIntError;
+use crate::SerializeError;
+use bytes::BufMut;
+use std::mem::size_of;
+
+/// An integer sent in network byte order (big-endian) rather than the host's
+/// native byte order.
+///
+/// The wrapped value is always in host byte order. Conversion happens during
+/// serialization, which also sets `NLA_F_NET_BYTEORDER` on the attribute.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+#[repr(transparent)]
+pub struct Be<T>(pub T);
+
+impl<T> From<T> for Be<T> {
+    fn from(val: T) -> Self {
+        Self(val)
+    }
+}
+
+macro_rules! impl_be_payload {
+    ($int_type: ident, $get_func: ident, $put_func: ident) => {
+        impl NetlinkPayloadRequest for Be<$int_type> {
+            const NLA_FLAGS: u16 = NLA_F_NET_BYTEORDER;
+
+            fn serialized_len(&self) -> usize {
+                size_of::<$int_type>()
+            }
+
+            fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+                $put_func(buf, self.0);
+                Ok(())
+            }
+        }
+
+        impl NetlinkPayloadResponse for Be<$int_type> {
+            type Error = ParseNlaIntError;
+
+            fn deserialize(buf: &[u8]) -> Result<Self, Self::Error> {
+                $get_func(buf).map(Be)
+            }
+        }
+    };
+}
+
+impl_be_payload!(u16, nla_get_be16, nla_put_be16);
+impl_be_payload!(u32, nla_get_be32, nla_put_be32);
+impl_be_payload!(u64, nla_get_be64, nla_put_be64);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn serializes_in_network_byte_order() {
+        let mut buf = vec![];
+        Be(0x1234u16).serialize(&mut buf).unwrap();
+        assert_eq!(buf, [0x12, 0x34]);
+    }
+
+    #[test]
+    fn deserializes_in_network_byte_order() {
+        let val = <Be<u32> as NetlinkPayloadResponse>::deserialize(&[0x00, 0x00, 0x12, 0xb5]);
+        assert_eq!(val, Ok(Be(4789)));
+    }
+
+    #[test]
+    fn invalid_length() {
+        let val = <Be<u64> as NetlinkPayloadResponse>::deserialize(&[0x00, 0x01]);
+        assert_eq!(
+            val,
+            Err(ParseNlaIntError::InvalidLength {
+                expected: 8,
+                found: 2
+            })
+        );
+    }
+}
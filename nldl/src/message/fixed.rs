@@ -0,0 +1,146 @@
+use super::Be;
+use crate::SerializeError;
+use bytes::BufMut;
+use std::mem::align_of;
+use std::mem::size_of;
+
+/// Types with a fixed size and alignment on the wire, such as the fields of C
+/// structs sent as attribute payloads (`rtnl_link_stats64`, `ifa_cacheinfo`).
+///
+/// Implemented for `#[repr(C)]` structs by `#[derive(nldl::Struct)]`.
+pub trait FixedLayout: Sized {
+    /// The size in bytes, including trailing padding.
+    const SIZE: usize;
+    const ALIGN: usize;
+
+    /// Writes exactly [Self::SIZE] bytes into `buf`, including any padding.
+    fn write_fixed<B: BufMut>(&self, buf: &mut B);
+
+    /// Reads the value from `buf`, which is exactly [Self::SIZE] bytes long.
+    fn read_fixed(buf: &[u8]) -> Self;
+}
+
+#[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+pub enum ParseFixedLayoutError {
+    #[error(
+        "Buffer too short for {}. Expected at least {} bytes found {}.",
+        ty,
+        expected,
+        found
+    )]
+    TooShort {
+        ty: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    #[error(
+        "Invalid buffer length for packed array of {}. {} is not a multiple of {}.",
+        ty,
+        found,
+        element_size
+    )]
+    NotMultipleOfElementSize {
+        ty: &'static str,
+        element_size: usize,
+        found: usize,
+    },
+}
+
+/// Fixed-size payloads can't overflow, so this never fails. It returns a
+/// [Result] to match [NetlinkPayloadRequest::serialize](super::NetlinkPayloadRequest::serialize).
+pub fn serialize_fixed<T: FixedLayout, B: BufMut>(
+    val: &T,
+    buf: &mut B,
+) -> Result<(), SerializeError> {
+    val.write_fixed(buf);
+    Ok(())
+}
+
+/// Newer kernels may append fields to a struct. Any bytes past `T::SIZE` are
+/// ignored.
+pub fn deserialize_fixed<T: FixedLayout>(buf: &[u8]) -> Result<T, ParseFixedLayoutError> {
+    if buf.len() < T::SIZE {
+        return Err(ParseFixedLayoutError::TooShort {
+            ty: std::any::type_name::<T>(),
+            expected: T::SIZE,
+            found: buf.len(),
+        });
+    }
+
+    Ok(T::read_fixed(&buf[..T::SIZE]))
+}
+
+/// Rounds `offset` up to the next multiple of `align`, which must be a power of
+/// two. Used by the derive macro to compute field offsets.
+#[doc(hidden)]
+pub const fn align_up(offset: usize, align: usize) -> usize {
+    (offset + align - 1) & !(align - 1)
+}
+
+#[doc(hidden)]
+pub const fn max_align(a: usize, b: usize) -> usize {
+    if a > b {
+        a
+    } else {
+        b
+    }
+}
+
+macro_rules! impl_fixed_layout_int {
+    ($int_type: ident) => {
+        impl FixedLayout for $int_type {
+            const SIZE: usize = size_of::<$int_type>();
+            const ALIGN: usize = align_of::<$int_type>();
+
+            fn write_fixed<B: BufMut>(&self, buf: &mut B) {
+                buf.put_slice(&self.to_ne_bytes());
+            }
+
+            fn read_fixed(buf: &[u8]) -> Self {
+                let mut arr = [0u8; size_of::<$int_type>()];
+                arr.copy_from_slice(buf);
+                $int_type::from_ne_bytes(arr)
+            }
+        }
+
+        impl FixedLayout for Be<$int_type> {
+            const SIZE: usize = size_of::<$int_type>();
+            const ALIGN: usize = align_of::<$int_type>();
+
+            fn write_fixed<B: BufMut>(&self, buf: &mut B) {
+                buf.put_slice(&self.0.to_be_bytes());
+            }
+
+            fn read_fixed(buf: &[u8]) -> Self {
+                let mut arr = [0u8; size_of::<$int_type>()];
+                arr.copy_from_slice(buf);
+                Be($int_type::from_be_bytes(arr))
+            }
+        }
+    };
+}
+
+impl_fixed_layout_int!(u8);
+impl_fixed_layout_int!(u16);
+impl_fixed_layout_int!(u32);
+impl_fixed_layout_int!(u64);
+impl_fixed_layout_int!(i8);
+impl_fixed_layout_int!(i16);
+impl_fixed_layout_int!(i32);
+impl_fixed_layout_int!(i64);
+
+impl<T: FixedLayout, const N: usize> FixedLayout for [T; N] {
+    const SIZE: usize = T::SIZE * N;
+    const ALIGN: usize = T::ALIGN;
+
+    fn write_fixed<B: BufMut>(&self, buf: &mut B) {
+        for val in self {
+            val.write_fixed(buf);
+        }
+    }
+
+    fn read_fixed(buf: &[u8]) -> Self {
+        let mut chunks = buf.chunks_exact(T::SIZE);
+        [(); N].map(|_| T::read_fixed(chunks.next().unwrap()))
+    }
+}
@@ -0,0 +1,84 @@
+use super::Bitfield32Value;
+use super::NetlinkPayloadRequest;
+use super::NetlinkPayloadResponse;
+use crate::SerializeError;
+use bytes::BufMut;
+use std::fmt::Debug;
+
+/// Wraps a type generated by the [bitflags](https://docs.rs/bitflags) crate so
+/// it can be used as an attribute payload.
+///
+/// Flags are sent as their underlying integer type. Bits the flags type doesn't
+/// define are kept instead of being dropped, so flags added by newer kernels
+/// survive a round trip.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Flags<F>(pub F);
+
+impl<F> From<F> for Flags<F> {
+    fn from(val: F) -> Self {
+        Self(val)
+    }
+}
+
+impl<F> NetlinkPayloadRequest for Flags<F>
+where
+    F: bitflags::Flags,
+    F::Bits: NetlinkPayloadRequest,
+{
+    fn serialized_len(&self) -> usize {
+        self.0.bits().serialized_len()
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        self.0.bits().serialize(buf)
+    }
+}
+
+impl<F> NetlinkPayloadResponse for Flags<F>
+where
+    F: bitflags::Flags + Debug + PartialEq,
+    F::Bits: NetlinkPayloadResponse,
+{
+    type Error = <F::Bits as NetlinkPayloadResponse>::Error;
+
+    fn deserialize(buf: &[u8]) -> Result<Self, Self::Error> {
+        F::Bits::deserialize(buf).map(|bits| Self(F::from_bits_retain(bits)))
+    }
+}
+
+impl<F> Bitfield32Value for Flags<F>
+where
+    F: bitflags::Flags<Bits = u32> + Debug + PartialEq,
+{
+    fn from_bits(bits: u32) -> Self {
+        Self(F::from_bits_retain(bits))
+    }
+
+    fn bits(&self) -> u32 {
+        self.0.bits()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    bitflags::bitflags! {
+        #[derive(Clone, Copy, Debug, PartialEq, Eq)]
+        struct TestFlags: u32 {
+            const A = 0x1;
+            const B = 0x2;
+        }
+    }
+
+    #[test]
+    fn unknown_bits_are_preserved() {
+        let flags = Flags::<TestFlags>::deserialize(&0x81u32.to_ne_bytes()).unwrap();
+        assert!(flags.0.contains(TestFlags::A));
+        assert!(!flags.0.contains(TestFlags::B));
+
+        let mut buf = vec![];
+        flags.serialize(&mut buf).unwrap();
+        assert_eq!(buf, 0x81u32.to_ne_bytes());
+    }
+}
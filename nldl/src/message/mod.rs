@@ -1,28 +1,69 @@
+mod bitfield;
+mod borrowed;
+mod byte_order;
+mod fixed;
+#[cfg(feature = "bitflags")]
+mod flags;
+mod net;
 mod netlink_message_error;
 mod netlink_message_type;
+mod packed;
 mod raw;
+mod string;
 pub mod utils;
 
+pub use self::bitfield::Bitfield32;
+pub use self::bitfield::Bitfield32Value;
+pub use self::byte_order::Be;
+#[doc(hidden)]
+pub use self::fixed::align_up;
+pub use self::fixed::deserialize_fixed;
+#[doc(hidden)]
+pub use self::fixed::max_align;
+pub use self::fixed::serialize_fixed;
+pub use self::fixed::FixedLayout;
+pub use self::fixed::ParseFixedLayoutError;
+#[cfg(feature = "bitflags")]
+pub use self::flags::Flags;
+pub use self::net::MacAddr;
+pub use self::net::ParseNetAddressError;
 pub use self::netlink_message_error::NetlinkErrorMessagePayload;
 pub use self::netlink_message_error::ReadNetlinkErrorMessageError;
 pub use self::netlink_message_type::NetlinkMessageType;
 pub use self::netlink_message_type::NetlinkMessageTypeDeserializeError;
+pub use self::packed::Packed;
+pub use self::string::NlaStr;
+pub use self::string::NlaString;
 
 use self::raw::RawNetlinkMessage;
 use self::raw::ReadRawNetlinkMessageError;
+use super::utils::nla_get_bool;
+use super::utils::nla_get_i16;
+use super::utils::nla_get_i32;
+use super::utils::nla_get_i64;
+use super::utils::nla_get_i8;
 use super::utils::nla_get_string;
 use super::utils::nla_get_u16;
 use super::utils::nla_get_u32;
 use super::utils::nla_get_u64;
 use super::utils::nla_get_u8;
+use super::utils::nla_put_bool;
+use super::utils::nla_put_i16;
+use super::utils::nla_put_i32;
+use super::utils::nla_put_i64;
+use super::utils::nla_put_i8;
 use super::utils::nla_put_string;
 use super::utils::nla_put_u16;
 use super::utils::nla_put_u32;
 use super::utils::nla_put_u64;
 use super::utils::nla_put_u8;
+use super::utils::nla_string_len;
 use super::utils::NlaGetStringError;
 use super::utils::ParseNlaIntError;
-use super::write_to_buf_with_prefixed_u32_len;
+use crate::attr::PolicyError;
+use crate::linux::nlmsg_align;
+use crate::SerializeError;
+use bytes::BufMut;
 use std::fmt::Debug;
 use std::mem::size_of;
 
@@ -37,11 +78,11 @@ pub struct NetlinkMessageHeader {
 }
 
 impl NetlinkMessageHeader {
-    fn serialize(&self, buf: &mut Vec<u8>) {
-        buf.extend_from_slice(&self.ty.to_ne_bytes()[..]);
-        buf.extend_from_slice(&self.flags.to_ne_bytes()[..]);
-        buf.extend_from_slice(&self.seq.to_ne_bytes()[..]);
-        buf.extend_from_slice(&self.pid.to_ne_bytes()[..]);
+    fn serialize<B: BufMut>(&self, buf: &mut B) {
+        buf.put_slice(&self.ty.to_ne_bytes()[..]);
+        buf.put_slice(&self.flags.to_ne_bytes()[..]);
+        buf.put_slice(&self.seq.to_ne_bytes()[..]);
+        buf.put_slice(&self.pid.to_ne_bytes()[..]);
     }
 }
 
@@ -66,12 +107,12 @@ pub struct RawNetlinkMessageHeader {
 }
 
 impl RawNetlinkMessageHeader {
-    fn serialize(&self, buf: &mut Vec<u8>) {
-        self.len.serialize(buf);
-        self.ty.serialize(buf);
-        self.flags.serialize(buf);
-        self.seq.serialize(buf);
-        self.pid.serialize(buf);
+    fn serialize<B: BufMut>(&self, buf: &mut B) {
+        nla_put_u32(buf, self.len);
+        nla_put_u16(buf, self.ty);
+        nla_put_u16(buf, self.flags);
+        nla_put_u32(buf, self.seq);
+        nla_put_u32(buf, self.pid);
     }
 
     fn deserialize(buf: &[u8; size_of::<libc::nlmsghdr>()]) -> Self {
@@ -91,11 +132,29 @@ pub struct NetlinkMessageRequest<T: NetlinkPayloadRequest> {
 }
 
 impl<T: NetlinkPayloadRequest> NetlinkMessageRequest<T> {
-    pub fn serialize(&self, buf: &mut Vec<u8>) {
-        write_to_buf_with_prefixed_u32_len(buf, |buf| {
-            self.header.serialize(buf);
-            self.payload.serialize(buf);
-        });
+    /// The number of bytes [serialize](Self::serialize) writes. Similar to
+    /// nlmsg_end, this includes padding at the end of the message body.
+    pub fn serialized_len(&self) -> usize {
+        nlmsg_align(size_of::<libc::nlmsghdr>() + self.payload.serialized_len())
+    }
+
+    pub fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        let payload_len = self.payload.serialized_len();
+        let unpadded_len = size_of::<libc::nlmsghdr>() + payload_len;
+        let message_len = nlmsg_align(unpadded_len);
+
+        let nlmsg_len = u32::try_from(message_len)
+            .map_err(|_| SerializeError::MessageTooLarge { len: message_len })?;
+        nla_put_u32(buf, nlmsg_len);
+        self.header.serialize(buf);
+        self.payload.serialize(buf)?;
+        buf.put_bytes(0, message_len - unpadded_len);
+        Ok(())
+    }
+
+    /// See [NetlinkPayloadRequest::validate].
+    pub fn validate(&self) -> Result<(), PolicyError> {
+        self.payload.validate()
     }
 }
 
@@ -139,7 +198,26 @@ impl<T: NetlinkPayloadResponse> TryFrom<RawNetlinkMessage<'_>> for NetlinkMessag
 }
 
 pub trait NetlinkPayloadRequest {
-    fn serialize(&self, buf: &mut Vec<u8>);
+    /// Flag bits (`NLA_F_*`) to set in the header of an attribute carrying
+    /// this payload.
+    const NLA_FLAGS: u16 = 0;
+
+    /// The number of bytes [serialize](Self::serialize) writes.
+    fn serialized_len(&self) -> usize;
+
+    /// Writes the payload into `buf`. This may be a `Vec<u8>`, a `&mut [u8]`
+    /// with at least [serialized_len](Self::serialized_len) bytes remaining,
+    /// or any other [BufMut].
+    ///
+    /// Fails if a nested attribute is too long for its 16-bit length field.
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError>;
+
+    /// Checks attributes in the payload against their `#[nla_policy(..)]`
+    /// annotations. Serializing doesn't do this, so call it first to catch
+    /// invalid requests before the kernel rejects them.
+    fn validate(&self) -> Result<(), PolicyError> {
+        Ok(())
+    }
 }
 
 pub trait NetlinkPayloadResponse: Debug + PartialEq + Sized {
@@ -147,57 +225,177 @@ pub trait NetlinkPayloadResponse: Debug + PartialEq + Sized {
     fn deserialize(buf: &[u8]) -> Result<Self, Self::Error>;
 }
 
+/// Like [NetlinkPayloadResponse], but the deserialized value may borrow from
+/// the receive buffer. This avoids copying payloads such as strings and byte
+/// arrays.
+///
+/// Every [NetlinkPayloadResponse] type also implements this trait.
+pub trait NetlinkPayloadResponseBorrowed<'a>: Debug + PartialEq + Sized {
+    type Error: std::error::Error;
+    fn deserialize_borrowed(buf: &'a [u8]) -> Result<Self, Self::Error>;
+}
+
+impl<'a, T: NetlinkPayloadResponse> NetlinkPayloadResponseBorrowed<'a> for T {
+    type Error = T::Error;
+
+    fn deserialize_borrowed(buf: &'a [u8]) -> Result<Self, Self::Error> {
+        T::deserialize(buf)
+    }
+}
+
 impl NetlinkPayloadRequest for () {
-    fn serialize(&self, _buf: &mut Vec<u8>) {}
+    fn serialized_len(&self) -> usize {
+        0
+    }
+
+    fn serialize<B: BufMut>(&self, _buf: &mut B) -> Result<(), SerializeError> {
+        Ok(())
+    }
 }
 
 impl NetlinkPayloadRequest for u8 {
-    fn serialize(&self, buf: &mut Vec<u8>) {
+    fn serialized_len(&self) -> usize {
+        size_of::<u8>()
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
         nla_put_u8(buf, *self);
+        Ok(())
     }
 }
 
 impl NetlinkPayloadRequest for u16 {
-    fn serialize(&self, buf: &mut Vec<u8>) {
+    fn serialized_len(&self) -> usize {
+        size_of::<u16>()
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
         nla_put_u16(buf, *self);
+        Ok(())
     }
 }
 
 impl NetlinkPayloadRequest for u32 {
-    fn serialize(&self, buf: &mut Vec<u8>) {
+    fn serialized_len(&self) -> usize {
+        size_of::<u32>()
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
         nla_put_u32(buf, *self);
+        Ok(())
     }
 }
 
 impl NetlinkPayloadRequest for u64 {
-    fn serialize(&self, buf: &mut Vec<u8>) {
+    fn serialized_len(&self) -> usize {
+        size_of::<u64>()
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
         nla_put_u64(buf, *self);
+        Ok(())
+    }
+}
+
+impl NetlinkPayloadRequest for i8 {
+    fn serialized_len(&self) -> usize {
+        size_of::<i8>()
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        nla_put_i8(buf, *self);
+        Ok(())
+    }
+}
+
+impl NetlinkPayloadRequest for i16 {
+    fn serialized_len(&self) -> usize {
+        size_of::<i16>()
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        nla_put_i16(buf, *self);
+        Ok(())
+    }
+}
+
+impl NetlinkPayloadRequest for i32 {
+    fn serialized_len(&self) -> usize {
+        size_of::<i32>()
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        nla_put_i32(buf, *self);
+        Ok(())
+    }
+}
+
+impl NetlinkPayloadRequest for i64 {
+    fn serialized_len(&self) -> usize {
+        size_of::<i64>()
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        nla_put_i64(buf, *self);
+        Ok(())
+    }
+}
+
+impl NetlinkPayloadRequest for bool {
+    fn serialized_len(&self) -> usize {
+        size_of::<u8>()
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        nla_put_bool(buf, *self);
+        Ok(())
     }
 }
 
 impl NetlinkPayloadRequest for Vec<u8> {
-    fn serialize(&self, buf: &mut Vec<u8>) {
-        buf.extend(self)
+    fn serialized_len(&self) -> usize {
+        self.len()
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        buf.put_slice(self);
+        Ok(())
     }
 }
 
 impl<const N: usize> NetlinkPayloadRequest for [u8; N] {
-    fn serialize(&self, buf: &mut Vec<u8>) {
-        buf.extend(self)
+    fn serialized_len(&self) -> usize {
+        N
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        buf.put_slice(self);
+        Ok(())
     }
 }
 
 impl NetlinkPayloadRequest for String {
-    fn serialize(&self, buf: &mut Vec<u8>) {
+    fn serialized_len(&self) -> usize {
+        nla_string_len(self)
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
         nla_put_string(buf, self);
+        Ok(())
     }
 }
 
 impl<T: NetlinkPayloadRequest> NetlinkPayloadRequest for Option<T> {
-    fn serialize(&self, buf: &mut Vec<u8>) {
+    const NLA_FLAGS: u16 = T::NLA_FLAGS;
+
+    fn serialized_len(&self) -> usize {
+        self.as_ref().map_or(0, T::serialized_len)
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
         match self {
             Some(val) => val.serialize(buf),
-            None => {}
+            None => Ok(()),
         }
     }
 }
@@ -242,6 +440,46 @@ impl NetlinkPayloadResponse for u64 {
     }
 }
 
+impl NetlinkPayloadResponse for i8 {
+    type Error = ParseNlaIntError;
+
+    fn deserialize(buf: &[u8]) -> Result<Self, Self::Error> {
+        nla_get_i8(buf)
+    }
+}
+
+impl NetlinkPayloadResponse for i16 {
+    type Error = ParseNlaIntError;
+
+    fn deserialize(buf: &[u8]) -> Result<Self, Self::Error> {
+        nla_get_i16(buf)
+    }
+}
+
+impl NetlinkPayloadResponse for i32 {
+    type Error = ParseNlaIntError;
+
+    fn deserialize(buf: &[u8]) -> Result<Self, Self::Error> {
+        nla_get_i32(buf)
+    }
+}
+
+impl NetlinkPayloadResponse for i64 {
+    type Error = ParseNlaIntError;
+
+    fn deserialize(buf: &[u8]) -> Result<Self, Self::Error> {
+        nla_get_i64(buf)
+    }
+}
+
+impl NetlinkPayloadResponse for bool {
+    type Error = ParseNlaIntError;
+
+    fn deserialize(buf: &[u8]) -> Result<Self, Self::Error> {
+        nla_get_bool(buf)
+    }
+}
+
 impl NetlinkPayloadResponse for Vec<u8> {
     type Error = std::convert::Infallible;
 
@@ -258,6 +496,9 @@ impl<const N: usize> NetlinkPayloadResponse for [u8; N] {
     }
 }
 
+/// Matches the kernel's `NLA_NUL_STRING` policy: the payload must be
+/// null-terminated. Use [NlaString] for `NLA_STRING` attributes, which may omit
+/// the terminator.
 impl NetlinkPayloadResponse for String {
     type Error = NlaGetStringError;
 
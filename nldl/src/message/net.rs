@@ -0,0 +1,317 @@
+use super::NetlinkPayloadRequest;
+use super::NetlinkPayloadResponse;
+use crate::utils::nla_get_be16;
+use crate::utils::nla_get_u16;
+use crate::utils::nla_get_u32;
+use crate::utils::nla_put_be16;
+use crate::utils::nla_put_u16;
+use crate::utils::nla_put_u32;
+use crate::SerializeError;
+use bytes::BufMut;
+use std::fmt;
+use std::mem::size_of;
+use std::net::IpAddr;
+use std::net::Ipv4Addr;
+use std::net::Ipv6Addr;
+use std::net::SocketAddrV4;
+use std::net::SocketAddrV6;
+
+const IPV4_ADDR_LEN: usize = 4;
+const IPV6_ADDR_LEN: usize = 16;
+const MAC_ADDR_LEN: usize = 6;
+const SOCKADDR_IN_LEN: usize = size_of::<libc::sockaddr_in>();
+const SOCKADDR_IN6_LEN: usize = size_of::<libc::sockaddr_in6>();
+const AF_INET: u16 = libc::AF_INET as u16;
+const AF_INET6: u16 = libc::AF_INET6 as u16;
+
+#[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
+pub enum ParseNetAddressError {
+    #[error(
+        "Invalid buffer length for {}. Expected {} found {}.",
+        ty,
+        expected,
+        found
+    )]
+    InvalidLength {
+        ty: &'static str,
+        expected: usize,
+        found: usize,
+    },
+    #[error(
+        "Invalid buffer length for IpAddr. Expected {} or {} found {}.",
+        IPV4_ADDR_LEN,
+        IPV6_ADDR_LEN,
+        .0
+    )]
+    InvalidIpAddrLength(usize),
+    #[error("Unexpected address family. Expected {} found {}.", expected, found)]
+    UnexpectedFamily { expected: u16, found: u16 },
+}
+
+fn check_len(ty: &'static str, buf: &[u8], expected: usize) -> Result<(), ParseNetAddressError> {
+    if buf.len() != expected {
+        return Err(ParseNetAddressError::InvalidLength {
+            ty,
+            expected,
+            found: buf.len(),
+        });
+    }
+    Ok(())
+}
+
+fn check_family(buf: &[u8], expected: u16) -> Result<(), ParseNetAddressError> {
+    // The buffer length was checked by the caller, so this can't fail.
+    let found = nla_get_u16(&buf[0..2]).unwrap();
+    if found != expected {
+        return Err(ParseNetAddressError::UnexpectedFamily { expected, found });
+    }
+    Ok(())
+}
+
+/// A 6-byte link-layer (Ethernet) address, such as the payload of
+/// `IFLA_ADDRESS` or `NDA_LLADDR`.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct MacAddr(pub [u8; MAC_ADDR_LEN]);
+
+impl From<[u8; MAC_ADDR_LEN]> for MacAddr {
+    fn from(octets: [u8; MAC_ADDR_LEN]) -> Self {
+        Self(octets)
+    }
+}
+
+impl fmt::Display for MacAddr {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let [a, b, c, d, e, g] = self.0;
+        write!(
+            f,
+            "{:02x}:{:02x}:{:02x}:{:02x}:{:02x}:{:02x}",
+            a, b, c, d, e, g
+        )
+    }
+}
+
+impl NetlinkPayloadRequest for MacAddr {
+    fn serialized_len(&self) -> usize {
+        MAC_ADDR_LEN
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        buf.put_slice(&self.0);
+        Ok(())
+    }
+}
+
+impl NetlinkPayloadResponse for MacAddr {
+    type Error = ParseNetAddressError;
+
+    fn deserialize(buf: &[u8]) -> Result<Self, Self::Error> {
+        check_len("MacAddr", buf, MAC_ADDR_LEN)?;
+        let mut octets = [0u8; MAC_ADDR_LEN];
+        octets.copy_from_slice(buf);
+        Ok(Self(octets))
+    }
+}
+
+impl NetlinkPayloadRequest for Ipv4Addr {
+    fn serialized_len(&self) -> usize {
+        IPV4_ADDR_LEN
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        buf.put_slice(&self.octets());
+        Ok(())
+    }
+}
+
+impl NetlinkPayloadResponse for Ipv4Addr {
+    type Error = ParseNetAddressError;
+
+    fn deserialize(buf: &[u8]) -> Result<Self, Self::Error> {
+        check_len("Ipv4Addr", buf, IPV4_ADDR_LEN)?;
+        let mut octets = [0u8; IPV4_ADDR_LEN];
+        octets.copy_from_slice(buf);
+        Ok(Self::from(octets))
+    }
+}
+
+impl NetlinkPayloadRequest for Ipv6Addr {
+    fn serialized_len(&self) -> usize {
+        IPV6_ADDR_LEN
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        buf.put_slice(&self.octets());
+        Ok(())
+    }
+}
+
+impl NetlinkPayloadResponse for Ipv6Addr {
+    type Error = ParseNetAddressError;
+
+    fn deserialize(buf: &[u8]) -> Result<Self, Self::Error> {
+        check_len("Ipv6Addr", buf, IPV6_ADDR_LEN)?;
+        let mut octets = [0u8; IPV6_ADDR_LEN];
+        octets.copy_from_slice(buf);
+        Ok(Self::from(octets))
+    }
+}
+
+impl NetlinkPayloadRequest for IpAddr {
+    fn serialized_len(&self) -> usize {
+        match self {
+            IpAddr::V4(addr) => addr.serialized_len(),
+            IpAddr::V6(addr) => addr.serialized_len(),
+        }
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        match self {
+            IpAddr::V4(addr) => addr.serialize(buf),
+            IpAddr::V6(addr) => addr.serialize(buf),
+        }
+    }
+}
+
+/// Attributes such as `RTA_DST` don't carry an address family of their own. The
+/// address family is inferred from the payload length instead.
+impl NetlinkPayloadResponse for IpAddr {
+    type Error = ParseNetAddressError;
+
+    fn deserialize(buf: &[u8]) -> Result<Self, Self::Error> {
+        match buf.len() {
+            IPV4_ADDR_LEN => Ok(IpAddr::V4(Ipv4Addr::deserialize(buf)?)),
+            IPV6_ADDR_LEN => Ok(IpAddr::V6(Ipv6Addr::deserialize(buf)?)),
+            len => Err(ParseNetAddressError::InvalidIpAddrLength(len)),
+        }
+    }
+}
+
+/// Serialized with the layout of `struct sockaddr_in`.
+impl NetlinkPayloadRequest for SocketAddrV4 {
+    fn serialized_len(&self) -> usize {
+        SOCKADDR_IN_LEN
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        nla_put_u16(buf, AF_INET);
+        nla_put_be16(buf, self.port());
+        self.ip().serialize(buf)?;
+        // sin_zero
+        buf.put_bytes(0, SOCKADDR_IN_LEN - 2 * size_of::<u16>() - IPV4_ADDR_LEN);
+        Ok(())
+    }
+}
+
+impl NetlinkPayloadResponse for SocketAddrV4 {
+    type Error = ParseNetAddressError;
+
+    fn deserialize(buf: &[u8]) -> Result<Self, Self::Error> {
+        check_len("SocketAddrV4", buf, SOCKADDR_IN_LEN)?;
+        check_family(buf, AF_INET)?;
+        let port = nla_get_be16(&buf[2..4]).unwrap();
+        let ip = Ipv4Addr::deserialize(&buf[4..8])?;
+        Ok(SocketAddrV4::new(ip, port))
+    }
+}
+
+/// Serialized with the layout of `struct sockaddr_in6`. As with the standard
+/// library's conversion, the flow info and scope ID are written as-is.
+impl NetlinkPayloadRequest for SocketAddrV6 {
+    fn serialized_len(&self) -> usize {
+        SOCKADDR_IN6_LEN
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        nla_put_u16(buf, AF_INET6);
+        nla_put_be16(buf, self.port());
+        nla_put_u32(buf, self.flowinfo());
+        self.ip().serialize(buf)?;
+        nla_put_u32(buf, self.scope_id());
+        Ok(())
+    }
+}
+
+impl NetlinkPayloadResponse for SocketAddrV6 {
+    type Error = ParseNetAddressError;
+
+    fn deserialize(buf: &[u8]) -> Result<Self, Self::Error> {
+        check_len("SocketAddrV6", buf, SOCKADDR_IN6_LEN)?;
+        check_family(buf, AF_INET6)?;
+        let port = nla_get_be16(&buf[2..4]).unwrap();
+        let flowinfo = nla_get_u32(&buf[4..8]).unwrap();
+        let ip = Ipv6Addr::deserialize(&buf[8..24])?;
+        let scope_id = nla_get_u32(&buf[24..28]).unwrap();
+        Ok(SocketAddrV6::new(ip, port, flowinfo, scope_id))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn serialize<T: NetlinkPayloadRequest>(val: &T) -> Vec<u8> {
+        let mut buf = vec![];
+        val.serialize(&mut buf).unwrap();
+        buf
+    }
+
+    #[test]
+    fn ip_addr_is_length_discriminated() {
+        let v4 = IpAddr::V4(Ipv4Addr::new(192, 0, 2, 1));
+        let v6 = IpAddr::V6(Ipv6Addr::LOCALHOST);
+        assert_eq!(IpAddr::deserialize(&serialize(&v4)), Ok(v4));
+        assert_eq!(IpAddr::deserialize(&serialize(&v6)), Ok(v6));
+        assert_eq!(
+            IpAddr::deserialize(&[0; 5]),
+            Err(ParseNetAddressError::InvalidIpAddrLength(5))
+        );
+    }
+
+    #[test]
+    fn mac_addr_invalid_length() {
+        assert_eq!(
+            MacAddr::deserialize(&[0; 8]),
+            Err(ParseNetAddressError::InvalidLength {
+                ty: "MacAddr",
+                expected: 6,
+                found: 8
+            })
+        );
+    }
+
+    #[test]
+    fn socket_addr_v4_matches_sockaddr_in() {
+        let addr = SocketAddrV4::new(Ipv4Addr::new(192, 0, 2, 1), 51820);
+        let buf = serialize(&addr);
+
+        let mut expected = vec![];
+        nla_put_u16(&mut expected, AF_INET);
+        expected.extend_from_slice(&[0xca, 0x6c, 192, 0, 2, 1]);
+        expected.extend_from_slice(&[0; 8]);
+        assert_eq!(buf, expected);
+
+        assert_eq!(SocketAddrV4::deserialize(&buf), Ok(addr));
+    }
+
+    #[test]
+    fn socket_addr_v6_round_trip() {
+        let addr = SocketAddrV6::new(Ipv6Addr::LOCALHOST, 51820, 0, 3);
+        let buf = serialize(&addr);
+        assert_eq!(buf.len(), SOCKADDR_IN6_LEN);
+        assert_eq!(SocketAddrV6::deserialize(&buf), Ok(addr));
+    }
+
+    #[test]
+    fn socket_addr_unexpected_family() {
+        let buf = serialize(&SocketAddrV4::new(Ipv4Addr::LOCALHOST, 1));
+        let mut buf6 = buf.clone();
+        buf6.resize(SOCKADDR_IN6_LEN, 0);
+        assert_eq!(
+            SocketAddrV6::deserialize(&buf6),
+            Err(ParseNetAddressError::UnexpectedFamily {
+                expected: AF_INET6,
+                found: AF_INET
+            })
+        );
+    }
+}
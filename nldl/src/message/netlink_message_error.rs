@@ -1,7 +1,10 @@
 use super::NetlinkPayloadRequest;
 use super::NetlinkPayloadResponse;
 use super::RawNetlinkMessageHeader;
+use crate::utils::nla_put_u32;
+use crate::SerializeError;
 use arrayref::array_ref;
+use bytes::BufMut;
 use std::mem::size_of;
 
 /// <https://www.infradead.org/~tgr/libnl/doc/core.html#core_errmsg>
@@ -12,9 +15,14 @@ pub struct NetlinkErrorMessagePayload {
 }
 
 impl NetlinkPayloadRequest for NetlinkErrorMessagePayload {
-    fn serialize(&self, buf: &mut Vec<u8>) {
-        self.error_code.serialize(buf);
+    fn serialized_len(&self) -> usize {
+        size_of::<u32>() + size_of::<libc::nlmsghdr>()
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        nla_put_u32(buf, self.error_code);
         self.original_header.serialize(buf);
+        Ok(())
     }
 }
 
@@ -2,6 +2,9 @@ use super::netlink_message_error::NetlinkErrorMessagePayload;
 use super::netlink_message_error::ReadNetlinkErrorMessageError;
 use super::NetlinkPayloadRequest;
 use super::NetlinkPayloadResponse;
+use crate::attr::PolicyError;
+use crate::SerializeError;
+use bytes::BufMut;
 
 /// The NetlinkMessageType variant is determined from a Netlink message's "type" field.
 ///
@@ -34,15 +37,32 @@ impl<T> NetlinkMessageType<T>
 where
     T: NetlinkPayloadRequest,
 {
-    pub fn serialize(&self, buf: &mut Vec<u8>) {
+    pub fn serialized_len(&self) -> usize {
+        match self {
+            NetlinkMessageType::Noop | NetlinkMessageType::Done | NetlinkMessageType::Overrun => 0,
+            NetlinkMessageType::Error(err_payload) => err_payload.serialized_len(),
+            NetlinkMessageType::ProtocolMessage(payload) => payload.serialized_len(),
+        }
+    }
+
+    pub fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
         match self {
             // These message types don't seem to have defined bodies.
-            NetlinkMessageType::Noop | NetlinkMessageType::Done | NetlinkMessageType::Overrun => {}
+            NetlinkMessageType::Noop | NetlinkMessageType::Done | NetlinkMessageType::Overrun => {
+                Ok(())
+            }
 
             NetlinkMessageType::Error(err_payload) => err_payload.serialize(buf),
             NetlinkMessageType::ProtocolMessage(payload) => payload.serialize(buf),
         }
     }
+
+    pub fn validate(&self) -> Result<(), PolicyError> {
+        match self {
+            NetlinkMessageType::ProtocolMessage(payload) => payload.validate(),
+            _ => Ok(()),
+        }
+    }
 }
 
 #[derive(thiserror::Error, Debug, PartialEq)]
@@ -87,7 +107,7 @@ mod tests {
     fn test_noop_serialization() -> anyhow::Result<()> {
         let original = NetlinkMessageType::<()>::Noop;
         let mut buf = vec![];
-        original.serialize(&mut buf);
+        original.serialize(&mut buf)?;
         assert_eq!(original, NetlinkMessageType::deserialize(NLMSG_NOOP, &buf)?);
         Ok(())
     }
@@ -96,7 +116,7 @@ mod tests {
     fn test_done_serialization() -> anyhow::Result<()> {
         let original = NetlinkMessageType::<()>::Done;
         let mut buf = vec![];
-        original.serialize(&mut buf);
+        original.serialize(&mut buf)?;
         assert_eq!(original, NetlinkMessageType::deserialize(NLMSG_DONE, &buf)?);
         Ok(())
     }
@@ -105,7 +125,7 @@ mod tests {
     fn test_overrun_serialization() -> anyhow::Result<()> {
         let original = NetlinkMessageType::<()>::Overrun;
         let mut buf = vec![];
-        original.serialize(&mut buf);
+        original.serialize(&mut buf)?;
         assert_eq!(
             original,
             NetlinkMessageType::deserialize(NLMSG_OVERRUN, &buf)?
@@ -0,0 +1,85 @@
+use super::FixedLayout;
+use super::NetlinkPayloadRequest;
+use super::NetlinkPayloadResponse;
+use super::ParseFixedLayoutError;
+use crate::SerializeError;
+use bytes::BufMut;
+use std::fmt::Debug;
+
+/// A flat array of fixed-size elements without per-element attribute headers,
+/// such as `NL80211_ATTR_CIPHER_SUITES` (an array of `u32`).
+///
+/// Elements may be any [FixedLayout] type, including integers and structs
+/// deriving `nldl::Struct`. Elements are stored back to back, each taking
+/// `T::SIZE` bytes like in a C array.
+#[derive(Clone, Debug, Default, PartialEq, Eq, Hash)]
+pub struct Packed<C>(pub C);
+
+impl<T> From<Vec<T>> for Packed<Vec<T>> {
+    fn from(values: Vec<T>) -> Self {
+        Self(values)
+    }
+}
+
+impl<T: FixedLayout> NetlinkPayloadRequest for Packed<Vec<T>> {
+    fn serialized_len(&self) -> usize {
+        T::SIZE * self.0.len()
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        for val in &self.0 {
+            val.write_fixed(buf);
+        }
+        Ok(())
+    }
+}
+
+impl<T: FixedLayout + Debug + PartialEq> NetlinkPayloadResponse for Packed<Vec<T>> {
+    type Error = ParseFixedLayoutError;
+
+    fn deserialize(buf: &[u8]) -> Result<Self, Self::Error> {
+        if T::SIZE == 0 {
+            return Ok(Self(vec![]));
+        }
+        let chunks = buf.chunks_exact(T::SIZE);
+        if !chunks.remainder().is_empty() {
+            return Err(ParseFixedLayoutError::NotMultipleOfElementSize {
+                ty: std::any::type_name::<T>(),
+                element_size: T::SIZE,
+                found: buf.len(),
+            });
+        }
+
+        Ok(Self(chunks.map(T::read_fixed).collect()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let suites = Packed(vec![0x000f_ac04u32, 0x000f_ac02]);
+
+        let mut buf = vec![];
+        suites.serialize(&mut buf).unwrap();
+        assert_eq!(buf.len(), 8);
+        assert_eq!(buf[..4], 0x000f_ac04u32.to_ne_bytes());
+
+        assert_eq!(Packed::<Vec<u32>>::deserialize(&buf), Ok(suites));
+        assert_eq!(Packed::<Vec<u32>>::deserialize(&[]), Ok(Packed(vec![])));
+    }
+
+    #[test]
+    fn length_must_be_multiple_of_element_size() {
+        assert_eq!(
+            Packed::<Vec<u16>>::deserialize(&[0; 5]),
+            Err(ParseFixedLayoutError::NotMultipleOfElementSize {
+                ty: "u16",
+                element_size: 2,
+                found: 5
+            })
+        );
+    }
+}
@@ -12,6 +12,8 @@ pub(crate) struct RawNetlinkMessage<'a> {
 pub enum ReadRawNetlinkMessageError {
     #[error("Found a netlink message with an incomplete header. Saw {len} bytes, but at least {} were expected", size_of::<libc::nlmsghdr>())]
     IncompleteHeader { len: usize },
+    #[error("Found a netlink message whose header declares a length of {nlmsg_len} bytes, which is shorter than the {} byte header", size_of::<libc::nlmsghdr>())]
+    InvalidLength { nlmsg_len: u32 },
     #[error("Found a netlink message with an insufficiently sized payload buffer. Expected a payload with {expected} bytes (determined from the header) but saw {actual} bytes.")]
     UnexpectedEndOfPayloadBuffer { actual: usize, expected: usize },
 }
@@ -30,7 +32,11 @@ impl<'a> TryFrom<&'a [u8]> for RawNetlinkMessage<'a> {
         let remaining = &buf[size_of::<libc::nlmsghdr>()..];
 
         let payload = {
-            let payload_len = (header.len as usize) - size_of::<libc::nlmsghdr>();
+            let payload_len = (header.len as usize)
+                .checked_sub(size_of::<libc::nlmsghdr>())
+                .ok_or(Self::Error::InvalidLength {
+                    nlmsg_len: header.len,
+                })?;
             if remaining.len() < payload_len {
                 return Err(Self::Error::UnexpectedEndOfPayloadBuffer {
                     actual: remaining.len(),
@@ -0,0 +1,232 @@
+use super::NetlinkPayloadRequest;
+use super::NetlinkPayloadResponse;
+use super::NetlinkPayloadResponseBorrowed;
+use crate::utils::nla_get_lenient_string;
+use crate::utils::nla_get_string_bytes;
+use crate::utils::nla_put_string;
+use crate::utils::nla_string_len;
+use crate::SerializeError;
+use bytes::BufMut;
+use std::ffi::CStr;
+use std::ffi::CString;
+use std::ffi::FromBytesWithNulError;
+use std::ffi::OsString;
+use std::fmt;
+use std::ops::Deref;
+use std::os::unix::ffi::OsStrExt;
+use std::os::unix::ffi::OsStringExt;
+use std::str::Utf8Error;
+use std::string::FromUtf8Error;
+
+/// A string for attributes with the kernel's `NLA_STRING` policy.
+///
+/// Unlike [String], which matches `NLA_NUL_STRING` and requires a null
+/// terminator, the payload may omit it. The string ends at the first null byte
+/// if there is one. [NlaStr] is the borrowing version.
+#[derive(Clone, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NlaString(pub String);
+
+impl From<String> for NlaString {
+    fn from(val: String) -> Self {
+        Self(val)
+    }
+}
+
+impl From<&str> for NlaString {
+    fn from(val: &str) -> Self {
+        Self(val.to_string())
+    }
+}
+
+impl From<NlaString> for String {
+    fn from(val: NlaString) -> Self {
+        val.0
+    }
+}
+
+impl Deref for NlaString {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        &self.0
+    }
+}
+
+impl fmt::Display for NlaString {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl NetlinkPayloadRequest for NlaString {
+    fn serialized_len(&self) -> usize {
+        nla_string_len(&self.0)
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        nla_put_string(buf, &self.0);
+        Ok(())
+    }
+}
+
+impl NetlinkPayloadResponse for NlaString {
+    type Error = FromUtf8Error;
+
+    fn deserialize(buf: &[u8]) -> Result<Self, Self::Error> {
+        nla_get_lenient_string(buf).map(Self)
+    }
+}
+
+/// Borrowing version of [NlaString] for attributes with the `NLA_STRING`
+/// policy. Points into the receive buffer instead of copying.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct NlaStr<'a>(pub &'a str);
+
+impl<'a> From<&'a str> for NlaStr<'a> {
+    fn from(val: &'a str) -> Self {
+        Self(val)
+    }
+}
+
+impl<'a> From<NlaStr<'a>> for &'a str {
+    fn from(val: NlaStr<'a>) -> Self {
+        val.0
+    }
+}
+
+impl Deref for NlaStr<'_> {
+    type Target = str;
+
+    fn deref(&self) -> &Self::Target {
+        self.0
+    }
+}
+
+impl fmt::Display for NlaStr<'_> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        self.0.fmt(f)
+    }
+}
+
+impl NetlinkPayloadRequest for NlaStr<'_> {
+    fn serialized_len(&self) -> usize {
+        nla_string_len(self.0)
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        nla_put_string(buf, self.0);
+        Ok(())
+    }
+}
+
+impl<'a> NetlinkPayloadResponseBorrowed<'a> for NlaStr<'a> {
+    type Error = Utf8Error;
+
+    fn deserialize_borrowed(buf: &'a [u8]) -> Result<Self, Self::Error> {
+        std::str::from_utf8(nla_get_string_bytes(buf)).map(Self)
+    }
+}
+
+/// The payload must be null-terminated and contain no other null bytes.
+impl NetlinkPayloadRequest for CString {
+    fn serialized_len(&self) -> usize {
+        self.as_bytes_with_nul().len()
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        buf.put_slice(self.as_bytes_with_nul());
+        Ok(())
+    }
+}
+
+impl NetlinkPayloadResponse for CString {
+    type Error = FromBytesWithNulError;
+
+    fn deserialize(buf: &[u8]) -> Result<Self, Self::Error> {
+        CStr::from_bytes_with_nul(buf).map(CStr::to_owned)
+    }
+}
+
+/// For strings that may not be valid UTF-8, such as interface aliases. The
+/// null terminator is handled the same way as [NlaString].
+impl NetlinkPayloadRequest for OsString {
+    fn serialized_len(&self) -> usize {
+        self.len() + 1
+    }
+
+    fn serialize<B: BufMut>(&self, buf: &mut B) -> Result<(), SerializeError> {
+        buf.put_slice(self.as_bytes());
+        buf.put_u8(0);
+        Ok(())
+    }
+}
+
+impl NetlinkPayloadResponse for OsString {
+    type Error = std::convert::Infallible;
+
+    fn deserialize(buf: &[u8]) -> Result<Self, Self::Error> {
+        Ok(OsString::from_vec(nla_get_string_bytes(buf).to_vec()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::utils::NlaGetStringError;
+
+    #[test]
+    fn string_requires_terminator() {
+        assert_eq!(String::deserialize(b"eth0\0"), Ok("eth0".to_string()));
+        assert!(matches!(
+            String::deserialize(b"eth0"),
+            Err(NlaGetStringError::NotNullTerminated(b'0'))
+        ));
+        assert!(matches!(
+            String::deserialize(b""),
+            Err(NlaGetStringError::NullBuffer)
+        ));
+    }
+
+    #[test]
+    fn nla_string_accepts_unterminated_payload() {
+        assert_eq!(NlaString::deserialize(b"eth0"), Ok(NlaString::from("eth0")));
+        assert_eq!(
+            NlaString::deserialize(b"eth0\0"),
+            Ok(NlaString::from("eth0"))
+        );
+        assert_eq!(
+            NlaString::deserialize(b"eth0\0\0\0"),
+            Ok(NlaString::from("eth0"))
+        );
+        assert_eq!(NlaString::deserialize(b""), Ok(NlaString::from("")));
+    }
+
+    #[test]
+    fn nla_str_accepts_unterminated_payload() {
+        let buf = b"eth0\0\0";
+        let val = NlaStr::deserialize_borrowed(buf).unwrap();
+        assert_eq!(val, NlaStr("eth0"));
+        assert_eq!(val.as_ptr(), buf.as_ptr());
+        assert_eq!(NlaStr::deserialize_borrowed(b"eth0"), Ok(NlaStr("eth0")));
+    }
+
+    #[test]
+    fn cstring_round_trip() {
+        let val = CString::new("wg0").unwrap();
+        let mut buf = vec![];
+        val.serialize(&mut buf).unwrap();
+        assert_eq!(buf, b"wg0\0");
+        assert_eq!(CString::deserialize(&buf), Ok(val));
+        assert!(CString::deserialize(b"wg0").is_err());
+    }
+
+    #[test]
+    fn os_string_accepts_non_utf8() {
+        let val = OsString::from_vec(vec![0xff, 0xfe]);
+        let mut buf = vec![];
+        val.serialize(&mut buf).unwrap();
+        assert_eq!(buf, [0xff, 0xfe, 0x00]);
+        assert_eq!(OsString::deserialize(&buf), Ok(val.clone()));
+        assert_eq!(OsString::deserialize(&[0xff, 0xfe]), Ok(val));
+    }
+}
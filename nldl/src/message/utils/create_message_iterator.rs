@@ -84,6 +84,6 @@ mod tests {
 
         assert!(matches![messages.next(), Some(Ok(_))]);
         assert!(matches![messages.next(), Some(Err(_))]);
-        assert!(matches![messages.next(), None]);
+        assert!(messages.next().is_none());
     }
 }
@@ -1,2 +1,7 @@
 mod create_message_iterator;
+mod visit_messages;
 pub use create_message_iterator::create_message_iterator;
+pub use visit_messages::visit_messages;
+pub use visit_messages::MessageVisitor;
+pub use visit_messages::VisitMessagesError;
+pub use visit_messages::VisitMessagesStatus;
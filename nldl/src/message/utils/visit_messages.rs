@@ -0,0 +1,171 @@
+use crate::linux::nlmsg_align;
+use crate::message::raw::RawNetlinkMessage;
+use crate::message::raw::ReadRawNetlinkMessageError;
+use crate::message::NetlinkErrorMessagePayload;
+use crate::message::NetlinkMessageHeader;
+use crate::message::NetlinkPayloadResponse;
+use crate::message::ReadNetlinkErrorMessageError;
+
+/// Callbacks invoked by [visit_messages] for each protocol message in a buffer.
+/// Control messages (`NLMSG_NOOP`, `NLMSG_ERROR`, `NLMSG_DONE` and
+/// `NLMSG_OVERRUN`) are handled by [visit_messages] itself.
+pub trait MessageVisitor<'a> {
+    type Error;
+
+    fn visit_message(
+        &mut self,
+        header: NetlinkMessageHeader,
+        payload: &'a [u8],
+    ) -> Result<(), Self::Error>;
+}
+
+impl<'a, F, E> MessageVisitor<'a> for F
+where
+    F: FnMut(NetlinkMessageHeader, &'a [u8]) -> Result<(), E>,
+{
+    type Error = E;
+
+    fn visit_message(&mut self, header: NetlinkMessageHeader, payload: &'a [u8]) -> Result<(), E> {
+        self(header, payload)
+    }
+}
+
+/// Whether a multipart response was completed by the visited buffer.
+#[derive(Debug, PartialEq, Eq, Clone, Copy)]
+pub enum VisitMessagesStatus {
+    /// The buffer ended without an `NLMSG_DONE` message. More messages should
+    /// be received.
+    Incomplete,
+    /// An `NLMSG_DONE` message was seen. Any bytes after it were ignored.
+    Done,
+}
+
+#[derive(thiserror::Error, Debug)]
+pub enum VisitMessagesError<E> {
+    #[error(transparent)]
+    RawReadError(#[from] ReadRawNetlinkMessageError),
+    #[error(transparent)]
+    ParseErrorMessageFail(#[from] ReadNetlinkErrorMessageError),
+    #[error("Received NLMSG_ERROR message with code: {:?}", .0.error_code)]
+    NetlinkError(NetlinkErrorMessagePayload),
+    #[error("Received NLMSG_OVERRUN message instead of proper response.")]
+    UnexpectedOverrun,
+    #[error("{0}")]
+    Visitor(E),
+}
+
+/// Walks the Netlink messages in a byte buffer, passing the header and payload
+/// of each protocol message to `visitor` without copying or collecting them.
+///
+/// Unlike [create_message_iterator](super::create_message_iterator), nothing
+/// is allocated per message. This makes it suitable for large multipart dumps
+/// processed one receive buffer at a time.
+pub fn visit_messages<'a, V>(
+    buf: &'a [u8],
+    visitor: &mut V,
+) -> Result<VisitMessagesStatus, VisitMessagesError<V::Error>>
+where
+    V: MessageVisitor<'a> + ?Sized,
+{
+    let mut offset: usize = 0;
+
+    while offset < buf.len() {
+        let raw = RawNetlinkMessage::try_from(&buf[offset..])?;
+        offset += nlmsg_align(raw.header.len as usize);
+
+        match raw.header.ty as libc::c_int {
+            libc::NLMSG_NOOP => {}
+            libc::NLMSG_ERROR => {
+                let err = NetlinkErrorMessagePayload::deserialize(raw.payload)?;
+                return Err(VisitMessagesError::NetlinkError(err));
+            }
+            libc::NLMSG_DONE => return Ok(VisitMessagesStatus::Done),
+            libc::NLMSG_OVERRUN => return Err(VisitMessagesError::UnexpectedOverrun),
+            _ => visitor
+                .visit_message(raw.header.into(), raw.payload)
+                .map_err(VisitMessagesError::Visitor)?,
+        }
+    }
+
+    Ok(VisitMessagesStatus::Incomplete)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::visit_messages;
+    use super::VisitMessagesError;
+    use super::VisitMessagesStatus;
+    use crate::message::raw::ReadRawNetlinkMessageError;
+    use crate::message::NetlinkMessageHeader;
+    use std::convert::Infallible;
+
+    fn message(ty: u16, payload: &[u8]) -> Vec<u8> {
+        let len = (16 + payload.len()) as u32;
+        let mut buf = vec![];
+        buf.extend_from_slice(&len.to_ne_bytes());
+        buf.extend_from_slice(&ty.to_ne_bytes());
+        buf.extend_from_slice(&[0; 10]);
+        buf.extend_from_slice(payload);
+        buf
+    }
+
+    #[test]
+    fn visits_protocol_messages_until_done() {
+        let mut buf = message(0x10, &[1, 2, 3, 4]);
+        buf.extend(message(libc::NLMSG_NOOP as u16, &[]));
+        buf.extend(message(0x10, &[5, 6, 7, 8]));
+        buf.extend(message(libc::NLMSG_DONE as u16, &[0; 4]));
+        buf.extend(message(0x10, &[9, 9, 9, 9]));
+
+        let mut seen = vec![];
+        let status = visit_messages(&buf, &mut |header: NetlinkMessageHeader, payload: &[u8]| {
+            seen.push((header.ty, payload.to_vec()));
+            Ok::<_, Infallible>(())
+        })
+        .unwrap();
+
+        assert_eq!(status, VisitMessagesStatus::Done);
+        assert_eq!(
+            seen,
+            vec![(0x10, vec![1, 2, 3, 4]), (0x10, vec![5, 6, 7, 8])]
+        );
+    }
+
+    #[test]
+    fn incomplete_without_done() {
+        let buf = message(0x10, &[1, 2, 3, 4]);
+        let status = visit_messages(&buf, &mut |_, _| Ok::<_, Infallible>(())).unwrap();
+        assert_eq!(status, VisitMessagesStatus::Incomplete);
+    }
+
+    #[test]
+    fn stops_on_visitor_error() {
+        let mut buf = message(0x10, &[1, 2, 3, 4]);
+        buf.extend(message(0x10, &[5, 6, 7, 8]));
+
+        let mut calls = 0;
+        let result = visit_messages(&buf, &mut |_, _| {
+            calls += 1;
+            Err("stop")
+        });
+
+        assert!(matches!(result, Err(VisitMessagesError::Visitor("stop"))));
+        assert_eq!(calls, 1);
+    }
+
+    #[test]
+    fn rejects_header_shorter_than_itself() {
+        for nlmsg_len in [0u32, 15] {
+            let mut buf = message(0x10, &[]);
+            buf[..4].copy_from_slice(&nlmsg_len.to_ne_bytes());
+
+            let result = visit_messages(&buf, &mut |_, _| Ok::<_, Infallible>(()));
+            assert!(matches!(
+                result,
+                Err(VisitMessagesError::RawReadError(
+                    ReadRawNetlinkMessageError::InvalidLength { nlmsg_len: len }
+                )) if len == nlmsg_len
+            ));
+        }
+    }
+}
@@ -1,4 +1,6 @@
+use bytes::BufMut;
 use std::mem::size_of;
+use std::str::Utf8Error;
 use std::string::FromUtf8Error;
 
 #[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
@@ -32,12 +34,37 @@ create_nla_get_int!(nla_get_u8, u8, size_of::<u8>());
 create_nla_get_int!(nla_get_u16, u16, size_of::<u16>());
 create_nla_get_int!(nla_get_u32, u32, size_of::<u32>());
 create_nla_get_int!(nla_get_u64, u64, size_of::<u64>());
+create_nla_get_int!(nla_get_i8, i8, size_of::<i8>());
+create_nla_get_int!(nla_get_i16, i16, size_of::<i16>());
+create_nla_get_int!(nla_get_i32, i32, size_of::<i32>());
 create_nla_get_int!(nla_get_i64, i64, size_of::<i64>());
 
+// Network byte order variants, named after their libnl equivalents.
+macro_rules! create_nla_get_be_int {
+    ($func_name: ident, $int_type: ident, $int_type_len: expr) => {
+        pub fn $func_name(buf: &[u8]) -> Result<$int_type, ParseNlaIntError> {
+            if (buf.len() != $int_type_len) {
+                return Err(ParseNlaIntError::InvalidLength {
+                    expected: $int_type_len,
+                    found: buf.len(),
+                });
+            }
+
+            let mut arr = [0u8; $int_type_len];
+            arr.copy_from_slice(&buf);
+            Ok($int_type::from_be_bytes(arr))
+        }
+    };
+}
+
+create_nla_get_be_int!(nla_get_be16, u16, size_of::<u16>());
+create_nla_get_be_int!(nla_get_be32, u32, size_of::<u32>());
+create_nla_get_be_int!(nla_get_be64, u64, size_of::<u64>());
+
 macro_rules! create_nla_put_int {
     ($func_name: ident, $int_type: ident) => {
-        pub fn $func_name(buf: &mut Vec<u8>, value: $int_type) {
-            buf.extend_from_slice(&value.to_ne_bytes()[..]);
+        pub fn $func_name<B: BufMut>(buf: &mut B, value: $int_type) {
+            buf.put_slice(&value.to_ne_bytes()[..]);
         }
     };
 }
@@ -46,8 +73,33 @@ create_nla_put_int!(nla_put_u8, u8);
 create_nla_put_int!(nla_put_u16, u16);
 create_nla_put_int!(nla_put_u32, u32);
 create_nla_put_int!(nla_put_u64, u64);
+create_nla_put_int!(nla_put_i8, i8);
+create_nla_put_int!(nla_put_i16, i16);
+create_nla_put_int!(nla_put_i32, i32);
+create_nla_put_int!(nla_put_i64, i64);
 
-#[derive(thiserror::Error, Clone, Debug)]
+macro_rules! create_nla_put_be_int {
+    ($func_name: ident, $int_type: ident) => {
+        pub fn $func_name<B: BufMut>(buf: &mut B, value: $int_type) {
+            buf.put_slice(&value.to_be_bytes()[..]);
+        }
+    };
+}
+
+create_nla_put_be_int!(nla_put_be16, u16);
+create_nla_put_be_int!(nla_put_be32, u32);
+create_nla_put_be_int!(nla_put_be64, u64);
+
+/// Booleans are sent as an NLA_U8. Any non-zero value is treated as true.
+pub fn nla_get_bool(buf: &[u8]) -> Result<bool, ParseNlaIntError> {
+    Ok(nla_get_u8(buf)? != 0)
+}
+
+pub fn nla_put_bool<B: BufMut>(buf: &mut B, value: bool) {
+    nla_put_u8(buf, value as u8);
+}
+
+#[derive(thiserror::Error, Clone, Debug, PartialEq, Eq)]
 pub enum NlaGetStringError {
     #[error("Cannot parse empty buffer as String")]
     NullBuffer,
@@ -55,6 +107,8 @@ pub enum NlaGetStringError {
     NotNullTerminated(u8),
     #[error(transparent)]
     FromUtf8Error(#[from] FromUtf8Error),
+    #[error(transparent)]
+    Utf8Error(#[from] Utf8Error),
 }
 
 pub fn nla_get_string(buf: &[u8]) -> Result<String, NlaGetStringError> {
@@ -71,8 +125,38 @@ pub fn nla_get_string(buf: &[u8]) -> Result<String, NlaGetStringError> {
     }
 }
 
-pub fn nla_put_string(buf: &mut Vec<u8>, val: &str) {
-    buf.extend_from_slice(val.as_bytes());
+/// Borrowing version of [nla_get_string].
+pub fn nla_get_str(buf: &[u8]) -> Result<&str, NlaGetStringError> {
+    match buf.split_last() {
+        None => Err(NlaGetStringError::NullBuffer),
+        Some((0, payload)) => Ok(std::str::from_utf8(payload)?),
+        Some((&last_byte, _)) => Err(NlaGetStringError::NotNullTerminated(last_byte)),
+    }
+}
+
+/// Parses an NLA_STRING payload leniently. Like the kernel's `nla_strscpy`,
+/// the string ends at the first null byte if there is one, so both terminated
+/// and unterminated payloads are accepted.
+pub fn nla_get_lenient_string(buf: &[u8]) -> Result<String, FromUtf8Error> {
+    String::from_utf8(nla_get_string_bytes(buf).to_vec())
+}
+
+/// Returns the bytes of an NLA_STRING payload up to (and excluding) the first
+/// null byte.
+pub fn nla_get_string_bytes(buf: &[u8]) -> &[u8] {
+    match buf.iter().position(|&byte| byte == 0) {
+        Some(nul_pos) => &buf[..nul_pos],
+        None => buf,
+    }
+}
+
+pub fn nla_put_string<B: BufMut>(buf: &mut B, val: &str) {
+    buf.put_slice(val.as_bytes());
     // netlink strings are C strings which are null-terminated.
-    buf.push(0);
+    buf.put_u8(0);
+}
+
+/// The number of bytes [nla_put_string] writes for `val`.
+pub fn nla_string_len(val: &str) -> usize {
+    val.len() + 1
 }
@@ -0,0 +1,111 @@
+use nldl::attr::NestedRef;
+use nldl::attr::UnknownAttributeRef;
+use nldl::message::NetlinkPayloadRequest;
+use nldl::message::NetlinkPayloadResponseBorrowed;
+
+#[derive(Debug, PartialEq, nldl::attr::Serialize, nldl::attr::Deserialize)]
+enum LinkAttribute<'a> {
+    #[nla_type(1)]
+    Address(&'a [u8]),
+    #[nla_type(3)]
+    Name(&'a str),
+    #[nla_type(4)]
+    Mtu(u32),
+    #[nla_type(18)]
+    LinkInfo(NestedRef<'a, LinkInfoAttribute<'a>>),
+    #[nla_type(_)]
+    Unknown(UnknownAttributeRef<'a>),
+}
+
+#[derive(Debug, PartialEq, nldl::attr::Serialize, nldl::attr::Deserialize)]
+enum LinkInfoAttribute<'a> {
+    #[nla_type(1)]
+    Kind(&'a str),
+    #[nla_type(_)]
+    Unknown(UnknownAttributeRef<'a>),
+}
+
+#[derive(Debug, PartialEq, nldl::attr::Deserialize)]
+struct Link<'a> {
+    #[nla_type(3)]
+    name: &'a str,
+    #[nla_type(4)]
+    mtu: Option<u32>,
+    #[nla_type(18)]
+    link_info: Option<NestedRef<'a, LinkInfoAttribute<'a>>>,
+}
+
+fn serialize_payload<T: NetlinkPayloadRequest>(payload: &T) -> Vec<u8> {
+    let mut buf = vec![];
+    payload.serialize(&mut buf).unwrap();
+    assert_eq!(buf.len(), payload.serialized_len());
+    buf
+}
+
+fn link_info_bytes() -> Vec<u8> {
+    serialize_payload(&vec![LinkInfoAttribute::Kind("wireguard")])
+}
+
+fn link_bytes(link_info: &[u8]) -> Vec<u8> {
+    serialize_payload(&vec![
+        LinkAttribute::Name("wg0"),
+        LinkAttribute::Address(&[1, 2, 3, 4, 5, 6]),
+        LinkAttribute::Mtu(1420),
+        LinkAttribute::LinkInfo(NestedRef::new(link_info)),
+        LinkAttribute::Unknown(UnknownAttributeRef {
+            ty: 99,
+            flags: libc::NLA_F_NESTED as u16,
+            payload: &[7],
+        }),
+    ])
+}
+
+#[test]
+fn enum_variants_borrow_from_buffer() {
+    let link_info = link_info_bytes();
+    let bytes = link_bytes(&link_info);
+
+    let attrs: Vec<LinkAttribute> = NestedRef::new(&bytes)
+        .iter()
+        .collect::<Result<_, _>>()
+        .unwrap();
+
+    let name = match &attrs[0] {
+        LinkAttribute::Name(name) => *name,
+        other => panic!("unexpected attribute {:?}", other),
+    };
+    assert_eq!(name, "wg0");
+    // Name's payload starts after the 4 byte attribute header.
+    assert_eq!(name.as_ptr(), bytes[4..].as_ptr());
+
+    assert_eq!(attrs[1], LinkAttribute::Address(&[1, 2, 3, 4, 5, 6]));
+    assert_eq!(attrs[2], LinkAttribute::Mtu(1420));
+    assert_eq!(
+        attrs[4],
+        LinkAttribute::Unknown(UnknownAttributeRef {
+            ty: 99,
+            flags: libc::NLA_F_NESTED as u16,
+            payload: &[7]
+        })
+    );
+
+    let kinds: Vec<LinkInfoAttribute> = match &attrs[3] {
+        LinkAttribute::LinkInfo(nested) => nested.iter().collect::<Result<_, _>>().unwrap(),
+        other => panic!("unexpected attribute {:?}", other),
+    };
+    assert_eq!(kinds, vec![LinkInfoAttribute::Kind("wireguard")]);
+
+    // Re-serializing the borrowed attributes gives back the original bytes.
+    assert_eq!(serialize_payload(&attrs), bytes);
+}
+
+#[test]
+fn struct_fields_borrow_from_buffer() {
+    let link_info = link_info_bytes();
+    let bytes = link_bytes(&link_info);
+
+    let link = Link::deserialize_borrowed(&bytes).unwrap();
+    assert_eq!(link.name, "wg0");
+    assert_eq!(link.mtu, Some(1420));
+    assert_eq!(link.link_info.unwrap().as_bytes(), &link_info[..]);
+}
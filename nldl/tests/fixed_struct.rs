@@ -0,0 +1,128 @@
+use nldl::message::Be;
+use nldl::message::FixedLayout;
+use nldl::message::NetlinkPayloadRequest;
+use nldl::message::NetlinkPayloadResponse;
+use nldl::message::ParseFixedLayoutError;
+
+#[derive(Debug, PartialEq, nldl::Struct)]
+#[repr(C)]
+struct CacheInfo {
+    prefered: u32,
+    valid: u32,
+}
+
+#[derive(Debug, PartialEq, nldl::Struct)]
+#[repr(C)]
+struct Mixed {
+    proto: u8,
+    port: Be<u16>,
+    bytes: u64,
+    info: CacheInfo,
+    flag: u8,
+}
+
+#[derive(Debug, PartialEq, nldl::Struct)]
+#[repr(C)]
+struct Pair<T, const N: usize> {
+    tag: u8,
+    values: [T; N],
+}
+
+fn serialize_payload<T: NetlinkPayloadRequest>(payload: &T) -> Vec<u8> {
+    let mut buf = vec![];
+    payload.serialize(&mut buf).unwrap();
+    assert_eq!(buf.len(), payload.serialized_len());
+    buf
+}
+
+fn sample() -> Mixed {
+    Mixed {
+        proto: 6,
+        port: Be(443),
+        bytes: 1,
+        info: CacheInfo {
+            prefered: 2,
+            valid: 3,
+        },
+        flag: 1,
+    }
+}
+
+#[test]
+fn layout_includes_implicit_padding() {
+    assert_eq!(Mixed::SIZE, std::mem::size_of::<Mixed>());
+    assert_eq!(Mixed::SIZE, 32);
+
+    let mut expected = vec![6, 0, 0x01, 0xbb, 0, 0, 0, 0];
+    expected.extend_from_slice(&1u64.to_ne_bytes());
+    expected.extend_from_slice(&2u32.to_ne_bytes());
+    expected.extend_from_slice(&3u32.to_ne_bytes());
+    expected.extend_from_slice(&[1, 0, 0, 0, 0, 0, 0, 0]);
+
+    let bytes = serialize_payload(&sample());
+    assert_eq!(bytes, expected);
+    assert_eq!(Mixed::deserialize(&bytes), Ok(sample()));
+}
+
+#[test]
+fn trailing_fields_from_newer_kernels_are_ignored() {
+    let mut bytes = serialize_payload(&sample());
+    bytes.extend_from_slice(&[0xff; 8]);
+    assert_eq!(Mixed::deserialize(&bytes), Ok(sample()));
+}
+
+#[test]
+fn short_payload_is_rejected() {
+    let bytes = serialize_payload(&sample());
+    assert!(matches!(
+        Mixed::deserialize(&bytes[..20]),
+        Err(ParseFixedLayoutError::TooShort {
+            expected: 32,
+            found: 20,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn packed_array_of_structs() {
+    use nldl::message::Packed;
+
+    let infos = Packed(vec![
+        CacheInfo {
+            prefered: 1,
+            valid: 2,
+        },
+        CacheInfo {
+            prefered: 3,
+            valid: 4,
+        },
+    ]);
+
+    let bytes = serialize_payload(&infos);
+    assert_eq!(bytes.len(), 2 * CacheInfo::SIZE);
+    assert_eq!(Packed::<Vec<CacheInfo>>::deserialize(&bytes), Ok(infos));
+
+    assert!(matches!(
+        Packed::<Vec<CacheInfo>>::deserialize(&bytes[..12]),
+        Err(ParseFixedLayoutError::NotMultipleOfElementSize {
+            element_size: 8,
+            found: 12,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn generic_struct() {
+    assert_eq!(Pair::<u32, 2>::SIZE, 12);
+    assert_eq!(Pair::<u64, 1>::SIZE, 16);
+
+    let pair = Pair {
+        tag: 1,
+        values: [Be(2u16), Be(3)],
+    };
+    let bytes = serialize_payload(&pair);
+    assert_eq!(bytes, [1, 0, 0, 2, 0, 3]);
+    assert_eq!(Pair::deserialize(&bytes), Ok(pair));
+}
@@ -0,0 +1,148 @@
+use nldl::attr::Nested;
+use nldl::attr::NestedRef;
+use nldl::attr::UnknownAttribute;
+use nldl::attr::UnknownAttributeRef;
+use nldl::message::NetlinkPayloadRequest;
+use nldl::message::NetlinkPayloadResponse;
+use std::fmt::Debug;
+
+// IFLA_LINKINFO attributes shared by every link kind. The IFLA_INFO_DATA
+// payload is specific to the kind.
+#[derive(Debug, PartialEq, nldl::attr::Serialize, nldl::attr::Deserialize)]
+#[nldl(visitor)]
+enum LinkInfoAttribute<Data> {
+    #[nla_type(1)]
+    Kind(String),
+    #[nla_type(2)]
+    InfoData(Data),
+    #[nla_type(_)]
+    Unknown(UnknownAttribute),
+}
+
+#[derive(Debug, PartialEq, nldl::attr::Serialize, nldl::attr::Deserialize)]
+enum VlanAttribute {
+    #[nla_type(1)]
+    Id(u16),
+}
+
+#[derive(Debug, PartialEq, nldl::attr::Serialize, nldl::attr::Deserialize)]
+enum ListAttribute<T>
+where
+    T: Debug,
+{
+    #[nla_type(1)]
+    Items(Nested<T>),
+}
+
+#[derive(Debug, PartialEq, nldl::attr::Serialize, nldl::attr::Deserialize)]
+enum BorrowedLinkInfoAttribute<'a, Data> {
+    #[nla_type(1)]
+    Kind(&'a str),
+    #[nla_type(2)]
+    InfoData(Data),
+    #[nla_type(_)]
+    Unknown(UnknownAttributeRef<'a>),
+}
+
+#[derive(Debug, PartialEq, nldl::attr::Deserialize)]
+struct LinkInfo<Data> {
+    #[nla_type(1)]
+    kind: String,
+    #[nla_type(2)]
+    data: Option<Data>,
+}
+
+fn serialize_payload<T: NetlinkPayloadRequest>(payload: &T) -> Vec<u8> {
+    let mut buf = vec![];
+    payload.serialize(&mut buf).unwrap();
+    assert_eq!(buf.len(), payload.serialized_len());
+    buf
+}
+
+#[test]
+fn type_parameter_round_trip() {
+    let attrs = vec![
+        LinkInfoAttribute::Kind("vlan".to_string()),
+        LinkInfoAttribute::InfoData(Nested(vec![VlanAttribute::Id(100)])),
+    ];
+    let bytes = serialize_payload(&attrs);
+
+    assert_eq!(
+        Vec::<LinkInfoAttribute<Nested<VlanAttribute>>>::deserialize(&bytes).unwrap(),
+        attrs
+    );
+}
+
+#[test]
+fn type_parameter_with_scalar_payload() {
+    let attrs = vec![LinkInfoAttribute::InfoData(7u32)];
+    let bytes = serialize_payload(&attrs);
+
+    assert_eq!(
+        Vec::<LinkInfoAttribute<u32>>::deserialize(&bytes).unwrap(),
+        attrs
+    );
+}
+
+#[test]
+fn wrapped_type_parameter_and_where_clause() {
+    let attrs = vec![ListAttribute::Items(Nested(vec![VlanAttribute::Id(1)]))];
+    let bytes = serialize_payload(&attrs);
+
+    assert_eq!(
+        Vec::<ListAttribute<VlanAttribute>>::deserialize(&bytes).unwrap(),
+        attrs
+    );
+}
+
+#[test]
+fn lifetime_and_type_parameter() {
+    let vlan_bytes = serialize_payload(&vec![VlanAttribute::Id(100)]);
+    let attrs = vec![
+        BorrowedLinkInfoAttribute::Kind("vlan"),
+        BorrowedLinkInfoAttribute::InfoData(NestedRef::<VlanAttribute>::new(&vlan_bytes)),
+    ];
+    let bytes = serialize_payload(&attrs);
+
+    let deserialized =
+        NestedRef::<BorrowedLinkInfoAttribute<NestedRef<VlanAttribute>>>::new(&bytes)
+            .into_iter()
+            .collect::<Result<Vec<_>, _>>()
+            .unwrap();
+    assert_eq!(deserialized, attrs);
+}
+
+#[test]
+fn generic_struct() {
+    let bytes = serialize_payload(&vec![
+        LinkInfoAttribute::Kind("vlan".to_string()),
+        LinkInfoAttribute::InfoData(Nested(vec![VlanAttribute::Id(100)])),
+    ]);
+
+    assert_eq!(
+        LinkInfo::<Nested<VlanAttribute>>::deserialize(&bytes).unwrap(),
+        LinkInfo {
+            kind: "vlan".to_string(),
+            data: Some(Nested(vec![VlanAttribute::Id(100)])),
+        }
+    );
+}
+
+#[test]
+fn generic_visitor() {
+    #[derive(Default)]
+    struct Collect(Vec<u32>);
+    impl LinkInfoAttributeVisitor<'_, u32> for Collect {
+        fn visit_info_data(&mut self, value: u32) {
+            self.0.push(value);
+        }
+    }
+
+    let bytes = serialize_payload(&vec![
+        LinkInfoAttribute::Kind("dummy".to_string()),
+        LinkInfoAttribute::InfoData(3u32),
+    ]);
+    let mut collect = Collect::default();
+    collect.visit_attributes(&bytes).unwrap();
+    assert_eq!(collect.0, vec![3]);
+}
@@ -0,0 +1,152 @@
+use nldl::attr::Lenient;
+use nldl::attr::LenientStruct;
+use nldl::attr::Nested;
+use nldl::attr::PolicyViolation;
+use nldl::attr::UnknownAttribute;
+use nldl::message::NetlinkPayloadRequest;
+use nldl::message::NetlinkPayloadResponse;
+
+#[derive(Debug, PartialEq, nldl::attr::Serialize, nldl::attr::Deserialize)]
+enum ControllerAttribute {
+    #[nla_type(1)]
+    FamilyId(u16),
+    #[nla_type(2)]
+    #[nla_policy(max_strlen = 15)]
+    FamilyName(String),
+    #[nla_type(3)]
+    Children(Nested<ControllerAttribute>),
+    #[nla_type(_)]
+    Unknown(UnknownAttribute),
+}
+
+#[derive(Debug, PartialEq, nldl::attr::Serialize, nldl::attr::Deserialize)]
+enum NoWildcardAttribute {
+    #[nla_type(1)]
+    FamilyId(u16),
+}
+
+#[derive(Debug, PartialEq, nldl::attr::Deserialize)]
+struct Family {
+    #[nla_type(1)]
+    family_id: Option<u16>,
+    #[nla_type(2)]
+    #[nla_policy(max_strlen = 15)]
+    family_name: String,
+    #[nla_type(3, default)]
+    children: Vec<Nested<NoWildcardAttribute>>,
+}
+
+// Encodes the same type IDs as ControllerAttribute with different payloads.
+#[derive(Debug, PartialEq, nldl::attr::Serialize)]
+enum WireAttribute {
+    #[nla_type(1)]
+    FamilyId(u32),
+    #[nla_type(2)]
+    FamilyName(String),
+    #[nla_type(3)]
+    Children(Nested<WireAttribute>),
+}
+
+fn serialize_payload<T: NetlinkPayloadRequest>(payload: &T) -> Vec<u8> {
+    let mut buf = vec![];
+    payload.serialize(&mut buf).unwrap();
+    buf
+}
+
+#[test]
+fn widened_attribute_is_kept_as_unknown() {
+    let bytes = serialize_payload(&vec![
+        WireAttribute::FamilyId(16),
+        WireAttribute::FamilyName("nlctrl".to_string()),
+    ]);
+    assert!(Vec::<ControllerAttribute>::deserialize(&bytes).is_err());
+
+    let lenient = Lenient::<ControllerAttribute>::deserialize(&bytes).unwrap();
+    assert_eq!(
+        lenient.attrs,
+        vec![
+            ControllerAttribute::Unknown(UnknownAttribute {
+                ty: 1,
+                flags: 0,
+                payload: 16u32.to_ne_bytes().to_vec(),
+            }),
+            ControllerAttribute::FamilyName("nlctrl".to_string()),
+        ]
+    );
+
+    assert_eq!(lenient.diagnostics.len(), 1);
+    let diagnostic = &lenient.diagnostics[0];
+    assert_eq!(diagnostic.attribute_struct_name, "ControllerAttribute");
+    assert_eq!(diagnostic.nla_type_id, 1);
+    assert_eq!(
+        diagnostic.reason,
+        "Invalid buffer length for integer. Expected 2 found 4."
+    );
+    assert_eq!(diagnostic.policy_error, None);
+}
+
+#[test]
+fn policy_violation_is_recorded() {
+    let bytes = serialize_payload(&vec![WireAttribute::FamilyName("a".repeat(16))]);
+    let lenient = Lenient::<ControllerAttribute>::deserialize(&bytes).unwrap();
+
+    let policy_error = lenient.diagnostics[0].policy_error.as_ref().unwrap();
+    assert_eq!(
+        policy_error.violation,
+        PolicyViolation::StringTooLong {
+            len: 16,
+            max_len: 15
+        }
+    );
+}
+
+#[test]
+fn nested_failure_keeps_outer_attribute() {
+    let bytes = serialize_payload(&vec![WireAttribute::Children(Nested(vec![
+        WireAttribute::FamilyId(16),
+    ]))]);
+    let lenient = Lenient::<ControllerAttribute>::deserialize(&bytes).unwrap();
+
+    assert!(matches!(
+        lenient.attrs[..],
+        [ControllerAttribute::Unknown(UnknownAttribute { ty: 3, .. })]
+    ));
+    assert_eq!(lenient.diagnostics[0].nla_type_id, 3);
+}
+
+#[test]
+fn enum_without_wildcard_fails() {
+    let bytes = serialize_payload(&vec![WireAttribute::FamilyId(16)]);
+    assert!(Lenient::<NoWildcardAttribute>::deserialize(&bytes).is_err());
+}
+
+#[test]
+fn struct_skips_malformed_fields() {
+    let bytes = serialize_payload(&vec![
+        WireAttribute::FamilyId(16),
+        WireAttribute::FamilyName("nlctrl".to_string()),
+        WireAttribute::Children(Nested(vec![WireAttribute::FamilyId(16)])),
+    ]);
+    assert!(Family::deserialize(&bytes).is_err());
+
+    let lenient = LenientStruct::<Family>::deserialize(&bytes).unwrap();
+    assert_eq!(
+        lenient.value,
+        Family {
+            family_id: None,
+            family_name: "nlctrl".to_string(),
+            children: vec![],
+        }
+    );
+    let skipped: Vec<_> = (lenient.diagnostics.iter())
+        .map(|diagnostic| (diagnostic.attribute_struct_name, diagnostic.nla_type_id))
+        .collect();
+    assert_eq!(skipped, vec![("Family", 1), ("Family", 3)]);
+}
+
+#[test]
+fn struct_required_field_failure_fails() {
+    let bytes = serialize_payload(&vec![WireAttribute::FamilyName("a".repeat(16))]);
+    let err = LenientStruct::<Family>::deserialize(&bytes).unwrap_err();
+    assert!(err.policy_error().is_some());
+}
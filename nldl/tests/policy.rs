@@ -0,0 +1,154 @@
+use nldl::attr::policy::AttributePathSegment;
+use nldl::attr::Nested;
+use nldl::attr::PolicyError;
+use nldl::attr::PolicyViolation;
+use nldl::attr::UnknownAttribute;
+use nldl::message::NetlinkPayloadRequest;
+use nldl::message::NetlinkPayloadResponse;
+
+#[derive(Debug, PartialEq, nldl::attr::Serialize, nldl::attr::Deserialize)]
+#[nldl(visitor)]
+enum LinkAttribute {
+    #[nla_type(1)]
+    #[nla_policy(min_len = 6, max_len = 6)]
+    Address(Vec<u8>),
+    #[nla_type(2)]
+    #[nla_policy(max_strlen = 15)]
+    IfName(String),
+    #[nla_type(3)]
+    #[nla_policy(range = 1..=4094)]
+    VlanId(u16),
+    #[nla_type(4)]
+    Children(Vec<Nested<LinkAttribute>>),
+    #[nla_type(_)]
+    Unknown(UnknownAttribute),
+}
+
+#[derive(Debug, PartialEq, nldl::attr::Deserialize)]
+struct LinkAttributeSet {
+    #[nla_type(3)]
+    #[nla_policy(range = ..100)]
+    vlan_id: Option<u16>,
+}
+
+fn serialize_payload<T: NetlinkPayloadRequest>(payload: &T) -> Vec<u8> {
+    let mut buf = vec![];
+    payload.serialize(&mut buf).unwrap();
+    buf
+}
+
+fn segment(attribute_struct_name: &'static str, nla_type_id: u16) -> AttributePathSegment {
+    AttributePathSegment {
+        attribute_struct_name,
+        nla_type_id,
+    }
+}
+
+#[test]
+fn valid_attributes_pass() {
+    let attrs = vec![
+        LinkAttribute::Address(vec![2, 0, 0, 0, 0, 1]),
+        LinkAttribute::IfName("eth0".to_string()),
+        LinkAttribute::VlanId(100),
+    ];
+
+    assert_eq!(attrs.validate(), Ok(()));
+    let bytes = serialize_payload(&attrs);
+    assert_eq!(Vec::<LinkAttribute>::deserialize(&bytes).unwrap(), attrs);
+}
+
+#[test]
+fn deserialize_rejects_wrong_length() {
+    let bytes = serialize_payload(&vec![LinkAttribute::Address(vec![1, 2, 3])]);
+    let err = Vec::<LinkAttribute>::deserialize(&bytes).unwrap_err();
+
+    assert_eq!(
+        err.policy_error(),
+        Some(PolicyError {
+            path: vec![segment("LinkAttribute", 1)],
+            violation: PolicyViolation::TooShort { len: 3, min_len: 6 },
+        })
+    );
+}
+
+#[test]
+fn deserialize_rejects_long_string() {
+    let bytes = serialize_payload(&vec![LinkAttribute::IfName("a".repeat(16))]);
+    let err = Vec::<LinkAttribute>::deserialize(&bytes).unwrap_err();
+
+    assert_eq!(
+        err.policy_error().unwrap().violation,
+        PolicyViolation::StringTooLong {
+            len: 16,
+            max_len: 15
+        }
+    );
+}
+
+#[test]
+fn nested_violation_reports_path() {
+    let attrs = vec![LinkAttribute::Children(vec![Nested(vec![
+        LinkAttribute::VlanId(1),
+        LinkAttribute::VlanId(5000),
+    ])])];
+    let violation = PolicyViolation::OutOfRange {
+        value: 5000,
+        range: "1 ..= 4094",
+    };
+
+    assert_eq!(
+        attrs.validate(),
+        Err(PolicyError {
+            path: vec![
+                segment("LinkAttribute", 4),
+                segment("Nested", 0),
+                segment("LinkAttribute", 3)
+            ],
+            violation: violation.clone(),
+        })
+    );
+
+    let bytes = serialize_payload(&attrs);
+    let err = Vec::<LinkAttribute>::deserialize(&bytes).unwrap_err();
+    assert_eq!(
+        err.policy_error(),
+        Some(PolicyError {
+            path: vec![
+                segment("LinkAttribute", 4),
+                segment("Nested", 0),
+                segment("LinkAttribute", 3)
+            ],
+            violation,
+        })
+    );
+}
+
+#[test]
+fn struct_fields_are_checked() {
+    let bytes = serialize_payload(&vec![LinkAttribute::VlanId(100)]);
+    let err = LinkAttributeSet::deserialize(&bytes).unwrap_err();
+
+    assert_eq!(
+        err.policy_error(),
+        Some(PolicyError {
+            path: vec![segment("LinkAttributeSet", 3)],
+            violation: PolicyViolation::OutOfRange {
+                value: 100,
+                range: ".. 100",
+            },
+        })
+    );
+}
+
+#[test]
+fn visitor_checks_policies() {
+    struct Ignore;
+    impl LinkAttributeVisitor<'_> for Ignore {}
+
+    let bytes = serialize_payload(&vec![LinkAttribute::VlanId(0)]);
+    let err = Ignore.visit_attributes(&bytes).unwrap_err();
+    assert!(matches!(
+        err.policy_error().unwrap().violation,
+        PolicyViolation::OutOfRange { value: 0, .. }
+    ));
+}
@@ -0,0 +1,414 @@
+use nldl::attr::Nested;
+use nldl::attr::UnknownAttribute;
+use nldl::message::Be;
+use nldl::message::NetlinkMessageHeader;
+use nldl::message::NetlinkMessageRequest;
+use nldl::message::NetlinkMessageResponse;
+use nldl::message::NetlinkMessageType;
+use nldl::message::NetlinkPayloadRequest;
+use nldl::message::NetlinkPayloadResponse;
+use nldl::SerializeError;
+
+#[derive(Debug, PartialEq, nldl::attr::Serialize, nldl::attr::Deserialize)]
+enum TestAttribute {
+    #[nla_type(0)]
+    Unspec,
+    #[nla_type(1)]
+    Name(String),
+    #[nla_type(2)]
+    Id(u32),
+    #[nla_type(3)]
+    Flag(u8),
+    #[nla_type(4)]
+    Children(Vec<Nested<TestNestedAttribute>>),
+    #[nla_type(_)]
+    Unknown(UnknownAttribute),
+}
+
+#[derive(Debug, PartialEq, nldl::attr::Serialize, nldl::attr::Deserialize)]
+enum TestNestedAttribute {
+    #[nla_type(1)]
+    Label(String),
+    #[nla_type(_)]
+    Unknown(UnknownAttribute),
+}
+
+fn test_header() -> NetlinkMessageHeader {
+    NetlinkMessageHeader {
+        ty: 0x10,
+        flags: libc::NLM_F_REQUEST as u16,
+        seq: 1,
+        pid: 0,
+    }
+}
+
+fn serialize_payload<T: NetlinkPayloadRequest>(payload: &T) -> Vec<u8> {
+    let mut buf = vec![];
+    payload.serialize(&mut buf).unwrap();
+    assert_eq!(buf.len(), payload.serialized_len());
+    buf
+}
+
+#[test]
+fn unaligned_attributes_are_padded() {
+    let attrs = vec![
+        TestAttribute::Name("hello".to_string()),
+        TestAttribute::Flag(9),
+        TestAttribute::Id(7),
+        TestAttribute::Children(vec![Nested(vec![TestNestedAttribute::Label(
+            "ab".to_string(),
+        )])]),
+    ];
+
+    #[rustfmt::skip]
+    let expected = [
+        // Name: 6 byte payload + 2 bytes padding
+        0x0a, 0x00, 0x01, 0x00, 0x68, 0x65, 0x6c, 0x6c, 0x6f, 0x00, 0x00, 0x00,
+        // Flag: 1 byte payload + 3 bytes padding
+        0x05, 0x00, 0x03, 0x00, 0x09, 0x00, 0x00, 0x00,
+        // Id
+        0x08, 0x00, 0x02, 0x00, 0x07, 0x00, 0x00, 0x00,
+        // Children: the nested length includes the padding of the last child.
+        // Both levels of nesting set NLA_F_NESTED.
+        0x10, 0x00, 0x04, 0x80,
+        0x0c, 0x00, 0x00, 0x80,
+        0x07, 0x00, 0x01, 0x00, 0x61, 0x62, 0x00, 0x00,
+    ];
+
+    let actual = serialize_payload(&attrs);
+    assert_eq!(actual, expected);
+
+    let decoded: Vec<TestAttribute> = NetlinkPayloadResponse::deserialize(&actual).unwrap();
+    assert_eq!(decoded, attrs);
+    assert_eq!(serialize_payload(&decoded), expected);
+}
+
+#[test]
+fn nested_flag_is_masked_on_deserialize() {
+    #[rustfmt::skip]
+    let recv_bytes = [
+        0x10, 0x00, 0x04, 0x80,
+        0x0c, 0x00, 0x01, 0x80,
+        0x07, 0x00, 0x01, 0x00, 0x61, 0x62, 0x00, 0x00,
+    ];
+
+    let decoded: Vec<TestAttribute> = NetlinkPayloadResponse::deserialize(&recv_bytes).unwrap();
+    assert_eq!(
+        decoded,
+        vec![TestAttribute::Children(vec![Nested(vec![
+            TestNestedAttribute::Label("ab".to_string())
+        ])])]
+    );
+}
+
+#[test]
+fn unknown_attribute_keeps_nested_flag() {
+    #[rustfmt::skip]
+    let recv_bytes = [
+        0x10, 0x00, 0x09, 0x80,
+        0x0c, 0x00, 0x01, 0x00,
+        0x07, 0x00, 0x01, 0x00, 0x61, 0x62, 0x00, 0x00,
+    ];
+
+    let decoded: Vec<TestAttribute> = NetlinkPayloadResponse::deserialize(&recv_bytes).unwrap();
+    assert!(matches!(
+        &decoded[..],
+        [TestAttribute::Unknown(UnknownAttribute { ty: 9, flags, .. })]
+            if *flags == libc::NLA_F_NESTED as u16
+    ));
+    assert_eq!(serialize_payload(&decoded), recv_bytes);
+}
+
+#[test]
+fn multi_attribute_request_round_trip() {
+    let message = NetlinkMessageRequest {
+        header: test_header(),
+        payload: NetlinkMessageType::ProtocolMessage(vec![
+            TestAttribute::Unspec,
+            TestAttribute::Name("a".to_string()),
+            TestAttribute::Name("abc".to_string()),
+            TestAttribute::Name("abcdefg".to_string()),
+            TestAttribute::Flag(1),
+            TestAttribute::Children(vec![
+                Nested(vec![TestNestedAttribute::Label("x".to_string())]),
+                Nested(vec![
+                    TestNestedAttribute::Label("yz".to_string()),
+                    TestNestedAttribute::Unknown(UnknownAttribute {
+                        ty: 9,
+                        flags: 0,
+                        payload: vec![1, 2, 3, 4, 5],
+                    }),
+                ]),
+            ]),
+            TestAttribute::Id(u32::MAX),
+        ]),
+    };
+
+    let bytes = nldl::serialize(&message).unwrap();
+    assert_eq!(bytes.len() % 4, 0);
+    assert_eq!(bytes.len(), message.serialized_len());
+
+    let decoded = NetlinkMessageResponse::<Vec<TestAttribute>>::deserialize(&bytes).unwrap();
+    assert_eq!(decoded.header.ty, message.header.ty);
+    assert_eq!(decoded.header.flags, message.header.flags);
+    assert_eq!(decoded.header.seq, message.header.seq);
+    assert_eq!(decoded.header.pid, message.header.pid);
+    assert_eq!(decoded.payload, message.payload);
+
+    let reencoded = nldl::serialize(&NetlinkMessageRequest {
+        header: decoded.header,
+        payload: decoded.payload,
+    })
+    .unwrap();
+    assert_eq!(reencoded, bytes);
+}
+
+#[test]
+fn message_body_is_padded() {
+    let message = NetlinkMessageRequest {
+        header: test_header(),
+        payload: NetlinkMessageType::ProtocolMessage(0xffu8),
+    };
+
+    #[rustfmt::skip]
+    let expected = [
+        0x14, 0x00, 0x00, 0x00, 0x10, 0x00, 0x01, 0x00, 0x01, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0xff, 0x00, 0x00, 0x00,
+    ];
+
+    assert_eq!(nldl::serialize(&message).unwrap(), expected);
+}
+
+#[test]
+fn signed_and_bool_payloads() {
+    #[derive(Debug, PartialEq, nldl::attr::Serialize, nldl::attr::Deserialize)]
+    enum SignedAttribute {
+        #[nla_type(1)]
+        Signal(i32),
+        #[nla_type(2)]
+        Expires(i64),
+        #[nla_type(3)]
+        Small(i8),
+        #[nla_type(4)]
+        Medium(i16),
+        #[nla_type(5)]
+        Enabled(bool),
+        #[nla_type(_)]
+        Unknown(UnknownAttribute),
+    }
+
+    let attrs = vec![
+        SignedAttribute::Signal(-42),
+        SignedAttribute::Expires(i64::MIN),
+        SignedAttribute::Small(-1),
+        SignedAttribute::Medium(-300),
+        SignedAttribute::Enabled(true),
+    ];
+
+    let bytes = serialize_payload(&attrs);
+    let decoded: Vec<SignedAttribute> = NetlinkPayloadResponse::deserialize(&bytes).unwrap();
+    assert_eq!(decoded, attrs);
+
+    // Any non-zero NLA_U8 is true, and the length is checked like other integers.
+    assert_eq!(
+        <bool as NetlinkPayloadResponse>::deserialize(&[2]),
+        Ok(true)
+    );
+    assert_eq!(
+        <bool as NetlinkPayloadResponse>::deserialize(&[0]),
+        Ok(false)
+    );
+    assert!(<i32 as NetlinkPayloadResponse>::deserialize(&[0, 0]).is_err());
+}
+
+#[test]
+fn big_endian_payload_sets_net_byteorder() {
+    #[derive(Debug, PartialEq, nldl::attr::Serialize, nldl::attr::Deserialize)]
+    enum TunnelAttribute {
+        #[nla_type(1)]
+        Port(Be<u16>),
+        #[nla_type(_)]
+        Unknown(UnknownAttribute),
+    }
+
+    let attrs = vec![TunnelAttribute::Port(Be(4789))];
+
+    #[rustfmt::skip]
+    let expected = [
+        // NLA_F_NET_BYTEORDER is bit 14 of the type.
+        0x06, 0x00, 0x01, 0x40, 0x12, 0xb5, 0x00, 0x00,
+    ];
+
+    let bytes = serialize_payload(&attrs);
+    assert_eq!(bytes, expected);
+
+    let decoded: Vec<TunnelAttribute> = NetlinkPayloadResponse::deserialize(&bytes).unwrap();
+    assert_eq!(decoded, attrs);
+}
+
+#[test]
+fn int_enum_payload_round_trip() {
+    #[derive(Debug, PartialEq, nldl::Enum)]
+    #[nldl(repr(u8))]
+    enum OperState {
+        #[nla_value(0)]
+        Unknown,
+        #[nla_value(6)]
+        Up,
+        #[nla_value(_)]
+        Other(u32),
+    }
+
+    #[derive(Debug, PartialEq, nldl::attr::Serialize, nldl::attr::Deserialize)]
+    enum LinkAttribute {
+        #[nla_type(16)]
+        OperState(OperState),
+        #[nla_type(_)]
+        Unknown(UnknownAttribute),
+    }
+
+    let attrs = vec![
+        LinkAttribute::OperState(OperState::Up),
+        LinkAttribute::OperState(OperState::Unknown),
+        LinkAttribute::OperState(OperState::Other(7)),
+    ];
+
+    #[rustfmt::skip]
+    let expected = [
+        0x05, 0x00, 0x10, 0x00, 0x06, 0x00, 0x00, 0x00,
+        0x05, 0x00, 0x10, 0x00, 0x00, 0x00, 0x00, 0x00,
+        0x05, 0x00, 0x10, 0x00, 0x07, 0x00, 0x00, 0x00,
+    ];
+
+    let bytes = serialize_payload(&attrs);
+    assert_eq!(bytes, expected);
+
+    let decoded: Vec<LinkAttribute> = NetlinkPayloadResponse::deserialize(&bytes).unwrap();
+    assert_eq!(decoded, attrs);
+}
+
+#[test]
+fn int_enum_rejects_unrepresentable_other_values() {
+    #[derive(Debug, PartialEq, nldl::Enum)]
+    #[nldl(repr(u8))]
+    enum LinkMode {
+        #[nla_value(0)]
+        Default,
+        #[nla_value(_)]
+        Other(u32),
+    }
+
+    let invalid_value = |value| SerializeError::InvalidEnumValue {
+        enum_name: "LinkMode",
+        value,
+    };
+    let mut buf = vec![];
+
+    // Would be truncated to 44.
+    assert_eq!(
+        LinkMode::Other(300).serialize(&mut buf),
+        Err(invalid_value(300))
+    );
+    // Would deserialize as LinkMode::Default.
+    assert_eq!(
+        LinkMode::Other(0).serialize(&mut buf),
+        Err(invalid_value(0))
+    );
+    assert!(buf.is_empty());
+
+    LinkMode::Other(255).serialize(&mut buf).unwrap();
+    assert_eq!(LinkMode::deserialize(&buf).unwrap(), LinkMode::Other(255));
+}
+
+#[test]
+fn serialize_into_reuses_caller_buffer() {
+    let message = NetlinkMessageRequest {
+        header: test_header(),
+        payload: NetlinkMessageType::ProtocolMessage(vec![
+            TestAttribute::Name("wg0".to_string()),
+            TestAttribute::Children(vec![Nested(vec![TestNestedAttribute::Label(
+                "peer".to_string(),
+            )])]),
+        ]),
+    };
+    let expected = nldl::serialize(&message).unwrap();
+
+    let mut buf = [0xaau8; 128];
+    let written = nldl::serialize_into(&message, &mut buf).unwrap();
+    assert_eq!(&buf[..written], &expected[..]);
+    // Bytes past the message are left untouched.
+    assert!(buf[written..].iter().all(|&byte| byte == 0xaa));
+
+    let mut bytes_mut = nldl::bytes::BytesMut::new();
+    message.serialize(&mut bytes_mut).unwrap();
+    assert_eq!(&bytes_mut[..], &expected[..]);
+}
+
+#[test]
+fn serialize_into_rejects_short_buffer() {
+    let message = NetlinkMessageRequest {
+        header: test_header(),
+        payload: NetlinkMessageType::ProtocolMessage(TestAttribute::Id(1)),
+    };
+
+    let mut buf = [0u8; 16];
+    assert_eq!(
+        nldl::serialize_into(&message, &mut buf),
+        Err(nldl::SerializeError::BufferTooSmall {
+            needed: 24,
+            available: 16,
+        })
+    );
+}
+
+#[test]
+fn oversized_attribute_is_an_error() {
+    let message = NetlinkMessageRequest {
+        header: test_header(),
+        payload: NetlinkMessageType::ProtocolMessage(vec![
+            TestAttribute::Id(1),
+            TestAttribute::Name("a".repeat(70_000)),
+        ]),
+    };
+
+    assert_eq!(
+        nldl::serialize(&message),
+        Err(nldl::SerializeError::AttributeTooLarge {
+            attribute: std::any::type_name::<TestAttribute>(),
+            ty: 1,
+            len: 70_005,
+        })
+    );
+}
+
+#[test]
+fn oversized_nested_attribute_reports_parent() {
+    // Each label fits in its own attribute, but not both in the parent.
+    let label = || Nested(vec![TestNestedAttribute::Label("a".repeat(40_000))]);
+    let attrs = vec![TestAttribute::Children(vec![label(), label()])];
+
+    let mut buf = vec![];
+    assert!(matches!(
+        attrs.serialize(&mut buf),
+        Err(nldl::SerializeError::AttributeTooLarge { ty: 4, .. })
+    ));
+}
+
+#[test]
+fn fragment_splits_nested_attributes() {
+    let label = || Nested(vec![TestNestedAttribute::Label("a".repeat(40_000))]);
+    let children = vec![label(), label(), label()];
+
+    let fragments = nldl::attr::fragment(children, nldl::attr::MAX_ATTRIBUTE_PAYLOAD_LEN).unwrap();
+    assert_eq!(fragments.len(), 3);
+
+    let attrs: Vec<TestAttribute> = fragments.into_iter().map(TestAttribute::Children).collect();
+    let bytes = serialize_payload(&attrs);
+    assert_eq!(Vec::<TestAttribute>::deserialize(&bytes).unwrap(), attrs);
+
+    let too_small = nldl::attr::fragment(vec![label()], 100);
+    assert!(matches!(
+        too_small,
+        Err(nldl::SerializeError::ItemTooLarge { max_len: 100, .. })
+    ));
+}
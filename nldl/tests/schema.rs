@@ -0,0 +1,161 @@
+use nldl::attr::schema::PayloadKind;
+use nldl::attr::schema::TypeIds;
+use nldl::attr::Nested;
+use nldl::attr::NestedRef;
+use nldl::attr::NlaArray;
+use nldl::attr::Schema;
+use nldl::attr::UnknownAttribute;
+
+#[derive(Debug, PartialEq, nldl::attr::Deserialize, nldl::attr::Schema)]
+enum LinkAttribute {
+    #[nla_type(0)]
+    Unspec,
+    #[nla_type(1)]
+    Address(Vec<u8>),
+    #[nla_type(2 | 3)]
+    IfName(String),
+    #[nla_type(4)]
+    Children(Vec<LinkAttribute>),
+    #[nla_type(5)]
+    Groups(NlaArray<Vec<GroupAttribute>>),
+    #[nla_type(6)]
+    Wrapped(Vec<Nested<GroupAttribute>>),
+    #[nla_type(7)]
+    #[nldl(deserialize_with = "deserialize_list")]
+    Tags(Vec<String>),
+    #[nla_type(0x100..=0x1ff)]
+    Vendor(u16, Vec<u8>),
+    #[nla_type(_)]
+    Unknown(UnknownAttribute),
+}
+
+#[derive(Debug, PartialEq, nldl::attr::Deserialize, nldl::attr::Schema)]
+enum GroupAttribute {
+    #[nla_type(1)]
+    Id(u32),
+}
+
+#[derive(Debug, PartialEq, nldl::attr::Deserialize, nldl::attr::Schema)]
+enum GroupAttributeRef<'a> {
+    #[nla_type(1)]
+    Name(&'a str),
+    #[nla_type(2)]
+    Children(NestedRef<'a, GroupAttributeRef<'a>>),
+}
+
+#[derive(Debug, PartialEq, nldl::attr::Deserialize, nldl::attr::Schema)]
+struct Link {
+    #[nla_type(2 | 3)]
+    if_name: String,
+    #[nla_type(4)]
+    children: Option<Vec<LinkAttribute>>,
+}
+
+fn deserialize_list(payload: &[u8]) -> Result<Vec<String>, std::str::Utf8Error> {
+    let list = std::str::from_utf8(payload)?;
+    Ok(list.split(',').map(str::to_string).collect())
+}
+
+fn nested_name(kind: &PayloadKind) -> &'static str {
+    match kind {
+        PayloadKind::Nested(schema) => schema().name,
+        kind => panic!("expected a nested payload, got {:?}", kind),
+    }
+}
+
+#[test]
+fn type_id_consts() {
+    assert_eq!(LinkAttribute::UNSPEC_TYPE, 0);
+    assert_eq!(LinkAttribute::IF_NAME_TYPE, 2);
+    assert_eq!(LinkAttribute::VENDOR_TYPE_START, 0x100);
+    assert_eq!(LinkAttribute::VENDOR_TYPE_END, 0x1ff);
+    assert_eq!(Link::IF_NAME_TYPE, 2);
+
+    // The constants are usable as patterns.
+    let kind = match 4 {
+        LinkAttribute::CHILDREN_TYPE => "children",
+        LinkAttribute::VENDOR_TYPE_START..=LinkAttribute::VENDOR_TYPE_END => "vendor",
+        _ => "other",
+    };
+    assert_eq!(kind, "children");
+}
+
+#[test]
+fn enum_schema() {
+    let schema = LinkAttribute::schema();
+    assert_eq!(schema.name, "LinkAttribute");
+    assert_eq!(schema.wildcard, Some("Unknown"));
+
+    let names: Vec<_> = schema.attributes.iter().map(|attr| attr.name).collect();
+    assert_eq!(
+        names,
+        vec!["Unspec", "Address", "IfName", "Children", "Groups", "Wrapped", "Tags", "Vendor"]
+    );
+
+    let if_name = schema.attribute(3).unwrap();
+    assert_eq!(if_name.name, "IfName");
+    assert_eq!(if_name.type_ids, TypeIds::Ids(&[2, 3]));
+    assert!(matches!(if_name.payload, PayloadKind::Value("String")));
+
+    let vendor = schema.attribute(0x150).unwrap();
+    assert_eq!(
+        vendor.type_ids,
+        TypeIds::Range {
+            start: 0x100,
+            end: 0x1ff
+        }
+    );
+    assert!(schema.attribute(0x200).is_none());
+
+    assert!(matches!(schema.attributes[0].payload, PayloadKind::Empty));
+    assert!(matches!(
+        schema.attributes[1].payload,
+        PayloadKind::Value(_)
+    ));
+    assert!(matches!(
+        schema.attributes[6].payload,
+        PayloadKind::Value(_)
+    ));
+}
+
+#[test]
+fn nested_schemas() {
+    let schema = LinkAttribute::schema();
+
+    // Ignores NLA_F_NESTED.
+    let children = schema.attribute(4 | 0x8000).unwrap();
+    assert_eq!(nested_name(&children.payload), "LinkAttribute");
+
+    match schema.attribute(5).unwrap().payload {
+        PayloadKind::Array(element) => assert_eq!(nested_name(element), "GroupAttribute"),
+        kind => panic!("expected an array payload, got {:?}", kind),
+    }
+
+    let wrapped = match schema.attribute(6).unwrap().payload {
+        PayloadKind::Nested(schema) => schema(),
+        kind => panic!("expected a nested payload, got {:?}", kind),
+    };
+    assert_eq!(wrapped.name, "Nested");
+    assert_eq!(
+        nested_name(&wrapped.attributes[0].payload),
+        "GroupAttribute"
+    );
+
+    let borrowed = GroupAttributeRef::schema();
+    assert_eq!(
+        nested_name(&borrowed.attributes[1].payload),
+        "GroupAttributeRef"
+    );
+}
+
+#[test]
+fn struct_schema() {
+    let schema = Link::schema();
+    assert_eq!(schema.name, "Link");
+    assert_eq!(schema.wildcard, None);
+    assert_eq!(schema.attribute(3).unwrap().name, "if_name");
+    assert_eq!(
+        nested_name(&schema.attribute(4).unwrap().payload),
+        "LinkAttribute"
+    );
+}
@@ -0,0 +1,211 @@
+use nldl::attr::deserialize_strict;
+use nldl::attr::Nested;
+use nldl::attr::StrictError;
+use nldl::attr::UnknownAttribute;
+use nldl::message::NetlinkPayloadRequest;
+use nldl::message::NetlinkPayloadResponse;
+
+#[derive(Debug, PartialEq, nldl::attr::Serialize, nldl::attr::Deserialize)]
+enum LenientAttribute {
+    #[nla_type(1)]
+    Id(u32),
+    #[nla_type(2)]
+    Name(String),
+    #[nla_type(_)]
+    Unknown(UnknownAttribute),
+}
+
+#[derive(Debug, PartialEq, nldl::attr::Serialize, nldl::attr::Deserialize)]
+#[nldl(strict, visitor)]
+enum StrictAttribute {
+    #[nla_type(1)]
+    Id(u32),
+    #[nla_type(3)]
+    Children(Nested<ChildAttribute>),
+}
+
+#[derive(Debug, PartialEq, nldl::attr::Serialize, nldl::attr::Deserialize)]
+#[nldl(strict)]
+enum ChildAttribute {
+    #[nla_type(1)]
+    Label(String),
+    #[nla_type(_)]
+    Unknown(UnknownAttribute),
+}
+
+#[derive(Debug, PartialEq, nldl::attr::Deserialize)]
+#[nldl(strict)]
+struct StrictSet {
+    #[nla_type(1)]
+    id: u32,
+    #[nla_type(2)]
+    names: Vec<String>,
+}
+
+fn serialize_payload<T: NetlinkPayloadRequest>(payload: &T) -> Vec<u8> {
+    let mut buf = vec![];
+    payload.serialize(&mut buf).unwrap();
+    buf
+}
+
+#[test]
+fn lenient_by_default() {
+    let bytes = serialize_payload(&vec![
+        LenientAttribute::Id(1),
+        LenientAttribute::Id(2),
+        LenientAttribute::Unknown(UnknownAttribute {
+            ty: 9,
+            flags: 0,
+            payload: vec![],
+        }),
+    ]);
+
+    assert_eq!(
+        Vec::<LenientAttribute>::deserialize(&bytes).unwrap().len(),
+        3
+    );
+}
+
+#[test]
+fn per_call_rejects_unknown_attribute() {
+    let bytes = serialize_payload(&vec![
+        LenientAttribute::Id(1),
+        LenientAttribute::Unknown(UnknownAttribute {
+            ty: 9,
+            flags: 0,
+            payload: vec![],
+        }),
+    ]);
+    let err = deserialize_strict::<Vec<LenientAttribute>>(&bytes).unwrap_err();
+
+    assert!(matches!(
+        err.strict_error(),
+        Some(StrictError::UnknownAttribute { nla_type_id: 9, .. })
+    ));
+}
+
+#[test]
+fn per_call_rejects_duplicate_attribute() {
+    let bytes = serialize_payload(&vec![LenientAttribute::Id(1), LenientAttribute::Id(2)]);
+    let err = deserialize_strict::<Vec<LenientAttribute>>(&bytes).unwrap_err();
+
+    assert!(matches!(
+        err.strict_error(),
+        Some(StrictError::DuplicateAttribute { nla_type_id: 1, .. })
+    ));
+}
+
+#[test]
+fn per_call_rejects_trailing_bytes() {
+    let mut bytes = serialize_payload(&vec![LenientAttribute::Id(1)]);
+    bytes.extend([0, 0]);
+    let err = deserialize_strict::<Vec<LenientAttribute>>(&bytes).unwrap_err();
+
+    assert_eq!(
+        err.strict_error(),
+        Some(StrictError::TrailingBytes { len: 2 })
+    );
+}
+
+#[test]
+fn rejects_zeroed_trailer() {
+    let bytes = [8, 0, 1, 0, 7, 0, 0, 0, 0, 0, 0, 0];
+    let err = deserialize_strict::<Vec<LenientAttribute>>(&bytes).unwrap_err();
+    assert_eq!(
+        err.strict_error(),
+        Some(StrictError::TrailingBytes { len: 4 })
+    );
+
+    let err = StrictSet::deserialize(&bytes).unwrap_err();
+    assert_eq!(
+        err.strict_error(),
+        Some(StrictError::TrailingBytes { len: 4 })
+    );
+}
+
+#[test]
+fn strict_enum_without_wildcard() {
+    let attrs = vec![
+        StrictAttribute::Id(1),
+        StrictAttribute::Children(Nested(vec![ChildAttribute::Label("a".to_string())])),
+    ];
+    let bytes = serialize_payload(&attrs);
+    assert_eq!(Vec::<StrictAttribute>::deserialize(&bytes).unwrap(), attrs);
+
+    let bytes = serialize_payload(&vec![LenientAttribute::Name("a".to_string())]);
+    let err = Vec::<StrictAttribute>::deserialize(&bytes).unwrap_err();
+    assert_eq!(
+        err.strict_error(),
+        Some(StrictError::UnknownAttribute {
+            attribute_struct_name: "strict::StrictAttribute",
+            nla_type_id: 2
+        })
+    );
+}
+
+#[test]
+fn strict_nested_attribute_set() {
+    let attrs = vec![StrictAttribute::Children(Nested(vec![
+        ChildAttribute::Label("a".to_string()),
+        ChildAttribute::Label("b".to_string()),
+    ]))];
+    let bytes = serialize_payload(&attrs);
+    let err = Vec::<StrictAttribute>::deserialize(&bytes).unwrap_err();
+
+    assert!(matches!(
+        err.strict_error(),
+        Some(StrictError::DuplicateAttribute { nla_type_id: 1, .. })
+    ));
+}
+
+#[test]
+fn strict_enum_wildcard_only_serializes() {
+    let attrs = vec![StrictAttribute::Children(Nested(vec![
+        ChildAttribute::Unknown(UnknownAttribute {
+            ty: 5,
+            flags: 0,
+            payload: vec![],
+        }),
+    ]))];
+    let bytes = serialize_payload(&attrs);
+    let err = Vec::<StrictAttribute>::deserialize(&bytes).unwrap_err();
+
+    assert!(matches!(
+        err.strict_error(),
+        Some(StrictError::UnknownAttribute { nla_type_id: 5, .. })
+    ));
+}
+
+#[test]
+fn strict_struct() {
+    let bytes = serialize_payload(&vec![
+        LenientAttribute::Id(1),
+        LenientAttribute::Name("a".to_string()),
+        LenientAttribute::Name("b".to_string()),
+    ]);
+    let set = StrictSet::deserialize(&bytes).unwrap();
+    assert_eq!(set.names, vec!["a", "b"]);
+
+    let bytes = serialize_payload(&vec![LenientAttribute::Id(1), LenientAttribute::Id(1)]);
+    let err = StrictSet::deserialize(&bytes).unwrap_err();
+    assert_eq!(
+        err.strict_error(),
+        Some(StrictError::DuplicateAttribute {
+            attribute_struct_name: "StrictSet",
+            nla_type_id: 1
+        })
+    );
+}
+
+#[test]
+fn strict_visitor() {
+    struct Ignore;
+    impl StrictAttributeVisitor<'_> for Ignore {}
+
+    let bytes = serialize_payload(&vec![LenientAttribute::Id(1), LenientAttribute::Id(1)]);
+    let err = Ignore.visit_attributes(&bytes).unwrap_err();
+    assert!(matches!(
+        err.strict_error(),
+        Some(StrictError::DuplicateAttribute { .. })
+    ));
+}
@@ -0,0 +1,149 @@
+use nldl::attr::deserialize_strict;
+use nldl::attr::StrictError;
+use nldl::attr::UnknownAttribute;
+use nldl::message::NetlinkPayloadRequest;
+use nldl::message::NetlinkPayloadResponse;
+use nldl::SerializeError;
+
+const VENDOR_START: u16 = 0x100;
+const VENDOR_END: u16 = 0x1ff;
+
+#[derive(Debug, PartialEq, nldl::attr::Serialize, nldl::attr::Deserialize)]
+#[nldl(visitor)]
+enum DeviceAttribute {
+    #[nla_type(1)]
+    Index(u32),
+    // Renamed from attribute 3, which older kernels still send.
+    #[nla_type(2 | 3)]
+    Name(String),
+    #[nla_type(4 | 5)]
+    Up,
+    #[nla_type(crate::VENDOR_START..=crate::VENDOR_END)]
+    #[nla_policy(max_len = 4)]
+    Vendor(u16, Vec<u8>),
+    #[nla_type(_)]
+    Unknown(UnknownAttribute),
+}
+
+#[derive(Debug, PartialEq, nldl::attr::Deserialize)]
+struct Device {
+    #[nla_type(2 | 3)]
+    name: String,
+}
+
+fn serialize_payload<T: NetlinkPayloadRequest>(payload: &T) -> Vec<u8> {
+    let mut buf = vec![];
+    payload.serialize(&mut buf).unwrap();
+    buf
+}
+
+fn unknown(ty: u16, payload: &[u8]) -> DeviceAttribute {
+    DeviceAttribute::Unknown(UnknownAttribute {
+        ty,
+        flags: 0,
+        payload: payload.to_vec(),
+    })
+}
+
+#[test]
+fn aliases_deserialize_and_serialize_as_first() {
+    let bytes = serialize_payload(&vec![unknown(3, b"wg0\0"), unknown(5, b"")]);
+    let attrs = Vec::<DeviceAttribute>::deserialize(&bytes).unwrap();
+    assert_eq!(
+        attrs,
+        vec![
+            DeviceAttribute::Name("wg0".to_string()),
+            DeviceAttribute::Up
+        ]
+    );
+
+    assert_eq!(
+        serialize_payload(&attrs),
+        serialize_payload(&vec![unknown(2, b"wg0\0"), unknown(4, b"")])
+    );
+}
+
+#[test]
+fn range_receives_type_id() {
+    let attrs = vec![
+        DeviceAttribute::Vendor(0x100, vec![1]),
+        DeviceAttribute::Vendor(0x1ff, vec![2, 3]),
+        DeviceAttribute::Index(7),
+    ];
+    let bytes = serialize_payload(&attrs);
+    assert_eq!(Vec::<DeviceAttribute>::deserialize(&bytes).unwrap(), attrs);
+
+    let bytes = serialize_payload(&vec![unknown(0x200, &[])]);
+    assert_eq!(
+        Vec::<DeviceAttribute>::deserialize(&bytes).unwrap(),
+        vec![unknown(0x200, &[])]
+    );
+}
+
+#[test]
+fn range_rejects_type_id_outside_range() {
+    let mut buf = vec![];
+    let err = vec![DeviceAttribute::Vendor(0x200, vec![1])]
+        .serialize(&mut buf)
+        .unwrap_err();
+    assert!(matches!(
+        err,
+        SerializeError::TypeIdOutOfRange {
+            attribute: "DeviceAttribute",
+            ty: 0x200,
+            start: VENDOR_START,
+            end: VENDOR_END,
+        }
+    ));
+}
+
+#[test]
+fn range_policy() {
+    let attr = DeviceAttribute::Vendor(0x150, vec![0; 5]);
+    let err = NetlinkPayloadRequest::validate(&attr).unwrap_err();
+    assert_eq!(err.path[0].nla_type_id, 0x150);
+
+    let bytes = serialize_payload(&vec![unknown(0x150, &[0; 5])]);
+    assert!(Vec::<DeviceAttribute>::deserialize(&bytes).is_err());
+}
+
+#[test]
+fn strict_accepts_aliases_and_ranges() {
+    let bytes = serialize_payload(&vec![unknown(3, b"wg0\0"), unknown(0x120, &[])]);
+    assert!(deserialize_strict::<Vec<DeviceAttribute>>(&bytes).is_ok());
+
+    let bytes = serialize_payload(&vec![unknown(0x200, &[])]);
+    let err = deserialize_strict::<Vec<DeviceAttribute>>(&bytes).unwrap_err();
+    assert!(matches!(
+        err.strict_error(),
+        Some(StrictError::UnknownAttribute {
+            nla_type_id: 0x200,
+            ..
+        })
+    ));
+}
+
+#[test]
+fn struct_field_alias() {
+    let bytes = serialize_payload(&vec![unknown(3, b"wg0\0")]);
+    assert_eq!(Device::deserialize(&bytes).unwrap().name, "wg0");
+}
+
+#[test]
+fn visitor_receives_range_type_id() {
+    #[derive(Default)]
+    struct Collect(Vec<(u16, Vec<u8>)>);
+    impl DeviceAttributeVisitor<'_> for Collect {
+        fn visit_vendor(&mut self, ty: u16, value: Vec<u8>) {
+            self.0.push((ty, value));
+        }
+    }
+
+    let bytes = serialize_payload(&vec![
+        DeviceAttribute::Name("wg0".to_string()),
+        DeviceAttribute::Vendor(0x101, vec![9]),
+    ]);
+    let mut collect = Collect::default();
+    collect.visit_attributes(&bytes).unwrap();
+    assert_eq!(collect.0, vec![(0x101, vec![9])]);
+}
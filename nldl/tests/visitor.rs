@@ -0,0 +1,114 @@
+use nldl::attr::Attr;
+use nldl::attr::ParseNetlinkAttributeFromBufferError;
+use nldl::attr::UnknownAttribute;
+use nldl::attr::UnknownAttributeRef;
+use nldl::message::NetlinkPayloadRequest;
+
+#[derive(Debug, PartialEq, nldl::attr::Serialize, nldl::attr::Deserialize)]
+#[nldl(visitor)]
+enum RouteAttribute {
+    #[nla_type(0)]
+    Unspec,
+    #[nla_type(1)]
+    DstAddr(u32),
+    #[nla_type(4)]
+    OifIndex(u32),
+    #[nla_type(15)]
+    TableId(u32),
+    #[nla_type(_)]
+    Unknown(UnknownAttribute),
+}
+
+#[derive(Debug, PartialEq, nldl::attr::Serialize, nldl::attr::Deserialize)]
+#[nldl(visitor)]
+enum LinkAttribute<'a> {
+    #[nla_type(3)]
+    Name(&'a str),
+    #[nla_type(_)]
+    Unknown(UnknownAttributeRef<'a>),
+}
+
+fn serialize_payload<T: NetlinkPayloadRequest>(payload: &T) -> Vec<u8> {
+    let mut buf = vec![];
+    payload.serialize(&mut buf).unwrap();
+    buf
+}
+
+#[derive(Default)]
+struct RouteSummary {
+    unspec: usize,
+    table_ids: Vec<u32>,
+    unknown_types: Vec<u16>,
+}
+
+impl<'a> RouteAttributeVisitor<'a> for RouteSummary {
+    fn visit_unspec(&mut self) {
+        self.unspec += 1;
+    }
+
+    fn visit_table_id(&mut self, value: u32) {
+        self.table_ids.push(value);
+    }
+
+    fn visit_unknown(&mut self, attr: Attr<'a>) {
+        self.unknown_types.push(attr.ty);
+    }
+}
+
+#[test]
+fn visits_each_attribute() {
+    let bytes = serialize_payload(&vec![
+        RouteAttribute::Unspec,
+        RouteAttribute::DstAddr(0x0a000001),
+        RouteAttribute::TableId(254),
+        RouteAttribute::OifIndex(2),
+        RouteAttribute::Unknown(UnknownAttribute {
+            ty: 20,
+            flags: 0,
+            payload: vec![1, 2, 3, 4],
+        }),
+        RouteAttribute::TableId(255),
+    ]);
+
+    let mut summary = RouteSummary::default();
+    summary.visit_attributes(&bytes).unwrap();
+
+    assert_eq!(summary.unspec, 1);
+    assert_eq!(summary.table_ids, vec![254, 255]);
+    assert_eq!(summary.unknown_types, vec![20]);
+}
+
+#[test]
+fn visitor_reports_payload_errors() {
+    // TableId with a 2 byte payload.
+    let bytes = [0x06, 0x00, 0x0f, 0x00, 0xfe, 0x00, 0x00, 0x00];
+
+    let err = RouteSummary::default()
+        .visit_attributes(&bytes)
+        .unwrap_err();
+
+    assert!(matches!(
+        err,
+        ParseNetlinkAttributeFromBufferError::AttributeDeserializeError(err)
+            if err.attribute_struct_name == "RouteAttribute" && err.nla_type_id == 15
+    ));
+}
+
+#[test]
+fn borrowed_visitor_receives_slices_of_buffer() {
+    struct Names<'a>(Vec<&'a str>);
+
+    impl<'a> LinkAttributeVisitor<'a> for Names<'a> {
+        fn visit_name(&mut self, value: &'a str) {
+            self.0.push(value);
+        }
+    }
+
+    let bytes = serialize_payload(&vec![LinkAttribute::Name("wg0"), LinkAttribute::Name("lo")]);
+
+    let mut names = Names(vec![]);
+    names.visit_attributes(&bytes).unwrap();
+
+    assert_eq!(names.0, vec!["wg0", "lo"]);
+    assert_eq!(names.0[0].as_ptr(), bytes[4..].as_ptr());
+}
@@ -0,0 +1,159 @@
+use nldl::attr::DeserializeError;
+use nldl::attr::ParseNetlinkAttributeFromBufferError;
+use nldl::attr::UnknownAttribute;
+use nldl::bytes::BufMut;
+use nldl::message::NetlinkPayloadRequest;
+use nldl::message::NetlinkPayloadResponse;
+use nldl::SerializeError;
+use std::array::TryFromSliceError;
+use std::time::Duration;
+
+/// A `u32` holding milliseconds.
+mod millis {
+    use super::*;
+
+    pub fn serialized_len(_: &Duration) -> usize {
+        4
+    }
+
+    pub fn serialize<B: BufMut>(val: &Duration, buf: &mut B) -> Result<(), SerializeError> {
+        buf.put_u32_ne(val.as_millis() as u32);
+        Ok(())
+    }
+
+    pub fn deserialize(payload: &[u8]) -> Result<Duration, TryFromSliceError> {
+        let millis = u32::from_ne_bytes(payload.try_into()?);
+        Ok(Duration::from_millis(millis.into()))
+    }
+}
+
+fn serialized_list_len(val: &[String]) -> usize {
+    val.join(",").len()
+}
+
+fn serialize_list<B: BufMut>(val: &[String], buf: &mut B) -> Result<(), SerializeError> {
+    buf.put_slice(val.join(",").as_bytes());
+    Ok(())
+}
+
+fn deserialize_list(payload: &[u8]) -> Result<Vec<String>, String> {
+    let list = std::str::from_utf8(payload).map_err(|err| err.to_string())?;
+    Ok(list.split(',').map(str::to_string).collect())
+}
+
+#[derive(Debug, PartialEq, nldl::attr::Serialize, nldl::attr::Deserialize)]
+#[nldl(visitor)]
+enum TimerAttribute {
+    #[nla_type(1)]
+    #[nldl(with = "millis")]
+    Timeout(Duration),
+    #[nla_type(2)]
+    #[nldl(
+        serialize_with = "serialize_list",
+        serialized_len_with = "serialized_list_len",
+        deserialize_with = "deserialize_list"
+    )]
+    #[nla_policy(max_len = 8)]
+    Tags(Vec<String>),
+    #[nla_type(_)]
+    Unknown(UnknownAttribute),
+}
+
+#[derive(Debug, PartialEq, nldl::attr::Deserialize)]
+struct Timer {
+    #[nla_type(1)]
+    #[nldl(with = "millis")]
+    timeout: Option<Duration>,
+    #[nla_type(2)]
+    #[nldl(deserialize_with = "deserialize_list")]
+    tags: Option<Vec<String>>,
+}
+
+fn serialize_payload<T: NetlinkPayloadRequest>(payload: &T) -> Vec<u8> {
+    let mut buf = vec![];
+    payload.serialize(&mut buf).unwrap();
+    buf
+}
+
+fn unknown(ty: u16, payload: &[u8]) -> TimerAttribute {
+    TimerAttribute::Unknown(UnknownAttribute {
+        ty,
+        flags: 0,
+        payload: payload.to_vec(),
+    })
+}
+
+#[test]
+fn custom_codecs_round_trip() {
+    let attrs = vec![
+        TimerAttribute::Timeout(Duration::from_millis(1500)),
+        TimerAttribute::Tags(vec!["a".to_string(), "bc".to_string()]),
+    ];
+    let bytes = serialize_payload(&attrs);
+    assert_eq!(
+        bytes,
+        serialize_payload(&vec![
+            unknown(1, &1500u32.to_ne_bytes()),
+            unknown(2, b"a,bc"),
+        ])
+    );
+    assert_eq!(Vec::<TimerAttribute>::deserialize(&bytes).unwrap(), attrs);
+}
+
+#[test]
+fn policy_uses_custom_serializer_len() {
+    let attr = TimerAttribute::Tags(vec!["abcd".to_string(), "efgh".to_string()]);
+    assert!(NetlinkPayloadRequest::validate(&attr).is_err());
+
+    let attr = TimerAttribute::Tags(vec!["abc".to_string(), "efgh".to_string()]);
+    assert!(NetlinkPayloadRequest::validate(&attr).is_ok());
+}
+
+#[test]
+fn custom_deserializer_error() {
+    let bytes = serialize_payload(&vec![unknown(1, &[0; 3])]);
+    let err = Vec::<TimerAttribute>::deserialize(&bytes).unwrap_err();
+    let err = match err {
+        ParseNetlinkAttributeFromBufferError::AttributeDeserializeError(err) => err,
+        err => panic!("unexpected error: {:?}", err),
+    };
+    let DeserializeError {
+        attribute_struct_name,
+        nla_type_id,
+        source,
+    } = err;
+    assert_eq!(attribute_struct_name, "TimerAttribute");
+    assert_eq!(nla_type_id, 1);
+    assert!(source.is::<TryFromSliceError>());
+
+    let bytes = serialize_payload(&vec![unknown(2, &[0xff])]);
+    assert!(Vec::<TimerAttribute>::deserialize(&bytes).is_err());
+}
+
+#[test]
+fn struct_fields() {
+    let bytes = serialize_payload(&vec![unknown(2, b"a,b"), unknown(1, &20u32.to_ne_bytes())]);
+    assert_eq!(
+        Timer::deserialize(&bytes).unwrap(),
+        Timer {
+            timeout: Some(Duration::from_millis(20)),
+            tags: Some(vec!["a".to_string(), "b".to_string()]),
+        }
+    );
+}
+
+#[test]
+fn visitor() {
+    #[derive(Default)]
+    struct Collect(Vec<Duration>);
+    impl TimerAttributeVisitor<'_> for Collect {
+        fn visit_timeout(&mut self, value: Duration) {
+            self.0.push(value);
+        }
+    }
+
+    let bytes = serialize_payload(&vec![TimerAttribute::Timeout(Duration::from_millis(7))]);
+    let mut collect = Collect::default();
+    collect.visit_attributes(&bytes).unwrap();
+    assert_eq!(collect.0, vec![Duration::from_millis(7)]);
+}
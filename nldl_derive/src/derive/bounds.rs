@@ -0,0 +1,69 @@
+use proc_macro2::Ident;
+use proc_macro2::TokenStream;
+use proc_macro2::TokenTree;
+use quote::ToTokens;
+use syn::parse::Parser;
+use syn::parse_quote;
+use syn::punctuated::Punctuated;
+use syn::GenericParam;
+use syn::Generics;
+use syn::Token;
+use syn::Type;
+use syn::WherePredicate;
+
+/// Copies `generics` and adds the `where` predicates returned by
+/// `predicates` for each of `tys` that mentions a type or const parameter.
+///
+/// Bounding the payload types rather than the parameters themselves lets a
+/// parameter appear wrapped, e.g. `Nested<T>` only requires `T: Serialize`
+/// through `Nested<T>: NetlinkPayloadRequest`. Types without parameters are
+/// left to the compiler to check directly.
+///
+/// Fails, spanned to the payload type, if `predicates` doesn't return valid
+/// `where` predicates for it.
+pub fn with_bounds<'a, F>(
+    generics: &Generics,
+    tys: impl IntoIterator<Item = &'a Type>,
+    predicates: F,
+) -> syn::Result<Generics>
+where
+    F: Fn(&Type) -> TokenStream,
+{
+    let params: Vec<&Ident> = (generics.params.iter())
+        .filter_map(|param| match param {
+            GenericParam::Type(param) => Some(&param.ident),
+            GenericParam::Const(param) => Some(&param.ident),
+            GenericParam::Lifetime(_) => None,
+        })
+        .collect();
+
+    let mut generics = generics.clone();
+    for ty in tys {
+        if mentions_any(ty.to_token_stream(), &params) {
+            let parsed = Punctuated::<WherePredicate, Token![,]>::parse_terminated
+                .parse2(predicates(ty))
+                .map_err(|err| {
+                    syn::Error::new_spanned(
+                        ty,
+                        format!("Unable to generate a where clause for this type: {}", err),
+                    )
+                })?;
+            generics.make_where_clause().predicates.extend(parsed);
+        }
+    }
+    Ok(generics)
+}
+
+/// The type a derive implements traits for, e.g. `LinkAttribute<Data>`.
+pub fn self_ty(name: &Ident, generics: &Generics) -> Type {
+    let (_, ty_generics, _) = generics.split_for_impl();
+    parse_quote! { #name #ty_generics }
+}
+
+fn mentions_any(tokens: TokenStream, params: &[&Ident]) -> bool {
+    tokens.into_iter().any(|token| match token {
+        TokenTree::Ident(ident) => params.iter().any(|param| **param == ident),
+        TokenTree::Group(group) => mentions_any(group.stream(), params),
+        _ => false,
+    })
+}
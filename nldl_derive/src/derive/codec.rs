@@ -0,0 +1,76 @@
+use crate::parsing::nldl_codec::NldlCodec;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Type;
+
+/// Generated calls encoding or decoding a payload, either through the
+/// functions given by `#[nldl(with = "..")]` and friends or through the
+/// payload type's own trait impls.
+pub struct PayloadCodec<'a> {
+    pub codec: &'a NldlCodec,
+    pub ty: &'a Type,
+}
+
+impl<'a> PayloadCodec<'a> {
+    pub fn new(codec: &'a NldlCodec, ty: &'a Type) -> Self {
+        Self { codec, ty }
+    }
+
+    /// Decodes the raw `payload` in scope. Evaluates to a `Result` whose
+    /// error converts into the `source` of a `DeserializeError`.
+    pub fn deserialize(
+        &self,
+        payload_trait: &TokenStream,
+        payload_fn: &TokenStream,
+    ) -> TokenStream {
+        let ty = self.ty;
+        match &self.codec.deserialize {
+            Some(deserialize) => quote! { #deserialize(payload) },
+            None => quote! { <#ty as #payload_trait>::#payload_fn(payload) },
+        }
+    }
+
+    /// Flags such as NLA_F_NESTED are determined by the payload type. Custom
+    /// serializers write plain payloads.
+    pub fn nla_flags(&self) -> TokenStream {
+        let ty = self.ty;
+        match &self.codec.serialize {
+            Some(_) => quote! { 0 },
+            None => quote! { <#ty as ::nldl::message::NetlinkPayloadRequest>::NLA_FLAGS },
+        }
+    }
+
+    /// Length of the payload `val` references.
+    pub fn serialized_len(&self) -> TokenStream {
+        match &self.codec.serialize {
+            Some(with) => {
+                let serialized_len = &with.serialized_len;
+                quote! { #serialized_len(val) }
+            }
+            None => quote! { ::nldl::message::NetlinkPayloadRequest::serialized_len(val) },
+        }
+    }
+
+    /// Writes the payload `val` references to `buf`.
+    pub fn serialize(&self) -> TokenStream {
+        match &self.codec.serialize {
+            Some(with) => {
+                let serialize = &with.serialize;
+                quote! { #serialize(val, buf) }
+            }
+            None => quote! { ::nldl::message::NetlinkPayloadRequest::serialize(val, buf) },
+        }
+    }
+
+    /// Whether the payload type's serialization impl is used, so its bounds
+    /// are needed.
+    pub fn serializes_with_trait(&self) -> bool {
+        self.codec.serialize.is_none()
+    }
+
+    /// Whether the payload type's deserialization impl is used, so its bounds
+    /// are needed.
+    pub fn deserializes_with_trait(&self) -> bool {
+        self.codec.deserialize.is_none()
+    }
+}
@@ -1,84 +1,235 @@
 use proc_macro2::TokenStream;
 use quote::quote;
+use syn::Data;
 use syn::DeriveInput;
 
-use crate::parsing::parse_or_panic::parse_or_panic;
-use crate::parsing::parse_or_panic::ParseOrPanicReturn;
+use super::codec::PayloadCodec;
+use super::deserializable_struct::impl_netlink_attribute_set_deserializable;
+use super::deserialize_traits::DeserializeTraits;
+use super::policy::payload_checks;
+use super::policy::value_checks;
+use super::strict::is_known_type_match;
+use super::strict::unknown_attribute_error;
+use super::type_ids::TypeIds;
+use super::visitor::impl_attribute_visitor;
+use crate::parsing::nldl_attr::find_nldl_flag;
+use crate::parsing::nldl_attr::has_nldl_flag;
+use crate::parsing::parse_enum::parse_enum;
+use crate::parsing::parse_enum::ParsedEnum;
 
-pub fn impl_netlink_attribute_deserializable(ast: &DeriveInput) -> TokenStream {
-    let ParseOrPanicReturn {
+pub fn impl_netlink_attribute_deserializable(ast: &DeriveInput) -> syn::Result<TokenStream> {
+    let visitor = has_nldl_flag(&ast.attrs, "visitor");
+    let strict = has_nldl_flag(&ast.attrs, "strict");
+
+    if let Data::Struct(data_struct) = &ast.data {
+        if let Some(visitor) = find_nldl_flag(&ast.attrs, "visitor") {
+            return Err(syn::Error::new_spanned(
+                visitor,
+                "#[nldl(visitor)] is only supported on attribute enums.",
+            ));
+        }
+        return impl_netlink_attribute_set_deserializable(ast, data_struct);
+    }
+
+    let parsed = parse_enum(ast)?;
+    let visitor_quote = if visitor {
+        impl_attribute_visitor(ast, &parsed, strict)?
+    } else {
+        quote! {}
+    };
+
+    let ParsedEnum {
         name,
         no_payload_idents,
         no_payload_nla_types,
         simple_idents,
         simple_nla_types,
+        simple_payload_tys,
+        simple_policies,
+        simple_codecs,
+        range_idents,
+        range_nla_types,
+        range_payload_tys,
+        range_policies,
+        range_codecs,
         wildcard_ident,
-    } = parse_or_panic(ast);
+        wildcard_payload_ty,
+    } = &parsed;
 
-    let wildcard_ident = match wildcard_ident {
-        None => panic!(
-            "One variant must be marked with #[nla_type(_)] for wildcard handling. None found."
-        ),
-        Some(ident) => ident,
-    };
+    let type_ids = TypeIds::new(
+        name,
+        no_payload_idents
+            .iter()
+            .copied()
+            .zip(no_payload_nla_types)
+            .chain(simple_idents.iter().copied().zip(simple_nla_types)),
+        range_idents.iter().copied().zip(range_nla_types),
+    );
+    let type_id_consts_quote = type_ids.consts();
+    let type_ids_checker_quote = type_ids.checker();
+    let no_payload_patterns: Vec<_> = (no_payload_idents.iter())
+        .zip(no_payload_nla_types)
+        .map(|(ident, ids)| type_ids.ids_pattern(ident, ids))
+        .collect();
+    let simple_patterns: Vec<_> = (simple_idents.iter())
+        .zip(simple_nla_types)
+        .map(|(ident, ids)| type_ids.ids_pattern(ident, ids))
+        .collect();
+    let range_patterns: Vec<_> = range_idents
+        .iter()
+        .map(|ident| type_ids.range_pattern(ident))
+        .collect();
 
-    // Expressions can't be inlined on the left side of a match arm, so we'll assign them to a
-    // namespaced constant first.
-    //
-    // Example:
-    //
-    //     mod InternalTypeIdsNamespace {
-    //         pub const Unspec = EXAMPLE_ZERO_CONST as u16
-    //     }
-    //
-    //     // ...
-    //
-    //     Ok(match ty {
-    //         InternalTypeIdsNamespace::Unspec => ...
-    //     })
-    let type_ids_mod_name = quote! { InternalTypeIdsNamespace };
-    let type_id_consts_quote = quote! {
-        #[allow(non_upper_case_globals)]
-        mod #type_ids_mod_name {
-            #( pub const #no_payload_idents: ::std::primitive::u16 = #no_payload_nla_types; )*
-            #( pub const #simple_idents: ::std::primitive::u16 = #simple_nla_types; )*
+    let name_str = name.to_string();
+    let simple_payload_checks: Vec<_> = (simple_policies.iter())
+        .map(|policy| payload_checks(policy, &name_str))
+        .collect();
+    let simple_value_checks: Vec<_> = (simple_policies.iter())
+        .map(|policy| value_checks(policy, &name_str))
+        .collect();
+    let range_payload_checks: Vec<_> = (range_policies.iter())
+        .map(|policy| payload_checks(policy, &name_str))
+        .collect();
+    let range_value_checks: Vec<_> = (range_policies.iter())
+        .map(|policy| value_checks(policy, &name_str))
+        .collect();
+    let simple_codecs: Vec<_> = (simple_codecs.iter())
+        .zip(simple_payload_tys)
+        .map(|(codec, ty)| PayloadCodec::new(codec, ty))
+        .collect();
+    let range_codecs: Vec<_> = (range_codecs.iter())
+        .zip(range_payload_tys)
+        .map(|(codec, ty)| PayloadCodec::new(codec, ty))
+        .collect();
+
+    let traits = DeserializeTraits::new(&ast.generics)?;
+    let simple_deserializes: Vec<_> = (simple_codecs.iter())
+        .map(|codec| codec.deserialize(&traits.payload_trait, &traits.payload_fn))
+        .collect();
+    let range_deserializes: Vec<_> = (range_codecs.iter())
+        .map(|codec| codec.deserialize(&traits.payload_trait, &traits.payload_fn))
+        .collect();
+    // Borrowing enums deserialize the wildcard payload like any other.
+    // Payloads with a custom deserializer don't need the payload trait.
+    let payload_tys = (simple_codecs.iter())
+        .chain(&range_codecs)
+        .filter(|codec| codec.deserializes_with_trait())
+        .map(|codec| codec.ty)
+        .chain(wildcard_payload_ty.filter(|_| traits.borrowed));
+    let generics = traits.impl_generics(name, &ast.generics, payload_tys)?;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let DeserializeTraits {
+        borrowed,
+        buf_lifetime,
+        attr_trait,
+        attr_fn,
+        attr_with_flags_fn,
+        payload_trait,
+        payload_fn,
+        deserialize_attribute_fn,
+    } = traits;
+
+    // Owned enums copy unknown attributes, including their flag bits, into an
+    // UnknownAttribute. Borrowing enums defer to the wildcard payload type,
+    // which is typically UnknownAttributeRef. Strict enums and enums without
+    // a wildcard reject unknown attributes.
+    let unknown_attribute_error = unknown_attribute_error(&name_str);
+    let (wildcard_quote, fallback_quote) = match wildcard_ident {
+        Some(wildcard_ident) if !strict => {
+            let (wildcard_quote, wildcard_value) = if !borrowed {
+                let unknown = quote! {
+                    Self::#wildcard_ident(::nldl::attr::UnknownAttribute { ty, flags, payload: ::std::vec::Vec::from(payload) })
+                };
+                (
+                    quote! { Ok(#unknown) },
+                    quote! { ::std::option::Option::Some(#unknown) },
+                )
+            } else {
+                (
+                    quote! { Ok(Self::#wildcard_ident(<_ as #attr_trait>::#attr_with_flags_fn(ty, flags, payload)?)) },
+                    quote! { <_ as #attr_trait>::#attr_with_flags_fn(ty, flags, payload).ok().map(Self::#wildcard_ident) },
+                )
+            };
+            let fallback_quote = quote! {
+                fn fallback(ty: ::std::primitive::u16, flags: ::std::primitive::u16, payload: &#buf_lifetime [::std::primitive::u8]) -> ::std::option::Option<Self> {
+                    #wildcard_value
+                }
+            };
+            (wildcard_quote, fallback_quote)
         }
+        _ => (quote! { Err(#unknown_attribute_error) }, quote! {}),
     };
 
-    // Generate an unused enum to check that the same u16 type id value doesn't appear more than
-    // once. The compiler errors are relatively appropriate.
-    let type_ids_enum_checker_quote = quote! {
-        #[repr(u16)]
-        enum InternalTypeIdsEnum {
-            #( #no_payload_idents = #no_payload_nla_types, )*
-            #( #simple_idents = #simple_nla_types, )*
-        }
+    let strict_const_quote = if strict {
+        quote! { const STRICT: ::std::primitive::bool = true; }
+    } else {
+        quote! {}
     };
+    let is_known_type = is_known_type_match(&type_ids.patterns());
 
-    let name_str = name.to_string();
+    Ok(quote! {
+        #type_id_consts_quote
 
-    quote! {
-        impl ::nldl::attr::Deserialize for #name {
-            fn deserialize(ty: ::std::primitive::u16, payload: &[::std::primitive::u8]) -> ::std::result::Result<Self, ::nldl::attr::DeserializeError> {
-                #type_ids_enum_checker_quote
+        #type_ids_checker_quote
 
-                #type_id_consts_quote
+        impl #impl_generics #attr_trait for #name #ty_generics #where_clause {
+            #strict_const_quote
 
+            fn #attr_fn(ty: ::std::primitive::u16, payload: &#buf_lifetime [::std::primitive::u8]) -> ::std::result::Result<Self, ::nldl::attr::DeserializeError> {
+                Self::#attr_with_flags_fn(ty, 0, payload)
+            }
+
+            #[allow(unused_variables)]
+            fn #attr_with_flags_fn(ty: ::std::primitive::u16, flags: ::std::primitive::u16, payload: &#buf_lifetime [::std::primitive::u8]) -> ::std::result::Result<Self, ::nldl::attr::DeserializeError> {
                 match ty {
-                    #( #type_ids_mod_name::#no_payload_idents => Ok(Self::#no_payload_idents), )*
-                    #( #type_ids_mod_name::#simple_idents =>
-                        match ::nldl::message::NetlinkPayloadResponse::deserialize(payload) {
-                            Ok(val) => Ok(Self::#simple_idents(val)),
+                    #( #no_payload_patterns => Ok(Self::#no_payload_idents), )*
+                    #( #simple_patterns => {
+                        #simple_payload_checks
+                        match #simple_deserializes {
+                            Ok(val) => {
+                                #simple_value_checks
+                                Ok(Self::#simple_idents(val))
+                            }
                             Err(err) => Err(::nldl::attr::DeserializeError {
                                 attribute_struct_name: #name_str,
                                 nla_type_id: ty,
-                                source: Box::new(err)
+                                source: ::std::convert::Into::into(err)
                             })
-                        },
-                    )*
-                    _ => Ok(Self::#wildcard_ident(::nldl::attr::UnknownAttribute { ty, payload: ::std::vec::Vec::from(payload) })),
+                        }
+                    }, )*
+                    #( #range_patterns => {
+                        #range_payload_checks
+                        match #range_deserializes {
+                            Ok(val) => {
+                                #range_value_checks
+                                Ok(Self::#range_idents(ty, val))
+                            }
+                            Err(err) => Err(::nldl::attr::DeserializeError {
+                                attribute_struct_name: #name_str,
+                                nla_type_id: ty,
+                                source: ::std::convert::Into::into(err)
+                            })
+                        }
+                    }, )*
+                    _ => #wildcard_quote,
                 }
             }
+
+            fn is_known_type(ty: ::std::primitive::u16) -> ::std::primitive::bool {
+                #is_known_type
+            }
+
+            #fallback_quote
         }
-    }
+
+        impl #impl_generics #payload_trait for #name #ty_generics #where_clause {
+            type Error = ::nldl::attr::ParseNetlinkAttributeFromBufferError;
+
+            fn #payload_fn(buf: &#buf_lifetime [::std::primitive::u8]) -> ::std::result::Result<Self, Self::Error> {
+                #deserialize_attribute_fn(buf)
+            }
+        }
+
+        #visitor_quote
+    })
 }
@@ -0,0 +1,228 @@
+use super::codec::PayloadCodec;
+use super::deserialize_traits::DeserializeTraits;
+use super::policy::payload_checks;
+use super::policy::value_checks;
+use super::strict::is_known_type_match;
+use super::type_ids::TypeIds;
+use crate::parsing::nla_type_field::parse_fields;
+use crate::parsing::nla_type_field::NetlinkAttributeFieldKind;
+use crate::parsing::nldl_attr::has_nldl_flag;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::DataStruct;
+use syn::DeriveInput;
+
+/// Structs are deserialized from a buffer containing a sequence of netlink
+/// attributes. Each field is filled from the attribute with the matching type
+/// ID. Attributes without a matching field are skipped unless the struct is
+/// marked with `#[nldl(strict)]`.
+pub fn impl_netlink_attribute_set_deserializable(
+    ast: &DeriveInput,
+    data_struct: &DataStruct,
+) -> syn::Result<TokenStream> {
+    let name = &ast.ident;
+    let fields = parse_fields(data_struct)?;
+    let codecs: Vec<_> = (fields.iter())
+        .map(|field| PayloadCodec::new(&field.codec, field.payload_ty()))
+        .collect();
+    let traits = DeserializeTraits::new(&ast.generics)?;
+    let generics = traits.impl_generics(
+        name,
+        &ast.generics,
+        (codecs.iter())
+            .filter(|codec| codec.deserializes_with_trait())
+            .map(|codec| codec.ty),
+    )?;
+    let field_deserializes: Vec<_> = (codecs.iter())
+        .map(|codec| codec.deserialize(&traits.payload_trait, &traits.payload_fn))
+        .collect();
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let DeserializeTraits {
+        borrowed: traits_borrowed,
+        buf_lifetime,
+        attr_trait,
+        attr_fn,
+        payload_trait,
+        payload_fn,
+        ..
+    } = traits;
+
+    let field_idents: Vec<_> = fields.iter().map(|field| field.ident).collect();
+    let field_names: Vec<_> = field_idents.iter().map(|ident| ident.to_string()).collect();
+    let field_nla_types: Vec<_> = fields.iter().map(|field| &field.ty).collect();
+    let payload_tys: Vec<_> = fields.iter().map(|field| field.payload_ty()).collect();
+    let name_str = name.to_string();
+    let field_payload_checks: Vec<_> = (fields.iter())
+        .map(|field| payload_checks(&field.policy, &name_str))
+        .collect();
+    let field_value_checks: Vec<_> = (fields.iter())
+        .map(|field| value_checks(&field.policy, &name_str))
+        .collect();
+
+    // Same approach as the enum derive. See TypeIds.
+    let type_ids = TypeIds::new(name, field_idents.iter().copied().zip(field_nla_types), []);
+    let namespace = type_ids.namespace();
+    let type_id_consts_quote = type_ids.consts();
+    let type_ids_checker_quote = type_ids.checker();
+    let field_patterns = type_ids.patterns();
+
+    // Vec fields collect every appearance of their attribute, so only the
+    // rest are checked for duplicates.
+    let repeated_patterns: Vec<_> = (fields.iter())
+        .filter(|field| matches!(field.kind, NetlinkAttributeFieldKind::Repeated { .. }))
+        .map(|field| type_ids.ids_pattern(field.ident, &field.ty))
+        .collect();
+    let is_known_type = is_known_type_match(&field_patterns);
+    let may_repeat = is_known_type_match(&repeated_patterns);
+    let strict_check_quote = if has_nldl_flag(&ast.attrs, "strict") {
+        quote! { <Self as ::nldl::attr::DeserializeStrict>::check_strict(buf)?; }
+    } else {
+        quote! {}
+    };
+
+    let accumulator_decls: Vec<_> = fields.iter().map(|field| {
+        let ident = field.ident;
+        let payload_ty = field.payload_ty();
+        match field.kind {
+            NetlinkAttributeFieldKind::Repeated { .. } => quote! {
+                let mut #ident: ::std::vec::Vec<#payload_ty> = ::std::vec::Vec::new();
+            },
+            _ => quote! {
+                let mut #ident: ::std::option::Option<#payload_ty> = ::std::option::Option::None;
+            },
+        }
+    })
+    .collect();
+
+    let accumulator_updates: Vec<_> = fields
+        .iter()
+        .map(|field| {
+            let ident = field.ident;
+            match field.kind {
+                NetlinkAttributeFieldKind::Repeated { .. } => quote! { #ident.push(val) },
+                _ => quote! { #ident = ::std::option::Option::Some(val) },
+            }
+        })
+        .collect();
+
+    // Lenient deserialization skips fields that fail to decode, unless the
+    // struct can't be built without them.
+    let lenient_failures = fields.iter().map(|field| match field.kind {
+        NetlinkAttributeFieldKind::Required => quote! { return Err(err) },
+        _ => quote! { diagnostics.push(::nldl::attr::LenientDiagnostic::from(&err)) },
+    });
+    let strict_failures = fields.iter().map(|_| quote! { return Err(err) });
+
+    let field_values: Vec<_> = fields
+        .iter()
+        .zip(&field_names)
+        .map(|(field, field_name)| {
+            let ident = field.ident;
+            match field.kind {
+                NetlinkAttributeFieldKind::Required => quote! {
+                    #ident.ok_or(::nldl::attr::MissingAttributeError {
+                        attribute_struct_name: #name_str,
+                        field_name: #field_name,
+                        nla_type_id: #namespace::#ident,
+                    })?
+                },
+                NetlinkAttributeFieldKind::Defaulted => quote! { #ident.unwrap_or_default() },
+                _ => quote! { #ident },
+            }
+        })
+        .collect();
+
+    // Fills the fields from `buf`, calling the given failure handler with
+    // `err` in scope when an attribute fails to decode.
+    let body = |failures: Vec<TokenStream>| {
+        quote! {
+            #strict_check_quote
+
+            #( #accumulator_decls )*
+
+            #[allow(unused_variables)]
+            ::nldl::attr::for_each_attribute(buf, |ty, payload| {
+                match ty {
+                    #( #field_patterns => {
+                        let decoded = (|| -> ::std::result::Result<#payload_tys, ::nldl::attr::DeserializeError> {
+                            #field_payload_checks
+                            let val: #payload_tys = match #field_deserializes {
+                                Ok(val) => val,
+                                Err(err) => return Err(::nldl::attr::DeserializeError {
+                                    attribute_struct_name: #name_str,
+                                    nla_type_id: ty,
+                                    source: ::std::convert::Into::into(err)
+                                })
+                            };
+                            #field_value_checks
+                            Ok(val)
+                        })();
+                        match decoded {
+                            Ok(val) => #accumulator_updates,
+                            Err(err) => #failures,
+                        }
+                    }, )*
+                    _ => {}
+                }
+                Ok(())
+            })?;
+
+            Ok(Self {
+                #( #field_idents: #field_values, )*
+            })
+        }
+    };
+    let deserialize_body = body(strict_failures.collect());
+
+    // The lenient impl is only generated for owned structs, like Lenient is
+    // only implemented for owned enums.
+    let lenient_quote = if traits_borrowed {
+        quote! {}
+    } else {
+        let lenient_body = body(lenient_failures.collect());
+        quote! {
+            impl #impl_generics ::nldl::attr::DeserializeLenient for #name #ty_generics #where_clause {
+                #[allow(unused_variables)]
+                fn deserialize_lenient(
+                    buf: &[::std::primitive::u8],
+                    diagnostics: &mut ::std::vec::Vec<::nldl::attr::LenientDiagnostic>,
+                ) -> ::std::result::Result<Self, ::nldl::attr::ParseNetlinkAttributeFromBufferError> {
+                    #lenient_body
+                }
+            }
+        }
+    };
+
+    Ok(quote! {
+        #type_id_consts_quote
+
+        #type_ids_checker_quote
+
+        impl #impl_generics #payload_trait for #name #ty_generics #where_clause {
+            type Error = ::nldl::attr::ParseNetlinkAttributeFromBufferError;
+
+            fn #payload_fn(buf: &#buf_lifetime [::std::primitive::u8]) -> ::std::result::Result<Self, Self::Error> {
+                #deserialize_body
+            }
+        }
+
+        #lenient_quote
+
+        impl #impl_generics ::nldl::attr::DeserializeStrict for #name #ty_generics #where_clause {
+            fn check_strict(buf: &[::std::primitive::u8]) -> ::std::result::Result<(), ::nldl::attr::ParseNetlinkAttributeFromBufferError> {
+                ::nldl::attr::check_strict(buf, #name_str, |ty| #is_known_type, |ty| #may_repeat)
+            }
+        }
+
+        impl #impl_generics #attr_trait for #name #ty_generics #where_clause {
+            fn #attr_fn(ty: ::std::primitive::u16, payload: &#buf_lifetime [::std::primitive::u8]) -> ::std::result::Result<Self, ::nldl::attr::DeserializeError> {
+                <Self as #payload_trait>::#payload_fn(payload)
+                    .map_err(|err| ::nldl::attr::DeserializeError {
+                        attribute_struct_name: #name_str,
+                        nla_type_id: ty,
+                        source: Box::new(err)
+                    })
+            }
+        }
+    })
+}
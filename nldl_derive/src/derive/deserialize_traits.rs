@@ -0,0 +1,87 @@
+use super::bounds::self_ty;
+use super::bounds::with_bounds;
+use proc_macro2::Ident;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Generics;
+use syn::Type;
+
+/// The traits and functions generated `Deserialize` code refers to.
+///
+/// Types without a lifetime parameter implement the owned `Deserialize` and
+/// `NetlinkPayloadResponse` traits. Types with a lifetime parameter (e.g.
+/// `enum Attr<'a> { Name(&'a str) }`) implement the borrowing versions so their
+/// fields can point into the receive buffer. Type and const parameters are
+/// passed through in both cases.
+pub struct DeserializeTraits {
+    /// Whether the borrowing traits are implemented.
+    pub borrowed: bool,
+    /// Lifetime of the `payload`/`buf` slices, e.g. `'a`. Empty when owned.
+    pub buf_lifetime: TokenStream,
+    pub attr_trait: TokenStream,
+    pub attr_fn: TokenStream,
+    /// The variant of `attr_fn` that's also given the header's flag bits.
+    pub attr_with_flags_fn: TokenStream,
+    pub payload_trait: TokenStream,
+    pub payload_fn: TokenStream,
+    pub deserialize_attribute_fn: TokenStream,
+}
+
+impl DeserializeTraits {
+    pub fn new(generics: &Generics) -> syn::Result<Self> {
+        let mut lifetimes = generics.lifetimes();
+        let lifetime = lifetimes.next().map(|def| &def.lifetime);
+        if let Some(extra) = lifetimes.next() {
+            return Err(syn::Error::new_spanned(
+                extra,
+                "Deriving Deserialize is only supported on types with at most one lifetime parameter.",
+            ));
+        }
+
+        Ok(match lifetime {
+            None => Self {
+                borrowed: false,
+                buf_lifetime: quote! {},
+                attr_trait: quote! { ::nldl::attr::Deserialize },
+                attr_fn: quote! { deserialize },
+                attr_with_flags_fn: quote! { deserialize_with_flags },
+                payload_trait: quote! { ::nldl::message::NetlinkPayloadResponse },
+                payload_fn: quote! { deserialize },
+                deserialize_attribute_fn: quote! { ::nldl::attr::deserialize_attribute },
+            },
+            Some(lifetime) => Self {
+                borrowed: true,
+                buf_lifetime: quote! { #lifetime },
+                attr_trait: quote! { ::nldl::attr::DeserializeBorrowed<#lifetime> },
+                attr_fn: quote! { deserialize_borrowed },
+                attr_with_flags_fn: quote! { deserialize_borrowed_with_flags },
+                payload_trait: quote! { ::nldl::message::NetlinkPayloadResponseBorrowed<#lifetime> },
+                payload_fn: quote! { deserialize_borrowed },
+                deserialize_attribute_fn: quote! { ::nldl::attr::deserialize_attribute_borrowed },
+            },
+        })
+    }
+
+    /// Generics for the generated impls. Payload types that mention a type
+    /// parameter must implement the payload trait with an error that can be
+    /// boxed into a `DeserializeError`. The deserialized type must satisfy
+    /// the traits' `Debug + PartialEq` supertraits.
+    pub fn impl_generics<'a>(
+        &self,
+        name: &Ident,
+        generics: &Generics,
+        payload_tys: impl IntoIterator<Item = &'a Type>,
+    ) -> syn::Result<Generics> {
+        let payload_trait = &self.payload_trait;
+        let generics = with_bounds(generics, payload_tys, |ty| {
+            quote! {
+                #ty: #payload_trait,
+                <#ty as #payload_trait>::Error: ::std::marker::Send + ::std::marker::Sync + 'static,
+            }
+        })?;
+        let self_ty = self_ty(name, &generics);
+        with_bounds(&generics, [&self_ty], |ty| {
+            quote! { #ty: ::std::fmt::Debug + ::std::cmp::PartialEq }
+        })
+    }
+}
@@ -0,0 +1,179 @@
+use super::bounds::self_ty;
+use super::bounds::with_bounds;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Data;
+use syn::DeriveInput;
+use syn::Index;
+use syn::Member;
+use syn::Meta;
+use syn::NestedMeta;
+
+pub fn impl_netlink_struct(ast: &DeriveInput) -> syn::Result<TokenStream> {
+    let data_struct = match &ast.data {
+        Data::Struct(data_struct) => data_struct,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &ast.ident,
+                "nldl::Struct may only be derived for structs.",
+            ))
+        }
+    };
+    check_repr_c(ast)?;
+
+    let name = &ast.ident;
+    let members: Vec<Member> = (data_struct.fields.iter())
+        .enumerate()
+        .map(|(i, field)| match &field.ident {
+            Some(ident) => Member::Named(ident.clone()),
+            None => Member::Unnamed(Index::from(i)),
+        })
+        .collect();
+    let tys: Vec<_> = data_struct.fields.iter().map(|field| &field.ty).collect();
+    let temporaries: Vec<_> = (0..members.len())
+        .map(|i| quote::format_ident!("__nldl_field_{}", i))
+        .collect();
+
+    // Mirrors how a C compiler lays out a struct. Each field starts at the next
+    // multiple of its own alignment, and the struct is padded at the end to a
+    // multiple of its largest field alignment.
+    let end_of_fields = tys.iter().fold(quote! { 0 }, |offset, ty| {
+        quote! {
+            ::nldl::message::align_up(#offset, <#ty as ::nldl::message::FixedLayout>::ALIGN)
+                + <#ty as ::nldl::message::FixedLayout>::SIZE
+        }
+    });
+    let align = tys.iter().fold(quote! { 1 }, |align, ty| {
+        quote! {
+            ::nldl::message::max_align(#align, <#ty as ::nldl::message::FixedLayout>::ALIGN)
+        }
+    });
+
+    // Field types that depend on type parameters must have a layout.
+    let generics = with_bounds(&ast.generics, tys.iter().copied(), |ty| {
+        quote! { #ty: ::nldl::message::FixedLayout }
+    })?;
+    let self_ty = self_ty(name, &generics);
+    let response_generics = with_bounds(&generics, [&self_ty], |ty| {
+        quote! { #ty: ::std::fmt::Debug + ::std::cmp::PartialEq }
+    })?;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+    let (response_impl_generics, _, response_where_clause) = response_generics.split_for_impl();
+
+    // The computed layout should always match what rustc picked for the
+    // #[repr(C)] struct. A mismatch means a field type's FixedLayout impl
+    // disagrees with its in-memory representation. Generic structs are
+    // checked once SIZE or ALIGN is used with concrete types.
+    let layout_check_quote = if ast.generics.params.is_empty() {
+        quote! {
+            const _: () = {
+                let _ = <#name as ::nldl::message::FixedLayout>::SIZE;
+                let _ = <#name as ::nldl::message::FixedLayout>::ALIGN;
+            };
+        }
+    } else {
+        quote! {}
+    };
+
+    Ok(quote! {
+        impl #impl_generics ::nldl::message::FixedLayout for #name #ty_generics #where_clause {
+            const SIZE: ::std::primitive::usize = {
+                let size = ::nldl::message::align_up(#end_of_fields, #align);
+                ::std::assert!(
+                    size == ::std::mem::size_of::<Self>(),
+                    "nldl::Struct computed a size that differs from the #[repr(C)] layout."
+                );
+                size
+            };
+            const ALIGN: ::std::primitive::usize = {
+                let align = #align;
+                ::std::assert!(
+                    align == ::std::mem::align_of::<Self>(),
+                    "nldl::Struct computed an alignment that differs from the #[repr(C)] layout."
+                );
+                align
+            };
+
+            #[allow(unused_variables, unused_assignments, unused_mut)]
+            fn write_fixed<__NldlB: ::nldl::bytes::BufMut>(&self, __nldl_buf: &mut __NldlB) {
+                let mut __nldl_offset: ::std::primitive::usize = 0;
+                #(
+                    let __nldl_aligned = ::nldl::message::align_up(__nldl_offset, <#tys as ::nldl::message::FixedLayout>::ALIGN);
+                    ::nldl::bytes::BufMut::put_bytes(__nldl_buf, 0, __nldl_aligned - __nldl_offset);
+                    ::nldl::message::FixedLayout::write_fixed(&self.#members, __nldl_buf);
+                    __nldl_offset = __nldl_aligned + <#tys as ::nldl::message::FixedLayout>::SIZE;
+                )*
+                ::nldl::bytes::BufMut::put_bytes(__nldl_buf, 0, <Self as ::nldl::message::FixedLayout>::SIZE - __nldl_offset);
+            }
+
+            #[allow(unused_variables, unused_assignments, unused_mut)]
+            fn read_fixed(__nldl_buf: &[::std::primitive::u8]) -> Self {
+                let mut __nldl_offset: ::std::primitive::usize = 0;
+                #(
+                    __nldl_offset = ::nldl::message::align_up(__nldl_offset, <#tys as ::nldl::message::FixedLayout>::ALIGN);
+                    let #temporaries = <#tys as ::nldl::message::FixedLayout>::read_fixed(
+                        &__nldl_buf[__nldl_offset..__nldl_offset + <#tys as ::nldl::message::FixedLayout>::SIZE],
+                    );
+                    __nldl_offset += <#tys as ::nldl::message::FixedLayout>::SIZE;
+                )*
+                Self { #( #members: #temporaries, )* }
+            }
+        }
+
+        #layout_check_quote
+
+        impl #impl_generics ::nldl::message::NetlinkPayloadRequest for #name #ty_generics #where_clause {
+            fn serialized_len(&self) -> ::std::primitive::usize {
+                <Self as ::nldl::message::FixedLayout>::SIZE
+            }
+
+            fn serialize<__NldlB: ::nldl::bytes::BufMut>(
+                &self,
+                buf: &mut __NldlB,
+            ) -> ::std::result::Result<(), ::nldl::SerializeError> {
+                ::nldl::message::serialize_fixed(self, buf)
+            }
+        }
+
+        impl #response_impl_generics ::nldl::message::NetlinkPayloadResponse for #name #ty_generics #response_where_clause {
+            type Error = ::nldl::message::ParseFixedLayoutError;
+
+            fn deserialize(buf: &[::std::primitive::u8]) -> ::std::result::Result<Self, Self::Error> {
+                ::nldl::message::deserialize_fixed(buf)
+            }
+        }
+    })
+}
+
+fn check_repr_c(ast: &DeriveInput) -> syn::Result<()> {
+    let repr_args: Vec<NestedMeta> = (ast.attrs.iter())
+        .filter(|attr| attr.path.is_ident("repr"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(list)) => Some(list.nested),
+            _ => None,
+        })
+        .flatten()
+        .collect();
+
+    let find_repr = |name: &str| {
+        repr_args.iter().find(|arg| match arg {
+            NestedMeta::Meta(meta) => meta.path().is_ident(name),
+            NestedMeta::Lit(_) => false,
+        })
+    };
+
+    if find_repr("C").is_none() {
+        return Err(syn::Error::new_spanned(
+            &ast.ident,
+            "nldl::Struct may only be derived for #[repr(C)] structs.",
+        ));
+    }
+    if let Some(packed) = find_repr("packed") {
+        return Err(syn::Error::new_spanned(
+            packed,
+            "nldl::Struct does not support #[repr(packed)] structs.",
+        ));
+    }
+
+    Ok(())
+}
@@ -0,0 +1,228 @@
+use crate::parsing::errors::Errors;
+use crate::parsing::nla_type::is_underscore;
+use proc_macro2::Ident;
+use proc_macro2::TokenStream;
+use quote::quote;
+use syn::Data;
+use syn::DeriveInput;
+use syn::Fields;
+use syn::Meta;
+use syn::NestedMeta;
+
+const NLA_VALUE_ATTR: &str = "nla_value";
+const SUPPORTED_REPRS: [&str; 3] = ["u8", "u16", "u32"];
+
+/// Fieldless enums whose variants map to the integer values of a kernel enum.
+///
+/// ```ignore
+/// #[derive(nldl::Enum)]
+/// #[nldl(repr(u8))]
+/// enum OperState {
+///     #[nla_value(0)]
+///     Unknown,
+///     #[nla_value(6)]
+///     Up,
+///     #[nla_value(_)]
+///     Other(u32),
+/// }
+/// ```
+pub fn impl_netlink_int_enum(ast: &DeriveInput) -> syn::Result<TokenStream> {
+    let data_enum = match &ast.data {
+        Data::Enum(data_enum) => data_enum,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &ast.ident,
+                "nldl::Enum may only be used on enums.",
+            ))
+        }
+    };
+    let name = &ast.ident;
+    let mut errors = Errors::default();
+    let repr = errors.ok(parse_repr(ast));
+
+    let mut value_idents: Vec<&Ident> = vec![];
+    let mut values: Vec<TokenStream> = vec![];
+    let mut other_ident: Option<&Ident> = None;
+
+    for variant in &data_enum.variants {
+        let ident = &variant.ident;
+        let value = (variant.attrs)
+            .iter()
+            .find(|attr| attr.path.is_ident(NLA_VALUE_ATTR))
+            .map(|attr| attr.parse_args::<TokenStream>())
+            .transpose()
+            .and_then(|value| {
+                value.ok_or_else(|| {
+                    syn::Error::new_spanned(
+                        ident,
+                        format!(
+                            "Please annotate all enum variants with #[nla_value(..)]. Saw \"{}\" unannotated.",
+                            ident
+                        ),
+                    )
+                })
+            });
+        let value = match errors.ok(value) {
+            Some(value) => value,
+            None => continue,
+        };
+
+        if is_underscore(value.clone()) {
+            if let Some(other_ident) = other_ident {
+                errors.push(syn::Error::new_spanned(
+                    value,
+                    format!(
+                        "Only one variant may be marked with #[nla_value(_)]. \"{}\" is already marked.",
+                        other_ident
+                    ),
+                ));
+                continue;
+            }
+            match &variant.fields {
+                Fields::Unnamed(fields) if fields.unnamed.len() == 1 => {}
+                _ => errors.push(syn::Error::new_spanned(
+                    variant,
+                    format!(
+                        "The #[nla_value(_)] variant \"{}\" must hold a single u32, such as Other(u32).",
+                        ident
+                    ),
+                )),
+            }
+            other_ident = Some(ident);
+            continue;
+        }
+
+        if !variant.fields.is_empty() {
+            errors.push(syn::Error::new_spanned(
+                &variant.fields,
+                format!(
+                    "Only the #[nla_value(_)] variant may have fields. Saw fields on \"{}\".",
+                    ident
+                ),
+            ));
+            continue;
+        }
+        value_idents.push(ident);
+        values.push(value);
+    }
+
+    if other_ident.is_none() {
+        errors.push(syn::Error::new_spanned(
+            name,
+            "One variant must be marked with #[nla_value(_)] to hold unknown values. None found.",
+        ));
+    }
+    errors.finish()?;
+    let (repr, other_ident) = (repr.unwrap(), other_ident.unwrap());
+
+    // Same approach as the attribute enum derive. Expressions are assigned to
+    // namespaced constants so they can be used as match patterns, and an unused
+    // enum with the chosen repr catches duplicate and overflowing values.
+    let values_mod_name = quote! { InternalValuesNamespace };
+    let value_consts_quote = quote! {
+        #[allow(non_upper_case_globals)]
+        mod #values_mod_name {
+            #( pub const #value_idents: #repr = #values; )*
+        }
+    };
+    let values_enum_checker_quote = if value_idents.is_empty() {
+        quote! {}
+    } else {
+        quote! {
+            #[allow(dead_code)]
+            #[repr(#repr)]
+            enum InternalValuesEnum {
+                #( #value_idents = #values, )*
+            }
+        }
+    };
+
+    // Other values must fit the repr and mustn't collide with a named variant,
+    // or they wouldn't deserialize back into Other.
+    let name_str = name.to_string();
+    let named_values_arm = if value_idents.is_empty() {
+        quote! {}
+    } else {
+        quote! { ::std::result::Result::Ok(#( #values_mod_name::#value_idents )|*) => ::std::option::Option::None, }
+    };
+
+    Ok(quote! {
+        impl ::nldl::message::NetlinkPayloadRequest for #name {
+            fn serialized_len(&self) -> ::std::primitive::usize {
+                ::std::mem::size_of::<#repr>()
+            }
+
+            fn serialize<__NldlB: ::nldl::bytes::BufMut>(
+                &self,
+                buf: &mut __NldlB,
+            ) -> ::std::result::Result<(), ::nldl::SerializeError> {
+                #value_consts_quote
+
+                let value: #repr = match self {
+                    #( Self::#value_idents => #values_mod_name::#value_idents, )*
+                    Self::#other_ident(other) => {
+                        #[allow(unreachable_patterns)]
+                        let value = match <#repr as ::std::convert::TryFrom<::std::primitive::u32>>::try_from(*other) {
+                            #named_values_arm
+                            ::std::result::Result::Ok(value) => ::std::option::Option::Some(value),
+                            ::std::result::Result::Err(_) => ::std::option::Option::None,
+                        };
+                        value.ok_or(::nldl::SerializeError::InvalidEnumValue {
+                            enum_name: #name_str,
+                            value: *other,
+                        })?
+                    }
+                };
+                ::nldl::message::NetlinkPayloadRequest::serialize(&value, buf)
+            }
+        }
+
+        impl ::nldl::message::NetlinkPayloadResponse for #name {
+            type Error = ::nldl::utils::ParseNlaIntError;
+
+            fn deserialize(buf: &[::std::primitive::u8]) -> ::std::result::Result<Self, Self::Error> {
+                #values_enum_checker_quote
+
+                #value_consts_quote
+
+                let value = <#repr as ::nldl::message::NetlinkPayloadResponse>::deserialize(buf)?;
+                Ok(match value {
+                    #( #values_mod_name::#value_idents => Self::#value_idents, )*
+                    value => Self::#other_ident(::std::primitive::u32::from(value)),
+                })
+            }
+        }
+    })
+}
+
+fn parse_repr(ast: &DeriveInput) -> syn::Result<Ident> {
+    let repr = (ast.attrs.iter())
+        .filter(|attr| attr.path.is_ident("nldl"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(list)) => Some(list.nested),
+            _ => None,
+        })
+        .flatten()
+        .find_map(|arg| match arg {
+            NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("repr") => {
+                list.nested.into_iter().next()
+            }
+            _ => None,
+        });
+
+    match repr {
+        Some(NestedMeta::Meta(Meta::Path(path)))
+            if SUPPORTED_REPRS.iter().any(|repr| path.is_ident(repr)) =>
+        {
+            Ok(path.get_ident().unwrap().clone())
+        }
+        Some(repr) => Err(syn::Error::new_spanned(
+            repr,
+            "Unsupported integer width. Supported widths are u8, u16 and u32.",
+        )),
+        None => Err(syn::Error::new_spanned(
+            &ast.ident,
+            "nldl::Enum requires an integer width such as #[nldl(repr(u8))]. Supported widths are u8, u16 and u32.",
+        )),
+    }
+}
@@ -1,2 +1,14 @@
+pub mod bounds;
+pub mod codec;
 pub mod deserializable;
+pub mod deserializable_struct;
+pub mod deserialize_traits;
+pub mod fixed_struct;
+pub mod int_enum;
+pub mod naming;
+pub mod policy;
+pub mod schema;
 pub mod serializable;
+pub mod strict;
+pub mod type_ids;
+pub mod visitor;
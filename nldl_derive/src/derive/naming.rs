@@ -0,0 +1,24 @@
+use proc_macro2::Ident;
+use syn::ext::IdentExt;
+
+/// `FamilyId` becomes `family_id`. Snake case names are kept as they are.
+pub fn snake_case(ident: &Ident) -> String {
+    let mut snake = String::new();
+    let chars: Vec<char> = ident.unraw().to_string().chars().collect();
+
+    for (i, c) in chars.iter().enumerate() {
+        if c.is_uppercase() && i > 0 {
+            let prev = chars[i - 1];
+            let next_is_lower = matches!(chars.get(i + 1), Some(next) if next.is_lowercase());
+            if prev.is_lowercase()
+                || prev.is_ascii_digit()
+                || (prev.is_uppercase() && next_is_lower)
+            {
+                snake.push('_');
+            }
+        }
+        snake.extend(c.to_lowercase());
+    }
+
+    snake
+}
@@ -0,0 +1,111 @@
+use super::codec::PayloadCodec;
+use crate::parsing::nla_policy::NlaPolicy;
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Checks on the raw attribute payload before it's decoded. Expects `ty` and
+/// `payload` in scope and returns a `DeserializeError` from the enclosing
+/// function or closure on failure.
+pub fn payload_checks(policy: &NlaPolicy, name_str: &str) -> TokenStream {
+    let mut checks = vec![];
+
+    if policy.min_len.is_some() || policy.max_len.is_some() {
+        let min_len = optional(&policy.min_len);
+        let max_len = optional(&policy.max_len);
+        checks.push(quote! {
+            ::nldl::attr::policy::check_len(payload.len(), #min_len, #max_len)
+        });
+    }
+    if let Some(max_strlen) = &policy.max_strlen {
+        checks.push(quote! {
+            ::nldl::attr::policy::check_received_strlen(payload, #max_strlen)
+        });
+    }
+
+    return_deserialize_error(checks, name_str)
+}
+
+/// Checks on the decoded payload. Expects `ty` and `val` in scope.
+pub fn value_checks(policy: &NlaPolicy, name_str: &str) -> TokenStream {
+    let checks: Vec<_> = range_check(policy, quote! { &val }).into_iter().collect();
+    return_deserialize_error(checks, name_str)
+}
+
+/// All checks for an attribute about to be sent. Expects `val` to be a
+/// reference to the payload and evaluates to a
+/// `Result<(), ::nldl::attr::PolicyError>`.
+///
+/// Payloads written by a custom serializer aren't validated any further, as
+/// their type's own impls aren't used.
+pub fn validate_checks(
+    policy: &NlaPolicy,
+    codec: &PayloadCodec,
+    name_str: &str,
+    nla_type: &TokenStream,
+) -> TokenStream {
+    let mut checks = vec![];
+
+    if policy.min_len.is_some() || policy.max_len.is_some() {
+        let min_len = optional(&policy.min_len);
+        let max_len = optional(&policy.max_len);
+        let serialized_len = codec.serialized_len();
+        checks.push(quote! {
+            ::nldl::attr::policy::check_len(#serialized_len, #min_len, #max_len)
+        });
+    }
+    if let Some(max_strlen) = &policy.max_strlen {
+        checks.push(quote! {
+            ::nldl::attr::policy::check_sent_strlen(val, #max_strlen)
+        });
+    }
+    checks.extend(range_check(policy, quote! { val }));
+
+    let nested_quote = if codec.serializes_with_trait() {
+        quote! {
+            .and_then(|()| {
+                ::nldl::message::NetlinkPayloadRequest::validate(val)
+                    .map_err(|err| err.within(#name_str, #nla_type))
+            })
+        }
+    } else {
+        quote! {}
+    };
+
+    quote! {
+        ::std::result::Result::Ok(())
+            #( .and_then(|()| #checks) )*
+            .map_err(|violation| ::nldl::attr::PolicyError::new(#name_str, #nla_type, violation))
+            #nested_quote
+    }
+}
+
+fn range_check(policy: &NlaPolicy, val: TokenStream) -> Option<TokenStream> {
+    let range = policy.range.as_ref()?;
+    let range_str = range.to_string();
+    Some(quote! {
+        ::nldl::attr::policy::check_range(#val, #range, #range_str)
+    })
+}
+
+fn optional(value: &Option<TokenStream>) -> TokenStream {
+    match value {
+        Some(value) => quote! { ::std::option::Option::Some(#value) },
+        None => quote! { ::std::option::Option::None },
+    }
+}
+
+fn return_deserialize_error(checks: Vec<TokenStream>, name_str: &str) -> TokenStream {
+    quote! {
+        #(
+            if let ::std::result::Result::Err(violation) = #checks {
+                return ::std::result::Result::Err(::std::convert::From::from(
+                    ::nldl::attr::DeserializeError {
+                        attribute_struct_name: #name_str,
+                        nla_type_id: ty,
+                        source: ::std::boxed::Box::new(violation),
+                    },
+                ));
+            }
+        )*
+    }
+}
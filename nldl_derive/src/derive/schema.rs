@@ -0,0 +1,228 @@
+use super::bounds::with_bounds;
+use super::naming::snake_case;
+use crate::parsing::nla_type_arg::NlaTypeIds;
+use crate::parsing::nla_type_arg::NlaTypeRange;
+use crate::parsing::nla_type_field::parse_fields;
+use crate::parsing::nldl_codec::NldlCodec;
+use crate::parsing::parse_enum::parse_enum;
+use crate::parsing::parse_enum::ParsedEnum;
+use crate::parsing::payload_kind::payload_kind;
+use crate::parsing::payload_kind::PayloadKind;
+use proc_macro2::Ident;
+use proc_macro2::TokenStream;
+use quote::format_ident;
+use quote::quote;
+use syn::Data;
+use syn::DeriveInput;
+use syn::Type;
+
+/// A variant or field as described by the schema.
+struct SchemaEntry<'a> {
+    ident: &'a Ident,
+    type_ids: EntryTypeIds<'a>,
+    payload_ty: Option<&'a Type>,
+    codec: Option<&'a NldlCodec>,
+}
+
+enum EntryTypeIds<'a> {
+    Ids(&'a NlaTypeIds),
+    Range(&'a NlaTypeRange),
+}
+
+/// Generates a `<VARIANT>_TYPE` constant per variant or field and an
+/// `nldl::attr::Schema` impl describing the type.
+///
+/// Example:
+///
+/// ```ignore
+/// impl ControllerAttribute {
+///     pub const FAMILY_ID_TYPE: u16 = libc::CTRL_ATTR_FAMILY_ID as u16;
+/// }
+///
+/// impl ::nldl::attr::Schema for ControllerAttribute {
+///     const SCHEMA: AttributeSetSchema = AttributeSetSchema {
+///         name: "ControllerAttribute",
+///         attributes: &[AttributeSchema {
+///             name: "FamilyId",
+///             type_ids: TypeIds::Ids(&[libc::CTRL_ATTR_FAMILY_ID as u16]),
+///             payload: PayloadKind::Value("u16"),
+///         }],
+///         wildcard: Some("Unknown"),
+///     };
+/// }
+/// ```
+pub fn impl_netlink_attribute_schema(ast: &DeriveInput) -> syn::Result<TokenStream> {
+    let name = &ast.ident;
+
+    let fields;
+    let parsed;
+    let (entries, wildcard_ident) = if let Data::Struct(data_struct) = &ast.data {
+        fields = parse_fields(data_struct)?;
+        let entries: Vec<_> = (fields.iter())
+            .map(|field| SchemaEntry {
+                ident: field.ident,
+                type_ids: EntryTypeIds::Ids(&field.ty),
+                payload_ty: Some(field.payload_ty()),
+                codec: Some(&field.codec),
+            })
+            .collect();
+        (entries, None)
+    } else {
+        parsed = parse_enum(ast)?;
+        (enum_entries(ast, &parsed), parsed.wildcard_ident)
+    };
+
+    let vis = &ast.vis;
+    let name_str = name.to_string();
+    let mut consts = vec![];
+    let mut attributes = vec![];
+    let mut nested_tys = vec![];
+
+    for entry in &entries {
+        let ident = entry.ident;
+        let ident_str = ident.to_string();
+        let const_name = format_ident!(
+            "{}_TYPE",
+            snake_case(ident).to_uppercase(),
+            span = ident.span()
+        );
+
+        let type_ids = match entry.type_ids {
+            EntryTypeIds::Ids(ids) => {
+                let first = ids.first();
+                let doc = format!("Type id of `{}::{}`.", name, ident);
+                consts.push(quote! {
+                    #[doc = #doc]
+                    #vis const #const_name: ::std::primitive::u16 = #first;
+                });
+                let ids = &ids.0;
+                quote! { ::nldl::attr::schema::TypeIds::Ids(&[#( #ids ),*]) }
+            }
+            EntryTypeIds::Range(range) => {
+                let (start, end) = (&range.start, &range.end);
+                let start_name = format_ident!("{}_START", const_name);
+                let end_name = format_ident!("{}_END", const_name);
+                let start_doc = format!("First type id of `{}::{}`.", name, ident);
+                let end_doc = format!("Last type id of `{}::{}`.", name, ident);
+                consts.push(quote! {
+                    #[doc = #start_doc]
+                    #vis const #start_name: ::std::primitive::u16 = #start;
+                    #[doc = #end_doc]
+                    #vis const #end_name: ::std::primitive::u16 = #end;
+                });
+                quote! {
+                    ::nldl::attr::schema::TypeIds::Range {
+                        start: Self::#start_name,
+                        end: Self::#end_name,
+                    }
+                }
+            }
+        };
+
+        // Payloads with a custom codec aren't encoded the way their type
+        // suggests, so they're always described as plain values.
+        let payload = match (entry.payload_ty, entry.codec) {
+            (None, _) => quote! { ::nldl::attr::schema::PayloadKind::Empty },
+            (Some(ty), Some(codec)) if !codec.is_empty() => {
+                payload_kind_quote(&PayloadKind::Value(ty), &mut nested_tys)
+            }
+            (Some(ty), _) => payload_kind_quote(&payload_kind(ty), &mut nested_tys),
+        };
+
+        attributes.push(quote! {
+            ::nldl::attr::schema::AttributeSchema {
+                name: #ident_str,
+                type_ids: #type_ids,
+                payload: #payload,
+            }
+        });
+    }
+
+    let wildcard = match wildcard_ident {
+        Some(ident) => {
+            let ident_str = ident.to_string();
+            quote! { ::std::option::Option::Some(#ident_str) }
+        }
+        None => quote! { ::std::option::Option::None },
+    };
+
+    let (consts_impl_generics, ty_generics, consts_where_clause) = ast.generics.split_for_impl();
+    // Nested types that depend on type parameters must describe themselves.
+    let generics = with_bounds(&ast.generics, nested_tys, |ty| {
+        quote! { #ty: ::nldl::attr::Schema }
+    })?;
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+
+    Ok(quote! {
+        impl #consts_impl_generics #name #ty_generics #consts_where_clause {
+            #( #consts )*
+        }
+
+        impl #impl_generics ::nldl::attr::Schema for #name #ty_generics #where_clause {
+            const SCHEMA: ::nldl::attr::schema::AttributeSetSchema =
+                ::nldl::attr::schema::AttributeSetSchema {
+                    name: #name_str,
+                    attributes: &[#( #attributes ),*],
+                    wildcard: #wildcard,
+                };
+        }
+    })
+}
+
+/// Entries for each variant with a type id, in declaration order.
+fn enum_entries<'a>(ast: &DeriveInput, parsed: &'a ParsedEnum) -> Vec<SchemaEntry<'a>> {
+    let no_payload = (parsed.no_payload_idents.iter())
+        .zip(&parsed.no_payload_nla_types)
+        .map(|(ident, ids)| SchemaEntry {
+            ident,
+            type_ids: EntryTypeIds::Ids(ids),
+            payload_ty: None,
+            codec: None,
+        });
+    let simple = (parsed.simple_idents.iter())
+        .zip(&parsed.simple_nla_types)
+        .zip(&parsed.simple_payload_tys)
+        .zip(&parsed.simple_codecs)
+        .map(|(((ident, ids), ty), codec)| SchemaEntry {
+            ident,
+            type_ids: EntryTypeIds::Ids(ids),
+            payload_ty: Some(ty),
+            codec: Some(codec),
+        });
+    let range = (parsed.range_idents.iter())
+        .zip(&parsed.range_nla_types)
+        .zip(&parsed.range_payload_tys)
+        .zip(&parsed.range_codecs)
+        .map(|(((ident, range), ty), codec)| SchemaEntry {
+            ident,
+            type_ids: EntryTypeIds::Range(range),
+            payload_ty: Some(ty),
+            codec: Some(codec),
+        });
+
+    let mut entries: Vec<_> = no_payload.chain(simple).chain(range).collect();
+    if let Data::Enum(data_enum) = &ast.data {
+        entries.sort_by_key(|entry| {
+            (data_enum.variants.iter()).position(|variant| variant.ident == *entry.ident)
+        });
+    }
+    entries
+}
+
+fn payload_kind_quote<'a>(kind: &PayloadKind<'a>, nested_tys: &mut Vec<&'a Type>) -> TokenStream {
+    match kind {
+        PayloadKind::Value(ty) => {
+            quote! { ::nldl::attr::schema::PayloadKind::Value(::std::stringify!(#ty)) }
+        }
+        PayloadKind::Nested(ty) => {
+            nested_tys.push(ty);
+            quote! {
+                ::nldl::attr::schema::PayloadKind::Nested(<#ty as ::nldl::attr::Schema>::schema)
+            }
+        }
+        PayloadKind::Array(kind) => {
+            let kind = payload_kind_quote(kind, nested_tys);
+            quote! { ::nldl::attr::schema::PayloadKind::Array(&#kind) }
+        }
+    }
+}
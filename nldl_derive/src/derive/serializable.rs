@@ -1,20 +1,38 @@
 extern crate proc_macro;
 
-use crate::parsing::parse_or_panic::parse_or_panic;
-use crate::parsing::parse_or_panic::ParseOrPanicReturn;
+use super::bounds::with_bounds;
+use super::codec::PayloadCodec;
+use super::policy::validate_checks;
+use crate::parsing::nla_type_arg::NlaTypeIds;
+use crate::parsing::parse_enum::parse_enum;
+use crate::parsing::parse_enum::ParsedEnum;
 use proc_macro2::TokenStream;
 use quote::quote;
 use syn::DeriveInput;
 
-pub fn impl_netlink_attribute_serializable(ast: &DeriveInput) -> TokenStream {
-    let ParseOrPanicReturn {
+pub fn impl_netlink_attribute_serializable(ast: &DeriveInput) -> syn::Result<TokenStream> {
+    let ParsedEnum {
         name,
         no_payload_idents,
         no_payload_nla_types,
         simple_idents,
         simple_nla_types,
+        simple_payload_tys,
+        simple_policies,
+        simple_codecs,
+        range_idents,
+        range_nla_types,
+        range_payload_tys,
+        range_policies,
+        range_codecs,
         wildcard_ident,
-    } = parse_or_panic(ast);
+        wildcard_payload_ty,
+        ..
+    } = parse_enum(ast)?;
+
+    // Aliases are accepted when deserializing, but only the first id is sent.
+    let no_payload_nla_types: Vec<_> = no_payload_nla_types.iter().map(NlaTypeIds::first).collect();
+    let simple_nla_types: Vec<_> = simple_nla_types.iter().map(NlaTypeIds::first).collect();
 
     // Option structs don't seem to be iterable in the quote! context below. Converting to a Vec as
     // a workaround.
@@ -23,24 +41,108 @@ pub fn impl_netlink_attribute_serializable(ast: &DeriveInput) -> TokenStream {
         Some(ident) => vec![ident],
     };
 
-    quote! {
-        impl ::nldl::attr::Serialize for #name {
+    let simple_codecs: Vec<_> = (simple_codecs.iter())
+        .zip(&simple_payload_tys)
+        .map(|(codec, ty)| PayloadCodec::new(codec, ty))
+        .collect();
+    let range_codecs: Vec<_> = (range_codecs.iter())
+        .zip(&range_payload_tys)
+        .map(|(codec, ty)| PayloadCodec::new(codec, ty))
+        .collect();
+
+    // Payloads that depend on type parameters need bounds for the calls below,
+    // unless a custom serializer takes their place.
+    let generics = with_bounds(
+        &ast.generics,
+        (simple_codecs.iter().chain(&range_codecs))
+            .filter(|codec| codec.serializes_with_trait())
+            .map(|codec| codec.ty),
+        |ty| quote! { #ty: ::nldl::message::NetlinkPayloadRequest },
+    )?;
+    let generics = with_bounds(
+        &generics,
+        wildcard_payload_ty,
+        |ty| quote! { #ty: ::nldl::attr::Serialize },
+    )?;
+    let (impl_generics, ty_generics, where_clause) = generics.split_for_impl();
+
+    let name_str = name.to_string();
+    let simple_validate_checks: Vec<_> = (simple_policies.iter())
+        .zip(&simple_codecs)
+        .zip(&simple_nla_types)
+        .map(|((policy, codec), nla_type)| validate_checks(policy, codec, &name_str, nla_type))
+        .collect();
+    let range_validate_checks: Vec<_> = (range_policies.iter())
+        .zip(&range_codecs)
+        .map(|(policy, codec)| validate_checks(policy, codec, &name_str, &quote! { *ty }))
+        .collect();
+    let simple_nla_flags: Vec<_> = simple_codecs.iter().map(PayloadCodec::nla_flags).collect();
+    let range_nla_flags: Vec<_> = range_codecs.iter().map(PayloadCodec::nla_flags).collect();
+    let simple_serialized_lens: Vec<_> = (simple_codecs.iter())
+        .map(PayloadCodec::serialized_len)
+        .collect();
+    let range_serialized_lens: Vec<_> = (range_codecs.iter())
+        .map(PayloadCodec::serialized_len)
+        .collect();
+    let simple_serializes: Vec<_> = simple_codecs.iter().map(PayloadCodec::serialize).collect();
+    let range_serializes: Vec<_> = range_codecs.iter().map(PayloadCodec::serialize).collect();
+    let range_starts: Vec<_> = range_nla_types.iter().map(|range| &range.start).collect();
+    let range_ends: Vec<_> = range_nla_types.iter().map(|range| &range.end).collect();
+
+    Ok(quote! {
+        impl #impl_generics ::nldl::attr::Serialize for #name #ty_generics #where_clause {
 
             fn get_type(&self) -> ::std::primitive::u16 {
                 match self {
                     #( Self::#no_payload_idents => #no_payload_nla_types, )*
-                    #( Self::#simple_idents(_) => #simple_nla_types, )*
+                    #( Self::#simple_idents(_) => (#simple_nla_types) | #simple_nla_flags, )*
+                    #( Self::#range_idents(ty, _) => *ty | #range_nla_flags, )*
                     #( Self::#wildcard_ident(a) => ::nldl::attr::Serialize::get_type(a), )*
                 }
             }
 
-            fn serialize_payload(&self, buf: &mut ::std::vec::Vec<::std::primitive::u8>) {
+            fn serialized_payload_len(&self) -> ::std::primitive::usize {
+                match self {
+                    #( Self::#no_payload_idents => 0, )*
+                    #( Self::#simple_idents(val) => #simple_serialized_lens, )*
+                    #( Self::#range_idents(_, val) => #range_serialized_lens, )*
+                    #( Self::#wildcard_ident(a) => ::nldl::attr::Serialize::serialized_payload_len(a), )*
+                }
+            }
+
+            fn serialize_payload<__NldlB: ::nldl::bytes::BufMut>(
+                &self,
+                buf: &mut __NldlB,
+            ) -> ::std::result::Result<(), ::nldl::SerializeError> {
                 match self {
-                    #( Self::#no_payload_idents => {}, )*
-                    #( Self::#simple_idents(val) => ::nldl::message::NetlinkPayloadRequest::serialize(val, buf), )*
+                    #( Self::#no_payload_idents => Ok(()), )*
+                    #( Self::#simple_idents(val) => #simple_serializes, )*
+                    #( Self::#range_idents(ty, val) => {
+                        // The id is sent as is, so one outside the range would
+                        // come back as a different variant.
+                        if !(#range_starts..=#range_ends).contains(ty) {
+                            return ::std::result::Result::Err(
+                                ::nldl::SerializeError::TypeIdOutOfRange {
+                                    attribute: #name_str,
+                                    ty: *ty,
+                                    start: #range_starts,
+                                    end: #range_ends,
+                                },
+                            );
+                        }
+                        #range_serializes
+                    } )*
                     #( Self::#wildcard_ident(a) => ::nldl::attr::Serialize::serialize_payload(a, buf), )*
                 }
             }
+
+            fn validate_payload(&self) -> ::std::result::Result<(), ::nldl::attr::PolicyError> {
+                match self {
+                    #( Self::#simple_idents(val) => #simple_validate_checks, )*
+                    #( Self::#range_idents(ty, val) => #range_validate_checks, )*
+                    _ => ::std::result::Result::Ok(()),
+                }
+            }
         }
-    }
+    })
 }
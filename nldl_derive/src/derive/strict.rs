@@ -0,0 +1,28 @@
+use proc_macro2::TokenStream;
+use quote::quote;
+
+/// Evaluates to whether `ty` matches one of `patterns` from
+/// [TypeIds](super::type_ids::TypeIds). Expects `ty` in scope.
+pub fn is_known_type_match(patterns: &[TokenStream]) -> TokenStream {
+    quote! {
+        match ty {
+            #( #patterns => true, )*
+            _ => false,
+        }
+    }
+}
+
+/// Evaluates to a `DeserializeError` for an attribute enum without a variant
+/// for the type ID. Expects `ty` in scope.
+pub fn unknown_attribute_error(name_str: &str) -> TokenStream {
+    quote! {
+        ::nldl::attr::DeserializeError {
+            attribute_struct_name: #name_str,
+            nla_type_id: ty,
+            source: ::std::boxed::Box::new(::nldl::attr::StrictError::UnknownAttribute {
+                attribute_struct_name: #name_str,
+                nla_type_id: ty,
+            }),
+        }
+    }
+}
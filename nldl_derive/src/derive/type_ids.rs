@@ -0,0 +1,212 @@
+use crate::parsing::nla_type_arg::NlaTypeIds;
+use crate::parsing::nla_type_arg::NlaTypeRange;
+use proc_macro2::Ident;
+use proc_macro2::TokenStream;
+use quote::format_ident;
+use quote::quote;
+
+/// The type ids of an attribute enum or struct, as referred to by generated
+/// code.
+///
+/// Expressions can't be inlined on the left side of a match arm, so they're
+/// assigned to constants in a hidden module first. It's emitted once next to
+/// the generated impls and named after the type, so an id that fails to
+/// evaluate is only reported once. The first id of a variant is named after it
+/// and aliases get a suffix.
+///
+/// Example:
+///
+/// ```ignore
+/// mod __ExampleInternalTypeIdsNamespace {
+///     #[repr(u16)]
+///     enum InternalTypeIdsEnum {
+///         Unspec = EXAMPLE_ZERO_CONST as u16,
+///         Name = 2,
+///         Name__alias1 = 3,
+///     }
+///
+///     pub const Unspec: u16 = InternalTypeIdsEnum::Unspec as u16;
+///     pub const Name: u16 = InternalTypeIdsEnum::Name as u16;
+///     pub const Name__alias1: u16 = InternalTypeIdsEnum::Name__alias1 as u16;
+///     pub const Vendor__start: u16 = 0x100;
+///     pub const Vendor__end: u16 = 0x1ff;
+/// }
+///
+/// // ...
+///
+/// Ok(match ty {
+///     __ExampleInternalTypeIdsNamespace::Unspec => ...
+///     __ExampleInternalTypeIdsNamespace::Name
+///     | __ExampleInternalTypeIdsNamespace::Name__alias1 => ...
+///     __ExampleInternalTypeIdsNamespace::Vendor__start
+///         ..=__ExampleInternalTypeIdsNamespace::Vendor__end => ...
+/// })
+/// ```
+pub struct TypeIds<'a> {
+    namespace: Ident,
+    ids: Vec<(&'a Ident, &'a NlaTypeIds)>,
+    ranges: Vec<(&'a Ident, &'a NlaTypeRange)>,
+}
+
+impl<'a> TypeIds<'a> {
+    pub fn new(
+        name: &Ident,
+        ids: impl IntoIterator<Item = (&'a Ident, &'a NlaTypeIds)>,
+        ranges: impl IntoIterator<Item = (&'a Ident, &'a NlaTypeRange)>,
+    ) -> Self {
+        Self {
+            namespace: format_ident!("__{}InternalTypeIdsNamespace", name),
+            ids: ids.into_iter().collect(),
+            ranges: ranges.into_iter().collect(),
+        }
+    }
+
+    /// The module holding the constants from [Self::consts].
+    pub fn namespace(&self) -> &Ident {
+        &self.namespace
+    }
+
+    /// The module of constants. Must be emitted exactly once per type, at
+    /// item level next to the generated impls.
+    ///
+    /// Duplicate ids are caught by an unused `#[repr(u16)]` enum with a
+    /// discriminant per id, for which the compiler errors are relatively
+    /// appropriate. The constants are read back from the discriminants so
+    /// each expression is only evaluated, and any error reported, once.
+    pub fn consts(&self) -> TokenStream {
+        let namespace = &self.namespace;
+        let (id_consts, id_values) = self.id_consts();
+        let range_starts = self.ranges.iter().map(|(ident, _)| range_start(ident));
+        let range_ends = self.ranges.iter().map(|(ident, _)| range_end(ident));
+        let range_start_values = self.ranges.iter().map(|(_, range)| &range.start);
+        let range_end_values = self.ranges.iter().map(|(_, range)| &range.end);
+
+        // A #[repr(u16)] enum can't be empty.
+        let id_enum = if id_consts.is_empty() {
+            quote! {}
+        } else {
+            quote! {
+                #[allow(dead_code, non_camel_case_types)]
+                #[repr(u16)]
+                enum InternalTypeIdsEnum {
+                    #( #id_consts = #id_values, )*
+                }
+            }
+        };
+
+        quote! {
+            #[doc(hidden)]
+            #[allow(non_snake_case, non_upper_case_globals)]
+            mod #namespace {
+                #id_enum
+
+                #( pub const #id_consts: ::std::primitive::u16 = InternalTypeIdsEnum::#id_consts as ::std::primitive::u16; )*
+                #( pub const #range_starts: ::std::primitive::u16 = #range_start_values; )*
+                #( pub const #range_ends: ::std::primitive::u16 = #range_end_values; )*
+            }
+        }
+    }
+
+    /// Checks at compile time that ranges are non-empty and don't overlap
+    /// other type ids. Ranges can't be expressed as discriminants like in
+    /// [Self::consts], so they're checked with const assertions instead.
+    /// Emitted at item level next to [Self::consts].
+    pub fn checker(&self) -> TokenStream {
+        let namespace = &self.namespace;
+        let (id_consts, _) = self.id_consts();
+
+        let mut assertions = vec![];
+        for (i, (range_ident, _)) in self.ranges.iter().enumerate() {
+            let (start, end) = (range_start(range_ident), range_end(range_ident));
+            let empty = format!("Type id range of \"{}\" is empty.", range_ident);
+            assertions.push(quote! {
+                assert!(#namespace::#start <= #namespace::#end, #empty);
+            });
+
+            for (id_ident, id_const) in self.id_idents().zip(&id_consts) {
+                let overlap = format!(
+                    "Type id of \"{}\" is in the type id range of \"{}\".",
+                    id_ident, range_ident
+                );
+                assertions.push(quote! {
+                    assert!(
+                        #namespace::#id_const < #namespace::#start
+                            || #namespace::#id_const > #namespace::#end,
+                        #overlap
+                    );
+                });
+            }
+
+            for (other_ident, _) in &self.ranges[i + 1..] {
+                let (other_start, other_end) = (range_start(other_ident), range_end(other_ident));
+                let overlap = format!(
+                    "Type id ranges of \"{}\" and \"{}\" overlap.",
+                    range_ident, other_ident
+                );
+                assertions.push(quote! {
+                    assert!(
+                        #namespace::#end < #namespace::#other_start
+                            || #namespace::#other_end < #namespace::#start,
+                        #overlap
+                    );
+                });
+            }
+        }
+
+        quote! {
+            const _: () = {
+                #( #assertions )*
+            };
+        }
+    }
+
+    /// Match pattern for every id of the variant or field named `ident`.
+    pub fn ids_pattern(&self, ident: &Ident, ids: &NlaTypeIds) -> TokenStream {
+        let namespace = &self.namespace;
+        let consts = id_const_names(ident, ids);
+        quote! { #( #namespace::#consts )|* }
+    }
+
+    /// Match pattern for the range of the variant named `ident`.
+    pub fn range_pattern(&self, ident: &Ident) -> TokenStream {
+        let namespace = &self.namespace;
+        let (start, end) = (range_start(ident), range_end(ident));
+        quote! { #namespace::#start..=#namespace::#end }
+    }
+
+    /// Match patterns for every variant or field, in the order given to
+    /// [Self::new].
+    pub fn patterns(&self) -> Vec<TokenStream> {
+        let ids = (self.ids.iter()).map(|(ident, ids)| self.ids_pattern(ident, ids));
+        let ranges = (self.ranges.iter()).map(|(ident, _)| self.range_pattern(ident));
+        ids.chain(ranges).collect()
+    }
+
+    fn id_consts(&self) -> (Vec<Ident>, Vec<&TokenStream>) {
+        let names = (self.ids.iter()).flat_map(|(ident, ids)| id_const_names(ident, ids));
+        let values = self.ids.iter().flat_map(|(_, ids)| &ids.0);
+        (names.collect(), values.collect())
+    }
+
+    /// The variant or field of each constant from [Self::id_consts].
+    fn id_idents(&self) -> impl Iterator<Item = &Ident> {
+        (self.ids.iter()).flat_map(|(ident, ids)| ids.0.iter().map(move |_| *ident))
+    }
+}
+
+fn id_const_names(ident: &Ident, ids: &NlaTypeIds) -> Vec<Ident> {
+    (0..ids.0.len())
+        .map(|i| match i {
+            0 => ident.clone(),
+            _ => format_ident!("{}__alias{}", ident, i),
+        })
+        .collect()
+}
+
+fn range_start(ident: &Ident) -> Ident {
+    format_ident!("{}__start", ident)
+}
+
+fn range_end(ident: &Ident) -> Ident {
+    format_ident!("{}__end", ident)
+}
@@ -0,0 +1,228 @@
+use super::bounds::with_bounds;
+use super::codec::PayloadCodec;
+use super::naming::snake_case;
+use super::policy::payload_checks;
+use super::policy::value_checks;
+use super::strict::is_known_type_match;
+use super::strict::unknown_attribute_error;
+use super::type_ids::TypeIds;
+use crate::parsing::parse_enum::ParsedEnum;
+use proc_macro2::Ident;
+use proc_macro2::TokenStream;
+use quote::format_ident;
+use quote::quote;
+use syn::DeriveInput;
+use syn::GenericParam;
+use syn::Lifetime;
+use syn::LifetimeDef;
+
+/// Generates a `<Name>Visitor` trait for an attribute enum marked with
+/// `#[nldl(visitor)]`.
+///
+/// Example:
+///
+/// ```ignore
+/// pub trait ControllerAttributeVisitor<'a> {
+///     fn visit_family_id(&mut self, value: u16) {}
+///     fn visit_unknown(&mut self, attr: ::nldl::attr::Attr<'a>) {}
+///
+///     fn visit_attributes(&mut self, buf: &'a [u8]) -> Result<(), ParseNetlinkAttributeFromBufferError> {
+///         // Decodes each attribute and calls the matching visit_* method.
+///     }
+/// }
+/// ```
+pub fn impl_attribute_visitor(
+    ast: &DeriveInput,
+    parsed: &ParsedEnum,
+    strict: bool,
+) -> syn::Result<TokenStream> {
+    let ParsedEnum {
+        name,
+        no_payload_idents,
+        no_payload_nla_types,
+        simple_idents,
+        simple_nla_types,
+        simple_payload_tys,
+        simple_policies,
+        simple_codecs,
+        range_idents,
+        range_nla_types,
+        range_payload_tys,
+        range_policies,
+        range_codecs,
+        wildcard_ident,
+        ..
+    } = parsed;
+
+    let vis = &ast.vis;
+    let visitor_name = format_ident!("{}Visitor", name);
+    let name_str = name.to_string();
+    let simple_payload_checks: Vec<_> = (simple_policies.iter())
+        .map(|policy| payload_checks(policy, &name_str))
+        .collect();
+    let simple_value_checks: Vec<_> = (simple_policies.iter())
+        .map(|policy| value_checks(policy, &name_str))
+        .collect();
+    let range_payload_checks: Vec<_> = (range_policies.iter())
+        .map(|policy| payload_checks(policy, &name_str))
+        .collect();
+    let range_value_checks: Vec<_> = (range_policies.iter())
+        .map(|policy| value_checks(policy, &name_str))
+        .collect();
+
+    // Payload types of borrowing enums refer to the enum's lifetime, so the
+    // trait reuses it. Owned enums get a fresh one for the buffer. Type
+    // parameters of the enum become parameters of the trait.
+    let mut generics = ast.generics.clone();
+    let lifetime = match ast.generics.lifetimes().next() {
+        Some(def) => def.lifetime.clone(),
+        None => {
+            let lifetime = Lifetime::new("'a", proc_macro2::Span::call_site());
+            generics.params.insert(
+                0,
+                GenericParam::Lifetime(LifetimeDef::new(lifetime.clone())),
+            );
+            lifetime
+        }
+    };
+    let simple_codecs: Vec<_> = (simple_codecs.iter())
+        .zip(simple_payload_tys)
+        .map(|(codec, ty)| PayloadCodec::new(codec, ty))
+        .collect();
+    let range_codecs: Vec<_> = (range_codecs.iter())
+        .zip(range_payload_tys)
+        .map(|(codec, ty)| PayloadCodec::new(codec, ty))
+        .collect();
+    let payload_trait = quote! { ::nldl::message::NetlinkPayloadResponseBorrowed<#lifetime> };
+    let payload_fn = quote! { deserialize_borrowed };
+    let simple_deserializes: Vec<_> = (simple_codecs.iter())
+        .map(|codec| codec.deserialize(&payload_trait, &payload_fn))
+        .collect();
+    let range_deserializes: Vec<_> = (range_codecs.iter())
+        .map(|codec| codec.deserialize(&payload_trait, &payload_fn))
+        .collect();
+    let payload_tys = (simple_codecs.iter())
+        .chain(&range_codecs)
+        .filter(|codec| codec.deserializes_with_trait())
+        .map(|codec| codec.ty);
+    let generics = with_bounds(&generics, payload_tys, |ty| {
+        quote! {
+            #ty: ::nldl::message::NetlinkPayloadResponseBorrowed<#lifetime>,
+            <#ty as ::nldl::message::NetlinkPayloadResponseBorrowed<#lifetime>>::Error:
+                ::std::marker::Send + ::std::marker::Sync + 'static,
+        }
+    })?;
+    let (trait_generics, _, where_clause) = generics.split_for_impl();
+
+    let no_payload_fns: Vec<Ident> = no_payload_idents.iter().map(visit_fn_ident).collect();
+    let simple_fns: Vec<Ident> = simple_idents.iter().map(visit_fn_ident).collect();
+    let range_fns: Vec<Ident> = range_idents.iter().map(visit_fn_ident).collect();
+    let wildcard_fn: Vec<Ident> = wildcard_ident.iter().map(visit_fn_ident).collect();
+
+    // The constants are emitted once, with the Deserialize impl.
+    let type_ids = TypeIds::new(
+        name,
+        (no_payload_idents.iter().copied())
+            .zip(no_payload_nla_types)
+            .chain(simple_idents.iter().copied().zip(simple_nla_types)),
+        range_idents.iter().copied().zip(range_nla_types),
+    );
+    let no_payload_patterns: Vec<_> = (no_payload_idents.iter())
+        .zip(no_payload_nla_types)
+        .map(|(ident, ids)| type_ids.ids_pattern(ident, ids))
+        .collect();
+    let simple_patterns: Vec<_> = (simple_idents.iter())
+        .zip(simple_nla_types)
+        .map(|(ident, ids)| type_ids.ids_pattern(ident, ids))
+        .collect();
+    let range_patterns: Vec<_> = (range_idents.iter())
+        .map(|ident| type_ids.range_pattern(ident))
+        .collect();
+
+    // Same handling of unknown attributes as the Deserialize impl.
+    let unknown_quote = match wildcard_fn.first() {
+        Some(wildcard_fn) if !strict => quote! { self.#wildcard_fn(attr) },
+        _ => {
+            let unknown_attribute_error = unknown_attribute_error(&name_str);
+            quote! { return Err(#unknown_attribute_error.into()) }
+        }
+    };
+    let strict_check_quote = if strict {
+        let is_known_type = is_known_type_match(&type_ids.patterns());
+        quote! {
+            ::nldl::attr::check_strict(buf, #name_str, |ty| #is_known_type, |_| false)?;
+        }
+    } else {
+        quote! {}
+    };
+
+    Ok(quote! {
+        /// Callbacks for each attribute decoded by `visit_attributes`. Every
+        /// method defaults to ignoring the attribute.
+        #vis trait #visitor_name #trait_generics #where_clause {
+            #(
+                fn #no_payload_fns(&mut self) {}
+            )*
+            #(
+                #[allow(unused_variables)]
+                fn #simple_fns(&mut self, value: #simple_payload_tys) {}
+            )*
+            #(
+                #[allow(unused_variables)]
+                fn #range_fns(&mut self, ty: ::std::primitive::u16, value: #range_payload_tys) {}
+            )*
+            #(
+                #[allow(unused_variables)]
+                fn #wildcard_fn(&mut self, attr: ::nldl::attr::Attr<#lifetime>) {}
+            )*
+
+            /// Decodes each attribute in `buf` and passes it to the matching
+            /// method without collecting them.
+            fn visit_attributes(
+                &mut self,
+                buf: &#lifetime [::std::primitive::u8],
+            ) -> ::std::result::Result<(), ::nldl::attr::ParseNetlinkAttributeFromBufferError> {
+                #strict_check_quote
+
+                for attr in ::nldl::attr::AttrIter::new(buf) {
+                    let attr = attr?;
+                    let ty = attr.ty;
+                    let payload = attr.payload;
+                    match ty {
+                        #( #no_payload_patterns => self.#no_payload_fns(), )*
+                        #( #simple_patterns => {
+                            #simple_payload_checks
+                            let val = #simple_deserializes
+                                .map_err(|err| ::nldl::attr::DeserializeError {
+                                    attribute_struct_name: #name_str,
+                                    nla_type_id: ty,
+                                    source: ::std::convert::Into::into(err),
+                                })?;
+                            #simple_value_checks
+                            self.#simple_fns(val);
+                        }, )*
+                        #( #range_patterns => {
+                            #range_payload_checks
+                            let val = #range_deserializes
+                                .map_err(|err| ::nldl::attr::DeserializeError {
+                                    attribute_struct_name: #name_str,
+                                    nla_type_id: ty,
+                                    source: ::std::convert::Into::into(err),
+                                })?;
+                            #range_value_checks
+                            self.#range_fns(ty, val);
+                        }, )*
+                        _ => #unknown_quote,
+                    }
+                }
+
+                Ok(())
+            }
+        }
+    })
+}
+
+/// `FamilyId` becomes `visit_family_id`.
+fn visit_fn_ident(variant: &&Ident) -> Ident {
+    format_ident!("visit_{}", snake_case(variant))
+}
@@ -1,20 +1,56 @@
 use derive::deserializable::impl_netlink_attribute_deserializable;
+use derive::fixed_struct::impl_netlink_struct;
+use derive::int_enum::impl_netlink_int_enum;
+use derive::schema::impl_netlink_attribute_schema;
 use derive::serializable::impl_netlink_attribute_serializable;
 use proc_macro::TokenStream;
+use syn::parse_macro_input;
+use syn::DeriveInput;
 
 mod derive;
 mod parsing;
 
 // Created following pattern from:
 // https://doc.rust-lang.org/1.46.0/book/ch19-06-macros.html?highlight=procedural,macros#how-to-write-a-custom-derive-macro
-#[proc_macro_derive(NetlinkAttributeSerializable, attributes(nla_type))]
+//
+// Problems are reported as spanned compile errors rather than panics so they
+// point at the offending variant or field.
+#[proc_macro_derive(NetlinkAttributeSerializable, attributes(nldl, nla_type, nla_policy))]
 pub fn netlink_attribute_serializable_derive(input: TokenStream) -> TokenStream {
-    let ast = syn::parse(input).expect("Unable to parse DeriveInput from TokenStream");
-    impl_netlink_attribute_serializable(&ast).into()
+    let ast = parse_macro_input!(input as DeriveInput);
+    impl_netlink_attribute_serializable(&ast)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
 
-#[proc_macro_derive(NetlinkAttributeDeserializable, attributes(nldl, nla_type))]
+#[proc_macro_derive(NetlinkAttributeDeserializable, attributes(nldl, nla_type, nla_policy))]
 pub fn netlink_attribute_deserializable_derive(input: TokenStream) -> TokenStream {
-    let ast = syn::parse(input).expect("Unable to parse DeriveInput from TokenStream");
-    impl_netlink_attribute_deserializable(&ast).into()
+    let ast = parse_macro_input!(input as DeriveInput);
+    impl_netlink_attribute_deserializable(&ast)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(NetlinkAttributeSchema, attributes(nldl, nla_type, nla_policy))]
+pub fn netlink_attribute_schema_derive(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    impl_netlink_attribute_schema(&ast)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(NetlinkStruct)]
+pub fn netlink_struct_derive(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    impl_netlink_struct(&ast)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
+}
+
+#[proc_macro_derive(NetlinkEnum, attributes(nldl, nla_value))]
+pub fn netlink_enum_derive(input: TokenStream) -> TokenStream {
+    let ast = parse_macro_input!(input as DeriveInput);
+    impl_netlink_int_enum(&ast)
+        .unwrap_or_else(syn::Error::into_compile_error)
+        .into()
 }
@@ -1,2 +1,9 @@
+pub mod errors;
+pub mod nla_policy;
 pub mod nla_type;
-pub mod parse_or_panic;
+pub mod nla_type_arg;
+pub mod nla_type_field;
+pub mod nldl_attr;
+pub mod nldl_codec;
+pub mod parse_enum;
+pub mod payload_kind;
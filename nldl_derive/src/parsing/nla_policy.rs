@@ -0,0 +1,101 @@
+use proc_macro2::TokenStream;
+use proc_macro2::TokenTree;
+use syn::parse::Parse;
+use syn::parse::ParseStream;
+use syn::Attribute;
+use syn::Ident;
+use syn::Token;
+
+const NLA_POLICY_ATTR: &str = "nla_policy";
+
+/// Constraints from a `#[nla_policy(..)]` annotation. Values are kept as
+/// tokens so constant expressions such as `libc::IFNAMSIZ - 1` may be used.
+#[derive(Default)]
+pub struct NlaPolicy {
+    pub min_len: Option<TokenStream>,
+    pub max_len: Option<TokenStream>,
+    pub max_strlen: Option<TokenStream>,
+    pub range: Option<TokenStream>,
+}
+
+impl NlaPolicy {
+    pub fn is_empty(&self) -> bool {
+        self.min_len.is_none()
+            && self.max_len.is_none()
+            && self.max_strlen.is_none()
+            && self.range.is_none()
+    }
+}
+
+impl Parse for NlaPolicy {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut policy = Self::default();
+
+        while !input.is_empty() {
+            let key: Ident = input.parse()?;
+            input.parse::<Token![=]>()?;
+
+            // Expressions can't be parsed without syn's "full" feature, so
+            // take every token up to the next comma.
+            let mut value = TokenStream::new();
+            while !input.is_empty() && !input.peek(Token![,]) {
+                value.extend([input.parse::<TokenTree>()?]);
+            }
+            if value.is_empty() {
+                return Err(input.error(format!("Expected a value for \"{}\".", key)));
+            }
+
+            let slot = match key.to_string().as_str() {
+                "min_len" => &mut policy.min_len,
+                "max_len" => &mut policy.max_len,
+                "max_strlen" => &mut policy.max_strlen,
+                "range" => &mut policy.range,
+                _ => {
+                    return Err(syn::Error::new(
+                        key.span(),
+                        format!(
+                            "Unknown policy \"{}\". Expected one of min_len, max_len, max_strlen or range.",
+                            key
+                        ),
+                    ))
+                }
+            };
+            if slot.replace(value).is_some() {
+                return Err(syn::Error::new(
+                    key.span(),
+                    format!("Policy \"{}\" is specified more than once.", key),
+                ));
+            }
+
+            if !input.is_empty() {
+                input.parse::<Token![,]>()?;
+            }
+        }
+
+        Ok(policy)
+    }
+}
+
+pub fn is_nla_policy_attr(attribute: &Attribute) -> bool {
+    attribute.path.is_ident(NLA_POLICY_ATTR)
+}
+
+/// Parses the `#[nla_policy(..)]` annotation among `attrs`, if there is one.
+/// Policies are combined within a single annotation, so a second one is an
+/// error rather than silently ignored.
+pub fn parse_nla_policy(attrs: &[Attribute]) -> syn::Result<NlaPolicy> {
+    let mut policy_attrs = attrs.iter().filter(|attr| is_nla_policy_attr(attr));
+
+    let attr = match policy_attrs.next() {
+        None => return Ok(NlaPolicy::default()),
+        Some(attr) => attr,
+    };
+    if let Some(duplicate) = policy_attrs.next() {
+        return Err(syn::Error::new_spanned(
+            duplicate,
+            "#[nla_policy] is specified more than once. Combine the policies into one annotation.",
+        ));
+    }
+
+    attr.parse_args()
+}
@@ -1,6 +1,19 @@
+use super::errors::Errors;
+use super::nla_policy::is_nla_policy_attr;
+use super::nla_policy::parse_nla_policy;
+use super::nla_policy::NlaPolicy;
+use super::nla_type_arg::NlaTypeArg;
+use super::nla_type_arg::NlaTypeIds;
+use super::nla_type_arg::NlaTypeRange;
+use super::nldl_codec::parse_nldl_codec;
+use super::nldl_codec::NldlCodec;
 use proc_macro2::TokenStream;
+use quote::ToTokens;
+use std::collections::BTreeMap;
+use std::ops::RangeInclusive;
 use syn::Attribute;
 use syn::DataEnum;
+use syn::LitInt;
 use syn::Variant;
 
 const NLA_TYPE_ATTR: &str = "nla_type";
@@ -15,6 +28,10 @@ enum NetlinkAttributeKind<'a> {
     /// Enum variants marked with `nla_type` having some serializable and/or
     /// deserializable associated value.
     Simple(NetlinkAttributeKindSimple<'a>),
+    /// Enum variants marked with a range of type ids, e.g.
+    /// `nla_type(0x100..=0x1ff)`. The variant holds the type id followed by
+    /// the payload.
+    Range(NetlinkAttributeKindRange<'a>),
     /// Enum variants marked with `nla_type(_)`. Typically for netlink attributes
     /// with unmatched type ids during deserialization.
     ///
@@ -25,23 +42,15 @@ enum NetlinkAttributeKind<'a> {
     Unmarked(NetlinkAttributeKindUnmarked<'a>),
 }
 
-#[derive(thiserror::Error, Debug)]
-pub enum NetlinkAttributeKindFromVariantError {
-    #[error(transparent)]
-    SynError(#[from] syn::Error),
-    #[error("Variant \"{ident}\" contains more than one associated value.")]
-    MultipleAssociatedValues { ident: syn::Ident },
-}
-
 impl<'a> TryFrom<&'a Variant> for NetlinkAttributeKind<'a> {
-    type Error = NetlinkAttributeKindFromVariantError;
+    type Error = syn::Error;
 
-    fn try_from(variant: &'a Variant) -> Result<Self, Self::Error> {
+    fn try_from(variant: &'a Variant) -> syn::Result<Self> {
         let ident = &variant.ident;
         let nla_type_attr = (variant.attrs)
             .iter()
             .find(|attr| is_nla_type_attr(attr))
-            .map(|attr| attr.parse_args::<TokenStream>())
+            .map(|attr| attr.parse_args::<NlaTypeArg>())
             .transpose()?;
 
         let ty = match nla_type_attr {
@@ -49,32 +58,118 @@ impl<'a> TryFrom<&'a Variant> for NetlinkAttributeKind<'a> {
             Some(ty) => ty,
         };
 
-        if is_underscore(ty.clone()) {
-            return Ok(Self::Wildcard(NetlinkAttributeKindWildcard { ident }));
+        let policy = parse_nla_policy(&variant.attrs)?;
+        if !policy.is_empty()
+            && (matches!(ty, NlaTypeArg::Wildcard(_)) || variant.fields.is_empty())
+        {
+            let policy_attr = (variant.attrs.iter()).find(|attr| is_nla_policy_attr(attr));
+            return Err(syn::Error::new_spanned(
+                policy_attr,
+                format!(
+                    "Variant \"{}\" has an #[nla_policy(..)] but no associated value to validate.",
+                    ident
+                ),
+            ));
+        }
+
+        let codec = parse_nldl_codec(&variant.attrs)?;
+        if !codec.is_empty() && (matches!(ty, NlaTypeArg::Wildcard(_)) || variant.fields.is_empty())
+        {
+            return Err(syn::Error::new_spanned(
+                ident,
+                format!(
+                    "Variant \"{}\" has a custom serializer or deserializer but no associated value.",
+                    ident
+                ),
+            ));
+        }
+        if codec.serialize.is_some() && policy.max_strlen.is_some() {
+            let policy_attr = (variant.attrs.iter()).find(|attr| is_nla_policy_attr(attr));
+            return Err(syn::Error::new_spanned(
+                policy_attr,
+                format!(
+                    "Variant \"{}\" has a custom serializer, so max_strlen can't be checked before sending.",
+                    ident
+                ),
+            ));
         }
 
+        let ty = match ty {
+            NlaTypeArg::Wildcard(ty) => {
+                return Ok(Self::Wildcard(NetlinkAttributeKindWildcard {
+                    ident,
+                    ty,
+                    payload_ty: variant.fields.iter().next().map(|field| &field.ty),
+                }))
+            }
+            NlaTypeArg::Range(ty) => {
+                let mut fields = variant.fields.iter();
+                return match (fields.next(), fields.next(), fields.next()) {
+                    (Some(_), Some(payload), None) => Ok(Self::Range(NetlinkAttributeKindRange {
+                        ident,
+                        ty,
+                        payload_ty: &payload.ty,
+                        policy,
+                        codec,
+                    })),
+                    _ => Err(syn::Error::new_spanned(
+                        ident,
+                        format!(
+                            "Variant \"{}\" has a type id range and must hold the type id and the payload, e.g. {}(u16, T).",
+                            ident, ident
+                        ),
+                    )),
+                };
+            }
+            NlaTypeArg::Ids(ty) => ty,
+        };
+
         match variant.fields.len() {
             0 => Ok(Self::NoPayload(NetlinkAttributeKindNoPayload { ident, ty })),
-            1 => Ok(Self::Simple(NetlinkAttributeKindSimple { ident, ty })),
-            _ => Err(Self::Error::MultipleAssociatedValues {
-                ident: ident.clone(),
-            }),
+            1 => Ok(Self::Simple(NetlinkAttributeKindSimple {
+                ident,
+                ty,
+                payload_ty: &variant.fields.iter().next().unwrap().ty,
+                policy,
+                codec,
+            })),
+            _ => Err(syn::Error::new_spanned(
+                &variant.fields,
+                format!(
+                    "Variant \"{}\" contains more than one associated value.",
+                    ident
+                ),
+            )),
         }
     }
 }
 
 pub struct NetlinkAttributeKindNoPayload<'a> {
     pub ident: &'a syn::Ident,
-    pub ty: TokenStream,
+    pub ty: NlaTypeIds,
 }
 
 pub struct NetlinkAttributeKindSimple<'a> {
     pub ident: &'a syn::Ident,
-    pub ty: TokenStream,
+    pub ty: NlaTypeIds,
+    pub payload_ty: &'a syn::Type,
+    pub policy: NlaPolicy,
+    pub codec: NldlCodec,
+}
+
+pub struct NetlinkAttributeKindRange<'a> {
+    pub ident: &'a syn::Ident,
+    pub ty: NlaTypeRange,
+    pub payload_ty: &'a syn::Type,
+    pub policy: NlaPolicy,
+    pub codec: NldlCodec,
 }
 
 pub struct NetlinkAttributeKindWildcard<'a> {
     pub ident: &'a syn::Ident,
+    /// The `_` argument, kept for error spans.
+    pub ty: TokenStream,
+    pub payload_ty: Option<&'a syn::Type>,
 }
 
 pub struct NetlinkAttributeKindUnmarked<'a> {
@@ -84,38 +179,153 @@ pub struct NetlinkAttributeKindUnmarked<'a> {
 pub struct PartitionedAttributeKinds<'a> {
     pub no_payload: Vec<NetlinkAttributeKindNoPayload<'a>>,
     pub simple: Vec<NetlinkAttributeKindSimple<'a>>,
+    pub range: Vec<NetlinkAttributeKindRange<'a>>,
     pub wildcard: Vec<NetlinkAttributeKindWildcard<'a>>,
     pub unmarked: Vec<NetlinkAttributeKindUnmarked<'a>>,
 }
 
 impl<'a> PartitionedAttributeKinds<'a> {
-    pub fn from(data_enum: &'a DataEnum) -> Result<Self, NetlinkAttributeKindFromVariantError> {
+    /// Partitions the variants of an attribute enum. Variants that fail to
+    /// parse are left out and their errors recorded.
+    pub fn from(data_enum: &'a DataEnum, errors: &mut Errors) -> Self {
         let mut partitioned_variants = Self {
             no_payload: vec![],
             simple: vec![],
+            range: vec![],
             wildcard: vec![],
             unmarked: vec![],
         };
+        let mut literal_type_ids = LiteralTypeIds::default();
 
         for variant in &data_enum.variants {
-            let variant: NetlinkAttributeKind = variant.try_into()?;
+            let variant = match errors.ok(NetlinkAttributeKind::try_from(variant)) {
+                Some(variant) => variant,
+                None => continue,
+            };
             match variant {
-                NetlinkAttributeKind::NoPayload(val) => partitioned_variants.no_payload.push(val),
-                NetlinkAttributeKind::Simple(val) => partitioned_variants.simple.push(val),
+                NetlinkAttributeKind::NoPayload(val) => {
+                    errors.ok(literal_type_ids.insert_ids(&val.ty, val.ident));
+                    partitioned_variants.no_payload.push(val)
+                }
+                NetlinkAttributeKind::Simple(val) => {
+                    errors.ok(literal_type_ids.insert_ids(&val.ty, val.ident));
+                    partitioned_variants.simple.push(val)
+                }
+                NetlinkAttributeKind::Range(val) => {
+                    errors.ok(literal_type_ids.insert_range(&val.ty, val.ident));
+                    partitioned_variants.range.push(val)
+                }
                 NetlinkAttributeKind::Wildcard(val) => partitioned_variants.wildcard.push(val),
                 NetlinkAttributeKind::Unmarked(val) => partitioned_variants.unmarked.push(val),
             }
         }
 
-        Ok(partitioned_variants)
+        partitioned_variants
+    }
+}
+
+/// Catches duplicate, overlapping and overflowing type IDs written as integer
+/// literals while parsing, so the error points at the exact argument. Other
+/// expressions are checked by the compiler through the generated
+/// `InternalTypeIdsEnum` and range assertions.
+#[derive(Default)]
+struct LiteralTypeIds<'a> {
+    ids: BTreeMap<u64, &'a syn::Ident>,
+    ranges: Vec<(RangeInclusive<u64>, &'a syn::Ident)>,
+}
+
+impl<'a> LiteralTypeIds<'a> {
+    fn insert_ids(&mut self, ids: &NlaTypeIds, ident: &'a syn::Ident) -> syn::Result<()> {
+        let mut errors = Errors::default();
+        for ty in &ids.0 {
+            errors.ok(self.insert_id(ty, ident));
+        }
+        errors.finish()
     }
+
+    fn insert_id(&mut self, ty: &TokenStream, ident: &'a syn::Ident) -> syn::Result<()> {
+        let (lit, value) = match parse_literal(ty)? {
+            Some(literal) => literal,
+            None => return Ok(()),
+        };
+
+        if let Some(existing) = self.used_by(&(value..=value)) {
+            return Err(syn::Error::new_spanned(
+                lit,
+                format!(
+                    "Type id {} is already used by variant \"{}\".",
+                    value, existing
+                ),
+            ));
+        }
+        self.ids.insert(value, ident);
+
+        Ok(())
+    }
+
+    fn insert_range(&mut self, range: &NlaTypeRange, ident: &'a syn::Ident) -> syn::Result<()> {
+        let (start, end) = match (parse_literal(&range.start)?, parse_literal(&range.end)?) {
+            (Some((_, start)), Some((_, end))) => (start, end),
+            _ => return Ok(()),
+        };
+        let range_tokens = range.to_token_stream();
+
+        if start > end {
+            return Err(syn::Error::new_spanned(
+                range_tokens,
+                format!("Type id range {}..={} is empty.", start, end),
+            ));
+        }
+        if let Some(existing) = self.used_by(&(start..=end)) {
+            return Err(syn::Error::new_spanned(
+                range_tokens,
+                format!(
+                    "Type id range {}..={} overlaps variant \"{}\".",
+                    start, end, existing
+                ),
+            ));
+        }
+        self.ranges.push((start..=end, ident));
+
+        Ok(())
+    }
+
+    fn used_by(&self, range: &RangeInclusive<u64>) -> Option<&'a syn::Ident> {
+        let id = self
+            .ids
+            .range(range.clone())
+            .map(|(_, ident)| *ident)
+            .next();
+        id.or_else(|| {
+            (self.ranges.iter())
+                .find(|(used, _)| used.start() <= range.end() && range.start() <= used.end())
+                .map(|(_, ident)| *ident)
+        })
+    }
+}
+
+fn parse_literal(ty: &TokenStream) -> syn::Result<Option<(LitInt, u64)>> {
+    let lit = match syn::parse2::<LitInt>(ty.clone()) {
+        Ok(lit) => lit,
+        Err(_) => return Ok(None),
+    };
+    let value: u64 = lit.base10_parse()?;
+
+    if value > u64::from(u16::MAX) {
+        return Err(syn::Error::new_spanned(
+            lit,
+            format!("Type id {} doesn't fit in a u16.", value),
+        ));
+    }
+
+    Ok(Some((lit, value)))
 }
 
-fn is_nla_type_attr(attribute: &Attribute) -> bool {
+pub fn is_nla_type_attr(attribute: &Attribute) -> bool {
     attribute.path.is_ident(NLA_TYPE_ATTR)
 }
 
-fn is_underscore(tokens: TokenStream) -> bool {
+pub fn is_underscore(tokens: TokenStream) -> bool {
     let parsed = syn::parse2::<syn::token::Underscore>(tokens);
     parsed.map(|_| true).unwrap_or(false)
 }
@@ -0,0 +1,118 @@
+use super::nla_type::is_underscore;
+use proc_macro2::Spacing;
+use proc_macro2::TokenStream;
+use proc_macro2::TokenTree;
+use quote::quote;
+use quote::ToTokens;
+use syn::parse::Parse;
+use syn::parse::ParseStream;
+
+/// The argument of an `#[nla_type(..)]` attribute.
+pub enum NlaTypeArg {
+    /// `#[nla_type(_)]`. Kept for error spans.
+    Wildcard(TokenStream),
+    /// `#[nla_type(A)]`, or `#[nla_type(A | B)]` for aliases.
+    Ids(NlaTypeIds),
+    /// `#[nla_type(A..=B)]`
+    Range(NlaTypeRange),
+}
+
+/// One or more type id expressions. Any of them is accepted when
+/// deserializing and the first is used when serializing.
+///
+/// Alternatives are split on `|` at the top level, so a bitwise or must be
+/// wrapped in parentheses.
+pub struct NlaTypeIds(pub Vec<TokenStream>);
+
+impl NlaTypeIds {
+    pub fn first(&self) -> &TokenStream {
+        &self.0[0]
+    }
+}
+
+/// An inclusive range of type ids. Both ends are expressions.
+pub struct NlaTypeRange {
+    pub start: TokenStream,
+    pub end: TokenStream,
+}
+
+impl ToTokens for NlaTypeRange {
+    fn to_tokens(&self, tokens: &mut TokenStream) {
+        let (start, end) = (&self.start, &self.end);
+        tokens.extend(quote! { #start..=#end });
+    }
+}
+
+impl Parse for NlaTypeArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let tokens: TokenStream = input.parse()?;
+        if is_underscore(tokens.clone()) {
+            return Ok(Self::Wildcard(tokens));
+        }
+
+        let alternatives = split_alternatives(tokens.clone());
+        if alternatives
+            .iter()
+            .any(|alternative| alternative.is_empty())
+        {
+            return Err(syn::Error::new_spanned(
+                tokens,
+                "Expected a type id expression on each side of |.",
+            ));
+        }
+
+        let mut ranges = alternatives.iter().filter_map(split_range);
+        match ranges.next() {
+            None => Ok(Self::Ids(NlaTypeIds(alternatives))),
+            Some(_) if alternatives.len() > 1 => Err(syn::Error::new_spanned(
+                tokens,
+                "A type id range can't be combined with other type ids.",
+            )),
+            Some(range) => range.map(Self::Range),
+        }
+    }
+}
+
+fn split_alternatives(tokens: TokenStream) -> Vec<TokenStream> {
+    let mut alternatives = vec![TokenStream::new()];
+    for token in tokens {
+        match &token {
+            TokenTree::Punct(punct) if punct.as_char() == '|' => {
+                alternatives.push(TokenStream::new())
+            }
+            _ => alternatives.last_mut().unwrap().extend([token]),
+        }
+    }
+    alternatives
+}
+
+/// Splits `A..=B` into its ends. Returns `None` for expressions without a
+/// top-level `..`.
+fn split_range(tokens: &TokenStream) -> Option<syn::Result<NlaTypeRange>> {
+    let tokens: Vec<TokenTree> = tokens.clone().into_iter().collect();
+    let dots = tokens.windows(2).position(|window| {
+        matches!(window, [TokenTree::Punct(first), TokenTree::Punct(second)]
+            if first.as_char() == '.' && first.spacing() == Spacing::Joint && second.as_char() == '.')
+    })?;
+
+    let start: TokenStream = tokens[..dots].iter().cloned().collect();
+    let inclusive =
+        matches!(tokens.get(dots + 2), Some(TokenTree::Punct(punct)) if punct.as_char() == '=');
+    let end: TokenStream = tokens.iter().skip(dots + 3).cloned().collect();
+
+    let range_tokens: TokenStream = tokens.iter().cloned().collect();
+    if !inclusive {
+        return Some(Err(syn::Error::new_spanned(
+            range_tokens,
+            "Only inclusive type id ranges (A..=B) are supported.",
+        )));
+    }
+    if start.is_empty() || end.is_empty() {
+        return Some(Err(syn::Error::new_spanned(
+            range_tokens,
+            "Type id ranges must have a start and an end.",
+        )));
+    }
+
+    Some(Ok(NlaTypeRange { start, end }))
+}
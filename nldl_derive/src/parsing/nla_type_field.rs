@@ -0,0 +1,176 @@
+use super::errors::Errors;
+use super::nla_policy::parse_nla_policy;
+use super::nla_policy::NlaPolicy;
+use super::nla_type::is_nla_type_attr;
+use super::nla_type_arg::NlaTypeArg;
+use super::nla_type_arg::NlaTypeIds;
+use super::nldl_codec::parse_nldl_codec;
+use super::nldl_codec::NldlCodec;
+use super::payload_kind::is_u8;
+use super::payload_kind::wrapped_type;
+use proc_macro2::TokenStream;
+use proc_macro2::TokenTree;
+use syn::parse::Parse;
+use syn::parse::ParseStream;
+use syn::DataStruct;
+use syn::Field;
+use syn::Fields;
+use syn::Type;
+
+/// Describes how many times the attribute backing a struct field may appear
+/// from the perspective of the `#[derive(NetlinkDeserializable)]` macro.
+pub enum NetlinkAttributeFieldKind<'a> {
+    /// Plain fields. The attribute must appear at least once.
+    Required,
+    /// Fields wrapped in `Option<T>`. The attribute may be omitted.
+    Optional { payload_ty: &'a Type },
+    /// Fields wrapped in `Vec<T>` (except `Vec<u8>`, which is a payload on its
+    /// own). Every appearance of the attribute is collected.
+    Repeated { payload_ty: &'a Type },
+    /// Fields annotated with `#[nla_type(.., default)]`. The attribute may be
+    /// omitted, leaving the field at `Default::default()`.
+    Defaulted,
+}
+
+pub struct NetlinkAttributeField<'a> {
+    pub ident: &'a syn::Ident,
+    pub ty: NlaTypeIds,
+    pub field_ty: &'a Type,
+    pub kind: NetlinkAttributeFieldKind<'a>,
+    pub policy: NlaPolicy,
+    pub codec: NldlCodec,
+}
+
+impl<'a> NetlinkAttributeField<'a> {
+    /// The type each attribute payload is deserialized into.
+    pub fn payload_ty(&self) -> &'a Type {
+        match self.kind {
+            NetlinkAttributeFieldKind::Required | NetlinkAttributeFieldKind::Defaulted => {
+                self.field_ty
+            }
+            NetlinkAttributeFieldKind::Optional { payload_ty } => payload_ty,
+            NetlinkAttributeFieldKind::Repeated { payload_ty } => payload_ty,
+        }
+    }
+}
+
+/// Parses every field of an attribute set struct, reporting all fields that
+/// fail to parse at once.
+pub fn parse_fields(data_struct: &DataStruct) -> syn::Result<Vec<NetlinkAttributeField<'_>>> {
+    let fields = match &data_struct.fields {
+        Fields::Named(fields) => &fields.named,
+        Fields::Unit => return Ok(vec![]),
+        Fields::Unnamed(fields) => {
+            return Err(syn::Error::new_spanned(
+                fields,
+                "nldl_derive may only be used on structs with named fields.",
+            ))
+        }
+    };
+
+    let mut errors = Errors::default();
+    let parsed = fields
+        .iter()
+        .filter_map(|field| errors.ok(parse_field(field)))
+        .collect();
+    errors.finish()?;
+
+    Ok(parsed)
+}
+
+fn parse_field(field: &Field) -> syn::Result<NetlinkAttributeField<'_>> {
+    let ident = field.ident.as_ref().unwrap();
+    let ty = (field.attrs)
+        .iter()
+        .find(|attr| is_nla_type_attr(attr))
+        .map(|attr| attr.parse_args::<NlaTypeFieldArg>())
+        .transpose()?
+        .ok_or_else(|| {
+            syn::Error::new_spanned(
+                ident,
+                format!(
+                    "Please annotate all struct fields with #[nla_type(..)]. Saw \"{}\" unannotated.",
+                    ident
+                ),
+            )
+        })?;
+
+    let NlaTypeFieldArg { ty, default } = ty;
+    let ty = match ty {
+        NlaTypeArg::Ids(ty) => ty,
+        NlaTypeArg::Wildcard(ty) => {
+            return Err(syn::Error::new_spanned(
+                ty,
+                format!(
+                    "#[nla_type(_)] is only supported on enum variants. Saw it on field \"{}\".",
+                    ident
+                ),
+            ))
+        }
+        NlaTypeArg::Range(ty) => {
+            return Err(syn::Error::new_spanned(
+                ty,
+                format!(
+                    "Type id ranges are only supported on enum variants. Saw one on field \"{}\".",
+                    ident
+                ),
+            ))
+        }
+    };
+
+    let codec = parse_nldl_codec(&field.attrs)?;
+    if codec.serialize.is_some() && codec.deserialize.is_none() {
+        return Err(syn::Error::new_spanned(
+            ident,
+            format!(
+                "Struct fields are only deserialized, so serialize_with has no effect. Saw it on field \"{}\".",
+                ident
+            ),
+        ));
+    }
+
+    Ok(NetlinkAttributeField {
+        ident,
+        ty,
+        field_ty: &field.ty,
+        kind: if default {
+            NetlinkAttributeFieldKind::Defaulted
+        } else {
+            field_kind(&field.ty)
+        },
+        policy: parse_nla_policy(&field.attrs)?,
+        codec,
+    })
+}
+
+/// The argument of `#[nla_type(..)]` on a struct field, which may end with
+/// `, default`.
+struct NlaTypeFieldArg {
+    ty: NlaTypeArg,
+    default: bool,
+}
+
+impl Parse for NlaTypeFieldArg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let mut tokens: Vec<TokenTree> = input.parse::<TokenStream>()?.into_iter().collect();
+        let default = matches!(tokens.as_slice(),
+            [.., TokenTree::Punct(comma), TokenTree::Ident(ident)]
+                if comma.as_char() == ',' && ident == "default");
+        if default {
+            tokens.truncate(tokens.len() - 2);
+        }
+
+        let ty = syn::parse2(tokens.into_iter().collect())?;
+        Ok(Self { ty, default })
+    }
+}
+
+fn field_kind(field_ty: &Type) -> NetlinkAttributeFieldKind<'_> {
+    match wrapped_type(field_ty, &["Option", "Vec"]) {
+        Some(("Option", payload_ty)) => NetlinkAttributeFieldKind::Optional { payload_ty },
+        Some(("Vec", payload_ty)) if !is_u8(payload_ty) => {
+            NetlinkAttributeFieldKind::Repeated { payload_ty }
+        }
+        _ => NetlinkAttributeFieldKind::Required,
+    }
+}
@@ -0,0 +1,25 @@
+use syn::Attribute;
+use syn::Meta;
+use syn::NestedMeta;
+use syn::Path;
+
+/// Returns true if a bare flag such as `visitor` appears in any
+/// `#[nldl(..)]` attribute.
+pub fn has_nldl_flag(attrs: &[Attribute], flag: &str) -> bool {
+    find_nldl_flag(attrs, flag).is_some()
+}
+
+/// Like [has_nldl_flag], but returns the flag so errors can point at it.
+pub fn find_nldl_flag(attrs: &[Attribute], flag: &str) -> Option<Path> {
+    (attrs.iter())
+        .filter(|attr| attr.path.is_ident("nldl"))
+        .filter_map(|attr| match attr.parse_meta() {
+            Ok(Meta::List(list)) => Some(list.nested),
+            _ => None,
+        })
+        .flatten()
+        .find_map(|arg| match arg {
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident(flag) => Some(path),
+            _ => None,
+        })
+}
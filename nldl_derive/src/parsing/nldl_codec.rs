@@ -0,0 +1,135 @@
+use syn::Attribute;
+use syn::Ident;
+use syn::Lit;
+use syn::Meta;
+use syn::NestedMeta;
+use syn::Path;
+
+/// Custom functions given by `#[nldl(with = "..")]`,
+/// `#[nldl(serialize_with = "..", serialized_len_with = "..")]` or
+/// `#[nldl(deserialize_with = "..")]` on a variant or field. Payloads without them go through the payload type's
+/// `NetlinkPayloadRequest` and `NetlinkPayloadResponse` impls.
+#[derive(Default)]
+pub struct NldlCodec {
+    pub serialize: Option<SerializeWith>,
+    /// Called with the raw payload. Returns `Result<T, E>` where `E` converts
+    /// into a boxed error.
+    pub deserialize: Option<Path>,
+}
+
+pub struct SerializeWith {
+    /// Called with the value and a `BufMut` to write the payload to.
+    pub serialize: Path,
+    /// Called with the value to get the payload length.
+    pub serialized_len: Path,
+}
+
+impl NldlCodec {
+    pub fn is_empty(&self) -> bool {
+        self.serialize.is_none() && self.deserialize.is_none()
+    }
+}
+
+/// Parses the codec options among the `#[nldl(..)]` attributes on a variant
+/// or field.
+pub fn parse_nldl_codec(attrs: &[Attribute]) -> syn::Result<NldlCodec> {
+    let mut codec = NldlCodec::default();
+    // serialize_with and serialized_len_with may be given in either order.
+    let mut serialize_with = None;
+    let mut serialized_len_with = None;
+
+    for attr in attrs.iter().filter(|attr| attr.path.is_ident("nldl")) {
+        let nested = match attr.parse_meta()? {
+            Meta::List(list) => list.nested,
+            meta => {
+                return Err(syn::Error::new_spanned(
+                    meta,
+                    "Expected #[nldl(with = \"..\")].",
+                ))
+            }
+        };
+
+        for arg in nested {
+            let (key, lit) = match arg {
+                NestedMeta::Meta(Meta::NameValue(name_value)) => (name_value.path, name_value.lit),
+                arg => return Err(unknown_option(arg)),
+            };
+            let path = match &lit {
+                Lit::Str(lit) => lit.parse::<Path>()?,
+                _ => {
+                    return Err(syn::Error::new_spanned(
+                        lit,
+                        "Expected a path in a string literal.",
+                    ))
+                }
+            };
+
+            let conflict = if key.is_ident("with") {
+                let serialize = SerializeWith {
+                    serialize: with_segment(&path, "serialize", &lit),
+                    serialized_len: with_segment(&path, "serialized_len", &lit),
+                };
+                let deserialize = with_segment(&path, "deserialize", &lit);
+                codec.serialize.replace(serialize).is_some()
+                    | codec.deserialize.replace(deserialize).is_some()
+            } else if key.is_ident("serialize_with") {
+                serialize_with.replace((key.clone(), path)).is_some()
+            } else if key.is_ident("serialized_len_with") {
+                serialized_len_with.replace((key.clone(), path)).is_some()
+            } else if key.is_ident("deserialize_with") {
+                codec.deserialize.replace(path).is_some()
+            } else {
+                return Err(unknown_option(key));
+            };
+
+            if conflict {
+                return Err(syn::Error::new_spanned(
+                    key,
+                    "A custom serializer or deserializer is specified more than once.",
+                ));
+            }
+        }
+    }
+
+    if let Some((key, serialize)) = serialize_with {
+        if codec.serialize.is_some() {
+            return Err(syn::Error::new_spanned(
+                key,
+                "A custom serializer or deserializer is specified more than once.",
+            ));
+        }
+        let (_, serialized_len) = serialized_len_with.take().ok_or_else(|| {
+            syn::Error::new_spanned(
+                &key,
+                "serialize_with requires serialized_len_with, a function returning the payload length.",
+            )
+        })?;
+        codec.serialize = Some(SerializeWith {
+            serialize,
+            serialized_len,
+        });
+    }
+    if let Some((key, _)) = serialized_len_with {
+        return Err(syn::Error::new_spanned(
+            key,
+            "serialized_len_with is only used together with serialize_with.",
+        ));
+    }
+
+    Ok(codec)
+}
+
+/// `module::function`, spanned at the string literal so unresolved functions
+/// are reported there.
+fn with_segment(module: &Path, function: &str, lit: &Lit) -> Path {
+    let mut path = module.clone();
+    path.segments.push(Ident::new(function, lit.span()).into());
+    path
+}
+
+fn unknown_option(tokens: impl quote::ToTokens) -> syn::Error {
+    syn::Error::new_spanned(
+        tokens,
+        "Unknown nldl option. Expected one of with, serialize_with, serialized_len_with or deserialize_with.",
+    )
+}
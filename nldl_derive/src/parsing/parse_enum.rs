@@ -0,0 +1,121 @@
+use super::errors::Errors;
+use super::nla_policy::NlaPolicy;
+use super::nla_type::PartitionedAttributeKinds;
+use super::nla_type_arg::NlaTypeIds;
+use super::nla_type_arg::NlaTypeRange;
+use super::nldl_codec::NldlCodec;
+use proc_macro2::Ident;
+use syn::Data;
+use syn::DeriveInput;
+
+pub struct ParsedEnum<'a> {
+    pub name: &'a Ident,
+    pub no_payload_idents: Vec<&'a Ident>,
+    pub no_payload_nla_types: Vec<NlaTypeIds>,
+    pub simple_idents: Vec<&'a Ident>,
+    pub simple_nla_types: Vec<NlaTypeIds>,
+    pub simple_payload_tys: Vec<&'a syn::Type>,
+    pub simple_policies: Vec<NlaPolicy>,
+    pub simple_codecs: Vec<NldlCodec>,
+    pub range_idents: Vec<&'a Ident>,
+    pub range_nla_types: Vec<NlaTypeRange>,
+    pub range_payload_tys: Vec<&'a syn::Type>,
+    pub range_policies: Vec<NlaPolicy>,
+    pub range_codecs: Vec<NldlCodec>,
+    pub wildcard_ident: Option<&'a Ident>,
+    pub wildcard_payload_ty: Option<&'a syn::Type>,
+}
+
+pub fn parse_enum(ast: &DeriveInput) -> syn::Result<ParsedEnum<'_>> {
+    let data_enum = match &ast.data {
+        Data::Enum(data_enum) => data_enum,
+        _ => {
+            return Err(syn::Error::new_spanned(
+                &ast.ident,
+                "nldl_derive may only be used on enums.",
+            ))
+        }
+    };
+
+    let mut errors = Errors::default();
+    let partitioned_variants = PartitionedAttributeKinds::from(data_enum, &mut errors);
+    for unmarked_variant in &partitioned_variants.unmarked {
+        errors.push(syn::Error::new_spanned(
+            unmarked_variant.ident,
+            format!(
+                "Please annotate all enum variants with #[nla_type(..)]. Saw \"{}\" unannotated.",
+                unmarked_variant.ident
+            ),
+        ));
+    }
+    let wildcard_ident = partitioned_variants
+        .wildcard
+        .first()
+        .map(|variant| variant.ident);
+    let wildcard_payload_ty = partitioned_variants
+        .wildcard
+        .first()
+        .and_then(|variant| variant.payload_ty);
+    for extra_wildcard in partitioned_variants.wildcard.iter().skip(1) {
+        errors.push(syn::Error::new_spanned(
+            &extra_wildcard.ty,
+            format!(
+                "Only 1 variant may be marked with #[nla_type(_)]. \"{}\" is already marked.",
+                wildcard_ident.unwrap()
+            ),
+        ));
+    }
+    errors.finish()?;
+
+    let name = &ast.ident;
+    let (no_payload_idents, no_payload_nla_types) = partitioned_variants
+        .no_payload
+        .into_iter()
+        .fold((vec![], vec![]), |mut acc, attr| {
+            acc.0.push(attr.ident);
+            acc.1.push(attr.ty);
+            acc
+        });
+    let (simple_idents, simple_nla_types, simple_payload_tys, simple_policies, simple_codecs) =
+        partitioned_variants.simple.into_iter().fold(
+            (vec![], vec![], vec![], vec![], vec![]),
+            |mut acc, attr| {
+                acc.0.push(attr.ident);
+                acc.1.push(attr.ty);
+                acc.2.push(attr.payload_ty);
+                acc.3.push(attr.policy);
+                acc.4.push(attr.codec);
+                acc
+            },
+        );
+    let (range_idents, range_nla_types, range_payload_tys, range_policies, range_codecs) =
+        partitioned_variants.range.into_iter().fold(
+            (vec![], vec![], vec![], vec![], vec![]),
+            |mut acc, attr| {
+                acc.0.push(attr.ident);
+                acc.1.push(attr.ty);
+                acc.2.push(attr.payload_ty);
+                acc.3.push(attr.policy);
+                acc.4.push(attr.codec);
+                acc
+            },
+        );
+
+    Ok(ParsedEnum {
+        name,
+        no_payload_idents,
+        no_payload_nla_types,
+        simple_idents,
+        simple_nla_types,
+        simple_payload_tys,
+        simple_policies,
+        simple_codecs,
+        range_idents,
+        range_nla_types,
+        range_payload_tys,
+        range_policies,
+        range_codecs,
+        wildcard_ident,
+        wildcard_payload_ty,
+    })
+}
@@ -0,0 +1,53 @@
+use syn::GenericArgument;
+use syn::PathArguments;
+use syn::Type;
+
+/// How a payload type is described by `#[derive(Schema)]`. Detected from the
+/// type as written, since the derive can't look at trait impls.
+pub enum PayloadKind<'a> {
+    /// Decoded by its own type.
+    Value(&'a Type),
+    /// `Vec<T>` (except `Vec<u8>`) or `NestedRef<'a, T>`. Holds `T`.
+    Nested(&'a Type),
+    /// `NlaArray<T>`. Holds the kind of `T`.
+    Array(Box<PayloadKind<'a>>),
+}
+
+pub fn payload_kind(ty: &Type) -> PayloadKind<'_> {
+    match wrapped_type(ty, &["Vec", "NestedRef", "NlaArray"]) {
+        Some(("Vec", inner)) if !is_u8(inner) => PayloadKind::Nested(inner),
+        Some(("NestedRef", inner)) => PayloadKind::Nested(inner),
+        Some(("NlaArray", inner)) => PayloadKind::Array(Box::new(payload_kind(inner))),
+        _ => PayloadKind::Value(ty),
+    }
+}
+
+/// Returns the wrapper name and inner type for types shaped like
+/// `Option<T>` or `NestedRef<'a, T>`, where the wrapper is one of `wrappers`.
+/// Lifetime arguments are skipped.
+pub fn wrapped_type<'a>(
+    ty: &'a Type,
+    wrappers: &[&'static str],
+) -> Option<(&'static str, &'a Type)> {
+    let segment = match ty {
+        Type::Path(type_path) if type_path.qself.is_none() => type_path.path.segments.last()?,
+        _ => return None,
+    };
+
+    let wrapper = (wrappers.iter()).find(|wrapper| segment.ident == wrapper)?;
+
+    let args = match &segment.arguments {
+        PathArguments::AngleBracketed(args) => args,
+        _ => return None,
+    };
+
+    let mut types = (args.args.iter()).filter(|arg| !matches!(arg, GenericArgument::Lifetime(_)));
+    match (types.next(), types.next()) {
+        (Some(GenericArgument::Type(inner)), None) => Some((wrapper, inner)),
+        _ => None,
+    }
+}
+
+pub fn is_u8(ty: &Type) -> bool {
+    matches!(ty, Type::Path(type_path) if type_path.path.is_ident("u8"))
+}
@@ -0,0 +1,28 @@
+#[derive(Debug, PartialEq, nldl::attr::Deserialize)]
+struct Family {
+    #[nla_type(1)]
+    id: u16,
+    #[nla_type(2)]
+    name: Option<String>,
+    #[nla_type(3)]
+    operations: Option<Vec<FamilyOperation>>,
+    #[nla_type(4)]
+    aliases: Vec<String>,
+    #[nla_type(5)]
+    raw: Vec<u8>,
+    #[nla_type(6, default)]
+    groups: Vec<FamilyOperation>,
+    #[nla_type(7 | 8, default)]
+    max_attr: u32,
+}
+
+#[derive(Debug, PartialEq, nldl::attr::Deserialize)]
+struct FamilyOperation {
+    #[nla_type(1)]
+    id: u32,
+}
+
+#[derive(Debug, PartialEq, nldl::attr::Deserialize)]
+struct Empty {}
+
+fn main() {}
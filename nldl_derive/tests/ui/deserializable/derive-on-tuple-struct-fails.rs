@@ -1,4 +1,4 @@
 #[derive(Debug, PartialEq, nldl::attr::Deserialize)]
-struct TestStruct {}
+struct TestStruct(u32);
 
 fn main() {}
@@ -0,0 +1,15 @@
+use nldl::attr::UnknownAttribute;
+
+#[derive(Debug, PartialEq, nldl::attr::Deserialize)]
+enum ControllerAttributeOperation {
+    Unspec,
+    #[nla_type(1)]
+    Id(u32, u32),
+    Flags(u32),
+    #[nla_type(_)]
+    Unknown(UnknownAttribute),
+    #[nla_type(_)]
+    Unknown2(UnknownAttribute),
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+#[derive(Debug, PartialEq, nldl::attr::Deserialize)]
+enum LinkAttribute<'a, 'b> {
+    #[nla_type(1)]
+    Address(&'a [u8]),
+    #[nla_type(3)]
+    Name(&'b str),
+}
+
+fn main() {}
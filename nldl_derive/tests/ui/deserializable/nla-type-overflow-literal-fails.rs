@@ -0,0 +1,13 @@
+use nldl::attr::UnknownAttribute;
+
+#[derive(Debug, PartialEq, nldl::attr::Deserialize)]
+enum ControllerAttributeOperation {
+    #[nla_type(0)]
+    Unspec,
+    #[nla_type(65536)]
+    Id(u32),
+    #[nla_type(_)]
+    Unknown(UnknownAttribute),
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+#[derive(Debug, PartialEq, nldl::attr::Deserialize)]
+enum VendorAttribute {
+    #[nla_type(0x100..0x1ff)]
+    Exclusive(u16, Vec<u8>),
+    #[nla_type(0x200..=0x2ff)]
+    Vendor(Vec<u8>),
+}
+
+fn main() {}
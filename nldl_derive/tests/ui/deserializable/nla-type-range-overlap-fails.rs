@@ -0,0 +1,17 @@
+use nldl::attr::UnknownAttribute;
+
+pub const VENDOR_START: u16 = 0x100;
+pub const VENDOR_END: u16 = 0x1ff;
+pub const VENDOR_ID: u16 = 0x110;
+
+#[derive(Debug, PartialEq, nldl::attr::Deserialize)]
+enum VendorAttribute {
+    #[nla_type(1 | crate::VENDOR_ID)]
+    Id(u32),
+    #[nla_type(crate::VENDOR_START..=crate::VENDOR_END)]
+    Vendor(u16, Vec<u8>),
+    #[nla_type(_)]
+    Unknown(UnknownAttribute),
+}
+
+fn main() {}
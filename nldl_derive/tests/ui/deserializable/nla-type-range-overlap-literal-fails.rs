@@ -0,0 +1,15 @@
+use nldl::attr::UnknownAttribute;
+
+#[derive(Debug, PartialEq, nldl::attr::Deserialize)]
+enum VendorAttribute {
+    #[nla_type(1 | 0x110)]
+    Id(u32),
+    #[nla_type(0x100..=0x1ff)]
+    Vendor(u16, Vec<u8>),
+    #[nla_type(0x180..=0x2ff)]
+    Extended(u16, Vec<u8>),
+    #[nla_type(_)]
+    Unknown(UnknownAttribute),
+}
+
+fn main() {}
@@ -0,0 +1,8 @@
+#[derive(Debug, PartialEq, nldl::attr::Deserialize)]
+struct FamilyOperation {
+    #[nla_type(1)]
+    id: u32,
+    flags: u32,
+}
+
+fn main() {}
@@ -0,0 +1,34 @@
+const IF_OPER_DORMANT: u8 = 5;
+
+#[derive(Debug, PartialEq, nldl::Enum)]
+#[nldl(repr(u8))]
+enum OperState {
+    #[nla_value(0)]
+    Unknown,
+    #[nla_value(6)]
+    Up,
+    #[nla_value(crate::IF_OPER_DORMANT)]
+    Dormant,
+    #[nla_value(_)]
+    Other(u32),
+}
+
+// Every u32 fits, and there are no named values to collide with.
+#[derive(Debug, PartialEq, nldl::Enum)]
+#[nldl(repr(u32))]
+enum Mark {
+    #[nla_value(_)]
+    Other(u32),
+}
+
+#[derive(Debug, PartialEq, nldl::attr::Serialize, nldl::attr::Deserialize)]
+enum LinkAttribute {
+    #[nla_type(16)]
+    OperState(OperState),
+    #[nla_type(17)]
+    Mark(Mark),
+    #[nla_type(_)]
+    Unknown(nldl::attr::UnknownAttribute),
+}
+
+fn main() {}
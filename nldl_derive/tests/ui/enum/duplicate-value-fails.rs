@@ -0,0 +1,12 @@
+#[derive(Debug, PartialEq, nldl::Enum)]
+#[nldl(repr(u16))]
+enum IfType {
+    #[nla_value(1)]
+    AdHoc,
+    #[nla_value(1)]
+    Station,
+    #[nla_value(_)]
+    Other(u32),
+}
+
+fn main() {}
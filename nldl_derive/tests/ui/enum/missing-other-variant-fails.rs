@@ -0,0 +1,10 @@
+#[derive(nldl::Enum)]
+#[nldl(repr(u32))]
+enum IfType {
+    #[nla_value(1)]
+    AdHoc,
+    #[nla_value(2)]
+    Station,
+}
+
+fn main() {}
@@ -1,4 +1,4 @@
-#[derive(Debug, PartialEq, nldl::attr::Deserialize)]
+#[derive(Debug, PartialEq, nldl::attr::Serialize)]
 struct TestStruct {}
 
 fn main() {}
@@ -0,0 +1,13 @@
+use nldl::attr::UnknownAttribute;
+
+#[derive(nldl::attr::Serialize)]
+enum TestEnum {
+    #[nla_type(1)]
+    #[nla_policy(min_len = 4)]
+    #[nla_policy(max_len = 8)]
+    Name(Vec<u8>),
+    #[nla_type(_)]
+    Unknown(UnknownAttribute),
+}
+
+fn main() {}
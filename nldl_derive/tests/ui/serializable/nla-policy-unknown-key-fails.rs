@@ -0,0 +1,12 @@
+use nldl::attr::UnknownAttribute;
+
+#[derive(nldl::attr::Serialize)]
+enum TestEnum {
+    #[nla_type(1)]
+    #[nla_policy(max_size = 4)]
+    Id(u32),
+    #[nla_type(_)]
+    Unknown(UnknownAttribute),
+}
+
+fn main() {}
@@ -0,0 +1,20 @@
+#[derive(nldl::attr::Serialize)]
+enum LinkAttribute {
+    #[nla_type(1)]
+    #[nldl(with = "millis", serialize_with = "serialize_millis")]
+    Timeout(u32),
+    #[nla_type(2)]
+    #[nldl(encode_with = "encode")]
+    Name(String),
+    #[nla_type(3)]
+    #[nldl(with = "flag")]
+    Up,
+    #[nla_type(4)]
+    #[nldl(serialize_with = "serialize_tags")]
+    Tags(Vec<String>),
+    #[nla_type(5)]
+    #[nldl(with = "millis", serialized_len_with = "millis_len")]
+    Interval(u32),
+}
+
+fn main() {}
@@ -0,0 +1,27 @@
+use nldl::message::Be;
+
+#[derive(Debug, PartialEq, nldl::Struct)]
+#[repr(C)]
+struct CacheInfo {
+    prefered: u32,
+    valid: u32,
+    cstamp: u32,
+    tstamp: u32,
+}
+
+#[derive(Debug, PartialEq, nldl::Struct)]
+#[repr(C)]
+struct SockId {
+    sport: Be<u16>,
+    dport: Be<u16>,
+    src: [Be<u32>; 4],
+    dst: [Be<u32>; 4],
+    interface: u32,
+    cookie: [u32; 2],
+}
+
+#[derive(Debug, PartialEq, nldl::Struct)]
+#[repr(C)]
+struct Padded(u8, u64, CacheInfo, u16);
+
+fn main() {}
@@ -0,0 +1,7 @@
+#[derive(nldl::Struct)]
+struct CacheInfo {
+    prefered: u32,
+    valid: u32,
+}
+
+fn main() {}